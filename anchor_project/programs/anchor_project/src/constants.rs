@@ -20,6 +20,58 @@ pub const MILESTONE_STATUS_PENDING: u8 = 0;
 pub const MILESTONE_STATUS_COMPLETED_BY_SP: u8 = 1;
 pub const MILESTONE_STATUS_APPROVED_BY_PAYER: u8 = 2;
 pub const MILESTONE_STATUS_DISPUTED: u8 = 3;
+pub const MILESTONE_STATUS_RESOLVED: u8 = 4; // Settled by dispute resolution without full payment
+
+// Pause flags, combined as a bitmask in GlobalState
+pub const PAUSE_NEW_CONTRACTS: u8 = 1 << 0; // Also blocks dispute, counter and appeal bond deposits
+pub const PAUSE_ACCEPTANCES: u8 = 1 << 1;
+pub const PAUSE_PAYOUTS: u8 = 1 << 2;
+pub const PAUSE_DISPUTE_RESOLUTION: u8 = 1 << 3;
+pub const PAUSE_ALL: u8 =
+    PAUSE_NEW_CONTRACTS | PAUSE_ACCEPTANCES | PAUSE_PAYOUTS | PAUSE_DISPUTE_RESOLUTION;
+
+// Arbitrator registry limits
+pub const MAX_SPECIALTIES: usize = 5;
+pub const MAX_SPECIALTY_LEN: usize = 32;
+
+// Dispute panels
+pub const MAX_PANEL_SIZE: usize = 5;
+pub const PANEL_STATUS_VOTING: u8 = 0;
+pub const PANEL_STATUS_DECIDED: u8 = 1;
+pub const PANEL_STATUS_EXECUTED: u8 = 2;
+
+// Dispute record status constants
+pub const DISPUTE_STATUS_OPEN: u8 = 0;
+pub const DISPUTE_STATUS_RULED: u8 = 1; // Ruling waiting out the appeal window
+pub const DISPUTE_STATUS_APPEALED: u8 = 2;
+pub const DISPUTE_STATUS_RESOLVED: u8 = 3;
+pub const DISPUTE_STATUS_SETTLED: u8 = 4; // Settled by agreement of the parties
+pub const DISPUTE_STATUS_TIMED_OUT: u8 = 5;
+pub const DISPUTE_STATUS_WITHDRAWN: u8 = 6;
+
+// Dispute scope and resolutions
+pub const CONTRACT_DISPUTE_INDEX: u8 = u8::MAX; // milestone_index of a dispute over the whole contract
+pub const RESOLUTION_SPLIT: u8 = 0; // Split the disputed milestone, the contract carries on
+pub const RESOLUTION_TERMINATE: u8 = 1; // Split every unsettled milestone and close the contract
+pub const RESOLUTION_CONTINUE: u8 = 2; // Contract disputes only: dismiss and resume the contract
+pub const DISMISS_VOTE: u16 = u16::MAX; // Panel vote to dismiss a contract dispute (RESOLUTION_CONTINUE)
+
+// Dispute evidence
+pub const MAX_EVIDENCE_ENTRIES: usize = 20;
+pub const MAX_EVIDENCE_URI_LEN: usize = 200;
+pub const EVIDENCE_TYPE_DOCUMENT: u8 = 0;
+pub const EVIDENCE_TYPE_IMAGE: u8 = 1;
+pub const EVIDENCE_TYPE_VIDEO: u8 = 2;
+pub const EVIDENCE_TYPE_MESSAGE: u8 = 3;
+pub const EVIDENCE_TYPE_OTHER: u8 = 4;
+
+// Post-completion reviews
+pub const MIN_REVIEW_SCORE: u8 = 1;
+pub const MAX_REVIEW_SCORE: u8 = 5;
+pub const REVIEW_WINDOW: i64 = 30 * 24 * 60 * 60; // Seconds after completion a review can be left
+
+// Platform fee in basis points, unless the mint config overrides it
+pub const DEFAULT_FEE_PERCENTAGE: u16 = 5;
 
 // Constants
 pub const ANCHOR_DISCRIMINATOR: usize = 8;
@@ -30,7 +30,7 @@ pub enum TrustPayError {
     ContractExpired,
     #[msg("Contract is not in progress.")]
     ContractNotInProgress,
-    #[msg("Contract is not in disputed status.")]
+    #[msg("Contract has no active disputes.")]
     ContractNotDisputed,
     #[msg("Invalid milestone index.")]
     InvalidMilestoneIndex,
@@ -44,7 +44,7 @@ pub enum TrustPayError {
     MilestoneNotDisputed,
     #[msg("Invalid dispute reason.")]
     InvalidDisputeReason,
-    #[msg("Invalid resolution value.")]
+    #[msg("Recipient share cannot exceed 10000 basis points.")]
     InvalidResolution,
     #[msg("Deadlin is too far.")]
     DeadlineTooFar,
@@ -60,4 +60,128 @@ pub enum TrustPayError {
     InvalidContractType,
     #[msg("Payer Mismatch")]
     PayerMismatch,
+    #[msg("Withdrawal exceeds the fees available in the treasury.")]
+    InsufficientTreasuryFunds,
+    #[msg("Treasury does not match the contract's fee destination.")]
+    InvalidFeeDestination,
+    #[msg("Minimum fee cannot exceed the maximum fee.")]
+    InvalidFeeCaps,
+    #[msg("Fees charged exceed the fees deposited.")]
+    FeeOvercharged,
+    #[msg("Contract funds do not reconcile.")]
+    FundsNotConserved,
+    #[msg("Basis points cannot exceed 10000.")]
+    InvalidBasisPoints,
+    #[msg("Only the counterparty can match the dispute bond.")]
+    UnauthorizedBondMatch,
+    #[msg("There is no dispute bond to match.")]
+    NoBondToMatch,
+    #[msg("The dispute bond has already been matched.")]
+    BondAlreadyMatched,
+    #[msg("Only the program upgrade authority or the resolver authority can initialize.")]
+    UnauthorizedInitializer,
+    #[msg("Signer is not the pending authority.")]
+    NotPendingAuthority,
+    #[msg("This operation is paused.")]
+    OperationPaused,
+    #[msg("Invalid pause flags or auto-unpause time.")]
+    InvalidPauseConfig,
+    #[msg("Arbitrator is not active.")]
+    ArbitratorInactive,
+    #[msg("Too many specialties or a specialty is empty or too long.")]
+    InvalidSpecialties,
+    #[msg("The arbitrator cannot be the payer or the recipient.")]
+    InvalidArbitrator,
+    #[msg("The contract names its own arbitrator.")]
+    ContractArbitratorSet,
+    #[msg("Invalid panel size, quorum or voting period.")]
+    InvalidPanelConfig,
+    #[msg("This dispute must be decided by an arbitrator panel.")]
+    PanelRequired,
+    #[msg("This dispute does not require an arbitrator panel.")]
+    PanelNotRequired,
+    #[msg("Panel members must be distinct, active, registered arbitrators outside the contract.")]
+    InvalidPanelMembers,
+    #[msg("Signer is not a member of the panel.")]
+    NotPanelMember,
+    #[msg("Panel member has already voted.")]
+    AlreadyVoted,
+    #[msg("The panel is not accepting votes.")]
+    PanelVotingClosed,
+    #[msg("The panel has not reached a decision.")]
+    PanelNotDecided,
+    #[msg("The panel is not deadlocked.")]
+    PanelNotDeadlocked,
+    #[msg("Invalid appeal window or appeal bond.")]
+    InvalidAppealConfig,
+    #[msg("A ruling on this milestone is already waiting out its appeal window.")]
+    RulingPending,
+    #[msg("There is no ruling to appeal or finalize.")]
+    NoPendingRuling,
+    #[msg("The appeal window has closed.")]
+    AppealWindowClosed,
+    #[msg("The appeal window is still open.")]
+    AppealWindowOpen,
+    #[msg("Only the losing party can appeal the ruling.")]
+    UnauthorizedAppeal,
+    #[msg("There is no higher tier to appeal to.")]
+    AppealUnavailable,
+    #[msg("Only the parties and the dispute's arbitrators can submit evidence.")]
+    UnauthorizedEvidenceSubmitter,
+    #[msg("Evidence URI must be 1-200 characters with a known evidence type.")]
+    InvalidEvidence,
+    #[msg("The evidence log is full.")]
+    EvidenceLogFull,
+    #[msg("Dispute response must be 10-200 characters.")]
+    InvalidDisputeResponse,
+    #[msg("Only the non-disputing party can respond to the dispute.")]
+    UnauthorizedResponder,
+    #[msg("The dispute already has a response.")]
+    AlreadyResponded,
+    #[msg("Only the parties can settle, and not on their own offer.")]
+    UnauthorizedSettlement,
+    #[msg("There is no settlement offer to accept.")]
+    NoSettlementOffer,
+    #[msg("The settlement offer has changed.")]
+    SettlementOfferChanged,
+    #[msg("Invalid maximum resolution time or default outcome.")]
+    InvalidTimeoutConfig,
+    #[msg("Dispute timeouts are disabled.")]
+    DisputeTimeoutDisabled,
+    #[msg("The dispute has not exceeded the maximum resolution time.")]
+    DisputeNotStale,
+    #[msg("The dispute record does not belong to this milestone's dispute.")]
+    DisputeMismatch,
+    #[msg("Only the disputer can withdraw the dispute.")]
+    UnauthorizedWithdrawal,
+    #[msg("The dispute has been ruled on, appealed or sent to a panel and can no longer be withdrawn.")]
+    DisputeNotWithdrawable,
+    #[msg("Other disputes on this contract must be closed first.")]
+    MilestoneDisputesOpen,
+    #[msg("Reviews open once the contract has completed.")]
+    ContractNotCompleted,
+    #[msg("Review score must be between 1 and 5.")]
+    InvalidReviewScore,
+    #[msg("Only the payer or recipient of the contract can review it.")]
+    UnauthorizedReviewer,
+    #[msg("This party has already reviewed the contract.")]
+    AlreadyReviewed,
+    #[msg("The reputation profile does not belong to the reviewed party.")]
+    ReviewMismatch,
+    #[msg("The mint uses a Token-2022 extension the escrow does not support.")]
+    UnsupportedMintExtension,
+    #[msg("Invalid contract size limits or fee override.")]
+    InvalidMintConfig,
+    #[msg("Contracts are not enabled for this mint.")]
+    MintNotEnabled,
+    #[msg("Contract amount is outside the mint's allowed range.")]
+    ContractAmountOutOfRange,
+    #[msg("Arbitrator registry accounts do not match the arbitrators who decided the dispute.")]
+    ArbitratorMismatch,
+    #[msg("Payer and recipient must be different wallets.")]
+    SelfContract,
+    #[msg("The review window for this contract has closed.")]
+    ReviewWindowClosed,
+    #[msg("The review record stays open until both parties review or the review window ends.")]
+    ReviewWindowOpen,
 }
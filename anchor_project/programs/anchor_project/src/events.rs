@@ -12,6 +12,7 @@ pub struct ContractCreatedEvent {
     pub deadline: i64,
     pub fee_percentage: u16,
     pub fee_destination: Pubkey,
+    pub arbitrator: Option<Pubkey>,
 }
 
 #[event]
@@ -65,6 +66,8 @@ pub struct ContractCompletedEvent {
     pub payer: Pubkey,
     pub recipient: Pubkey,
     pub total_amount: u64,
+    pub fee_charged: u64,
+    pub fee_refunded: u64,
     pub completed_at: i64,
 }
 
@@ -88,21 +91,278 @@ pub struct DisputeCreatedEvent {
     pub amount: u64,
     pub disputer: Pubkey,
     pub reason: String,
-    pub dispute_id: String,
+    pub dispute_id: u64, // Seed of the Dispute PDA
+    pub bond_amount: u64,
     pub disputed_at: i64,
 }
 
 #[event]
 pub struct DisputeResolvedEvent {
     pub trust_pay: Pubkey,
+    pub dispute_id: u64,
     pub payer: Pubkey,
     pub recipient: Pubkey,
     pub milestone_index: u8,
     pub description: String,
     pub amount: u64,
+    pub fee_amount: u64, // Charged on the recipient's portion
+    pub resolver: Pubkey,
+    pub recipient_share_bps: u16,
+    pub amount_to_payer: u64,
+    pub amount_to_recipient: u64,
+    pub resolution_reason: String,
+    pub bond_to_payer: u64,
+    pub bond_to_recipient: u64,
+    pub arbitration_fee: u64,
+    pub appeal_bond_to_payer: u64,
+    pub appeal_bond_to_recipient: u64,
+    pub resolved_at: i64,
+}
+
+#[event]
+pub struct FeeCollectedEvent {
+    pub treasury: Pubkey,
+    pub mint: Pubkey,
+    pub trust_pay: Pubkey,
+    pub amount: u64,
+    pub total_fees_collected: u64,
+    pub collected_at: i64,
+}
+
+#[event]
+pub struct FeesWithdrawnEvent {
+    pub treasury: Pubkey,
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub total_fees_withdrawn: u64,
+    pub available_fees: u64,
+    pub withdrawn_at: i64,
+}
+
+#[event]
+pub struct FeeCapsUpdatedEvent {
+    pub mint_config: Pubkey,
+    pub mint: Pubkey,
+    pub min_fee: u64,
+    pub max_fee: u64,
+    pub updated_at: i64,
+}
+
+#[event]
+pub struct DisputeBondMatchedEvent {
+    pub trust_pay: Pubkey,
+    pub milestone_index: u8,
+    pub counterparty: Pubkey,
+    pub bond_amount: u64,
+    pub matched_at: i64,
+}
+
+#[event]
+pub struct AuthorityProposedEvent {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub proposed_at: i64,
+}
+
+#[event]
+pub struct AuthorityTransferredEvent {
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub transferred_at: i64,
+}
+
+#[event]
+pub struct PauseUpdatedEvent {
+    pub authority: Pubkey,
+    pub pause_flags: u8,
+    pub paused_until: i64,
+    pub updated_at: i64,
+}
+
+#[event]
+pub struct ArbitratorUpdatedEvent {
+    pub arbitrator: Pubkey,
+    pub active: bool,
+    pub specialties: Vec<String>,
+    pub updated_at: i64,
+}
+
+#[event]
+pub struct DisputeAssignedEvent {
+    pub trust_pay: Pubkey,
+    pub milestone_index: u8,
+    pub arbitrator: Pubkey,
+    pub assigned_at: i64,
+}
+
+#[event]
+pub struct PanelOpenedEvent {
+    pub trust_pay: Pubkey,
+    pub milestone_index: u8,
+    pub members: Vec<Pubkey>,
+    pub quorum: u8,
+    pub round: u8, // 0 when first opened, incremented on reassignment
+    pub voting_deadline: i64,
+    pub opened_at: i64,
+}
+
+#[event]
+pub struct PanelVoteCastEvent {
+    pub trust_pay: Pubkey,
+    pub milestone_index: u8,
+    pub arbitrator: Pubkey,
+    pub recipient_share_bps: u16,
+    pub round: u8,
+    pub voted_at: i64,
+}
+
+#[event]
+pub struct PanelDecidedEvent {
+    pub trust_pay: Pubkey,
+    pub milestone_index: u8,
+    pub decision: u16, // Recipient share in basis points
+    pub round: u8,
+    pub decided_at: i64,
+}
+
+#[event]
+pub struct DisputeRuledEvent {
+    pub trust_pay: Pubkey,
+    pub milestone_index: u8,
+    pub resolver: Pubkey,
+    pub recipient_share_bps: u16,
+    pub resolution: u8, // RESOLUTION_* the ruling settles with
+    pub resolution_reason: String,
+    pub appeal_deadline: i64,
+    pub ruled_at: i64,
+}
+
+#[event]
+pub struct DisputeAppealedEvent {
+    pub trust_pay: Pubkey,
+    pub milestone_index: u8,
+    pub appellant: Pubkey,
+    pub appealed_ruling: u16,
+    pub appeal_bond: u64,
+    pub escalated_to_panel: bool, // Otherwise heard by the GlobalState authority
+    pub appealed_at: i64,
+}
+
+#[event]
+pub struct EvidenceSubmittedEvent {
+    pub trust_pay: Pubkey,
+    pub milestone_index: u8,
+    pub dispute_id: u64,
+    pub submitter: Pubkey,
+    pub entry_index: u8,
+    pub content_hash: [u8; 32],
+    pub uri: String,
+    pub evidence_type: u8,
+    pub head_hash: [u8; 32], // Log head after this entry
+    pub submitted_at: i64,
+}
+
+#[event]
+pub struct DisputeRespondedEvent {
+    pub trust_pay: Pubkey,
+    pub milestone_index: u8,
+    pub responder: Pubkey,
+    pub response: String,
+    pub responded_at: i64,
+}
+
+#[event]
+pub struct SettlementOfferedEvent {
+    pub trust_pay: Pubkey,
+    pub milestone_index: u8,
+    pub offered_by: Pubkey,
+    pub recipient_share_bps: u16,
+    pub offered_at: i64,
+}
+
+#[event]
+pub struct SettlementAcceptedEvent {
+    pub trust_pay: Pubkey,
+    pub milestone_index: u8,
+    pub accepted_by: Pubkey,
+    pub recipient_share_bps: u16,
+    pub accepted_at: i64,
+}
+
+#[event]
+pub struct DisputeTimedOutEvent {
+    pub trust_pay: Pubkey,
+    pub milestone_index: u8,
+    pub disputed_at: i64,
+    pub max_resolution_time: i64,
+    pub recipient_share_bps: u16, // Default outcome applied
+    pub finalized_by: Pubkey,
+    pub timed_out_at: i64,
+}
+
+#[event]
+pub struct DisputeWithdrawnEvent {
+    pub trust_pay: Pubkey,
+    pub dispute_id: u64,
+    pub milestone_index: u8,
+    pub disputer: Pubkey,
+    pub restored_status: u8, // Milestone status before the dispute, or the contract status
+    pub bond_to_payer: u64,
+    pub bond_to_recipient: u64,
+    pub contract_status: u8,
+    pub withdrawn_at: i64,
+}
+
+#[event]
+pub struct ContractTerminatedEvent {
+    pub trust_pay: Pubkey,
+    pub dispute_id: u64,
+    pub resolver: Pubkey,
+    pub recipient_share_bps: u16, // Compensation share of every unsettled milestone
+    pub milestones_terminated: u8,
+    pub amount_to_recipient: u64,
+    pub amount_to_payer: u64,
     pub fee_amount: u64,
+    pub terminated_at: i64,
+}
+
+#[event]
+pub struct ContractDisputeResolvedEvent {
+    pub trust_pay: Pubkey,
+    pub dispute_id: u64,
+    pub payer: Pubkey,
+    pub recipient: Pubkey,
     pub resolver: Pubkey,
-    pub resolution: u8, // 0 = favor payer, 1 = favor recipient, 2 = split
+    pub resolution: u8, // RESOLUTION_TERMINATE or RESOLUTION_CONTINUE
+    pub recipient_share_bps: u16,
     pub resolution_reason: String,
+    pub bond_to_payer: u64,
+    pub bond_to_recipient: u64,
+    pub arbitration_fee: u64,
+    pub appeal_bond_to_payer: u64,
+    pub appeal_bond_to_recipient: u64,
     pub resolved_at: i64,
 }
+
+#[event]
+pub struct ReviewSubmittedEvent {
+    pub trust_pay: Pubkey,
+    pub reviewer: Pubkey,
+    pub reviewee: Pubkey,
+    pub score: u8,
+    pub comment_hash: [u8; 32],
+    pub submitted_at: i64,
+}
+
+#[event]
+pub struct MintConfiguredEvent {
+    pub mint: Pubkey,
+    pub treasury: Pubkey,
+    pub enabled: bool,
+    pub min_contract_amount: u64,
+    pub max_contract_amount: u64,
+    pub fee_override: Option<u16>,
+    pub updated_at: i64,
+}
@@ -0,0 +1,348 @@
+use anchor_lang::prelude::*;
+
+use crate::error::TrustPayError;
+
+pub const BASIS_POINTS: u64 = 10000;
+
+// Fee for an amount in basis points, clamped to the mint's fee caps (max_fee = 0 means uncapped)
+pub fn calculate_fee(amount: u64, fee_percentage: u16, min_fee: u64, max_fee: u64) -> Result<u64> {
+    let fee = mul_div(amount, fee_percentage as u64, BASIS_POINTS)?;
+
+    let fee = fee.max(min_fee);
+    if max_fee > 0 {
+        Ok(fee.min(max_fee))
+    } else {
+        Ok(fee)
+    }
+}
+
+// Split a contract fee across milestones pro rata, giving any rounding remainder to the last
+// milestone so the per-milestone fees always add up to the fee deposited
+pub fn allocate_fee(total_fee: u64, total_amount: u64, amounts: &[u64]) -> Result<Vec<u64>> {
+    require!(total_amount > 0, TrustPayError::InvalidAmount);
+
+    let mut fees = Vec::with_capacity(amounts.len());
+    let mut allocated: u64 = 0;
+    for (i, amount) in amounts.iter().enumerate() {
+        let fee = if i + 1 == amounts.len() {
+            total_fee
+                .checked_sub(allocated)
+                .ok_or(TrustPayError::CalculationError)?
+        } else {
+            mul_div(*amount, total_fee, total_amount)?
+        };
+        allocated = allocated
+            .checked_add(fee)
+            .ok_or(TrustPayError::CalculationError)?;
+        fees.push(fee);
+    }
+
+    Ok(fees)
+}
+
+// Fee deposited but never charged, returned to the payer when the contract closes
+pub fn fee_refund(fee_deposited: u64, fee_charged: u64) -> Result<u64> {
+    fee_deposited
+        .checked_sub(fee_charged)
+        .ok_or(TrustPayError::FeeOvercharged.into())
+}
+
+// Deposits must always equal payouts plus fees plus refunds
+pub fn is_conserved(deposited: u64, paid_out: u64, fees: u64, refunded: u64) -> bool {
+    paid_out
+        .checked_add(fees)
+        .and_then(|total| total.checked_add(refunded))
+        .is_some_and(|total| total == deposited)
+}
+
+// Bond a disputer posts, in basis points of the disputed amount
+pub fn dispute_bond(amount: u64, dispute_bond_bps: u16) -> Result<u64> {
+    mul_div(amount, dispute_bond_bps as u64, BASIS_POINTS)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct MilestoneSplit {
+    pub to_recipient: u64,
+    pub to_payer: u64,
+    pub fee: u64,
+}
+
+// Divide a disputed milestone by the recipient's share in basis points. The fee is charged on
+// the recipient's portion only; the payer takes the rounding remainder.
+pub fn split_milestone(amount: u64, fee: u64, recipient_share_bps: u16) -> Result<MilestoneSplit> {
+    require!(
+        recipient_share_bps as u64 <= BASIS_POINTS,
+        TrustPayError::InvalidResolution
+    );
+
+    let to_recipient = mul_div(amount, recipient_share_bps as u64, BASIS_POINTS)?;
+    Ok(MilestoneSplit {
+        to_recipient,
+        to_payer: amount
+            .checked_sub(to_recipient)
+            .ok_or(TrustPayError::CalculationError)?,
+        fee: mul_div(fee, recipient_share_bps as u64, BASIS_POINTS)?,
+    })
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BondOutcome {
+    DisputerWins,
+    CounterpartyWins,
+    Split,
+    Withdrawn,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct BondSettlement {
+    pub to_disputer: u64,
+    pub to_counterparty: u64,
+    pub arbitration_fee: u64,
+}
+
+// Divide the bond pool: the resolver takes the arbitration fee and the winner takes the rest.
+// A split returns each bond less its share of the fee; a withdrawal returns both bonds in full.
+pub fn settle_bonds(
+    disputer_bond: u64,
+    counter_bond: u64,
+    arbitration_fee_bps: u16,
+    outcome: BondOutcome,
+) -> Result<BondSettlement> {
+    let pool = disputer_bond
+        .checked_add(counter_bond)
+        .ok_or(TrustPayError::CalculationError)?;
+
+    if outcome == BondOutcome::Withdrawn {
+        return Ok(BondSettlement {
+            to_disputer: disputer_bond,
+            to_counterparty: counter_bond,
+            arbitration_fee: 0,
+        });
+    }
+
+    let arbitration_fee = mul_div(pool, arbitration_fee_bps as u64, BASIS_POINTS)?;
+    let remaining = pool
+        .checked_sub(arbitration_fee)
+        .ok_or(TrustPayError::CalculationError)?;
+
+    let settlement = match outcome {
+        BondOutcome::DisputerWins => BondSettlement {
+            to_disputer: remaining,
+            to_counterparty: 0,
+            arbitration_fee,
+        },
+        BondOutcome::CounterpartyWins => BondSettlement {
+            to_disputer: 0,
+            to_counterparty: remaining,
+            arbitration_fee,
+        },
+        _ => {
+            let to_counterparty = if pool == 0 {
+                0
+            } else {
+                mul_div(counter_bond, remaining, pool)?
+            };
+            BondSettlement {
+                to_disputer: remaining
+                    .checked_sub(to_counterparty)
+                    .ok_or(TrustPayError::CalculationError)?,
+                to_counterparty,
+                arbitration_fee,
+            }
+        }
+    };
+
+    Ok(settlement)
+}
+
+fn mul_div(value: u64, numerator: u64, denominator: u64) -> Result<u64> {
+    let result = (value as u128)
+        .checked_mul(numerator as u128)
+        .ok_or(TrustPayError::CalculationError)?
+        .checked_div(denominator as u128)
+        .ok_or(TrustPayError::CalculationError)?;
+    u64::try_from(result).map_err(|_| TrustPayError::CalculationError.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fee_uses_basis_points() {
+        assert_eq!(calculate_fee(5_000_000, 5, 0, 0).unwrap(), 2_500);
+        assert_eq!(calculate_fee(1_999, 5, 0, 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn fee_is_clamped_to_caps() {
+        assert_eq!(calculate_fee(1_000, 5, 100, 0).unwrap(), 100);
+        assert_eq!(calculate_fee(u64::MAX, 5, 100, 5_000).unwrap(), 5_000);
+        assert_eq!(calculate_fee(5_000_000, 5, 100, 5_000).unwrap(), 2_500);
+    }
+
+    #[test]
+    fn fee_does_not_overflow_on_large_amounts() {
+        let fee = calculate_fee(u64::MAX, 10_000, 0, 0).unwrap();
+        assert_eq!(fee, u64::MAX);
+    }
+
+    #[test]
+    fn allocation_sums_to_the_deposited_fee() {
+        let amounts = [333_333, 333_333, 333_334, 7, 1];
+        let total: u64 = amounts.iter().sum();
+        for total_fee in [0, 1, 2, 499, 500, 12_345] {
+            let fees = allocate_fee(total_fee, total, &amounts).unwrap();
+            assert_eq!(fees.len(), amounts.len());
+            assert_eq!(fees.iter().sum::<u64>(), total_fee);
+        }
+    }
+
+    #[test]
+    fn allocation_is_pro_rata() {
+        let fees = allocate_fee(1_000, 10_000, &[2_000, 3_000, 5_000]).unwrap();
+        assert_eq!(fees, vec![200, 300, 500]);
+    }
+
+    #[test]
+    fn allocation_rejects_zero_total() {
+        assert!(allocate_fee(10, 0, &[0]).is_err());
+    }
+
+    #[test]
+    fn refund_is_the_uncharged_fee() {
+        assert_eq!(fee_refund(1_000, 400).unwrap(), 600);
+        assert_eq!(fee_refund(1_000, 1_000).unwrap(), 0);
+        assert!(fee_refund(1_000, 1_001).is_err());
+    }
+
+    #[test]
+    fn milestone_lifecycle_conserves_funds() {
+        let amounts = [1_234_567, 89_101, 1_121_314, 15, 161_718];
+        let total_amount: u64 = amounts.iter().sum();
+        let fee_deposited = calculate_fee(total_amount, 5, 7, 0).unwrap();
+        let fees = allocate_fee(fee_deposited, total_amount, &amounts).unwrap();
+        let deposited = total_amount + fee_deposited;
+
+        // Approve, refund and split milestones in turn
+        let mut paid_out = 0;
+        let mut fee_charged = 0;
+        let mut refunded = 0;
+        for (i, (amount, fee)) in amounts.iter().zip(fees.iter()).enumerate() {
+            match i % 3 {
+                0 => {
+                    paid_out += amount;
+                    fee_charged += fee;
+                }
+                1 => refunded += amount,
+                _ => {
+                    let recipient_share = amount / 2;
+                    paid_out += recipient_share;
+                    refunded += amount - recipient_share;
+                }
+            }
+        }
+        refunded += fee_refund(fee_deposited, fee_charged).unwrap();
+
+        assert!(is_conserved(deposited, paid_out, fee_charged, refunded));
+        assert!(!is_conserved(
+            deposited,
+            paid_out,
+            fee_charged,
+            refunded + 1
+        ));
+    }
+
+    #[test]
+    fn split_follows_the_recipient_share() {
+        let split = split_milestone(1_000_000, 500, 7_000).unwrap();
+        assert_eq!(split.to_recipient, 700_000);
+        assert_eq!(split.to_payer, 300_000);
+        assert_eq!(split.fee, 350);
+
+        let split = split_milestone(1_000_001, 500, 10_000).unwrap();
+        assert_eq!(split.to_recipient, 1_000_001);
+        assert_eq!(split.fee, 500);
+
+        let split = split_milestone(1_000_001, 500, 0).unwrap();
+        assert_eq!(split.to_payer, 1_000_001);
+        assert_eq!(split.fee, 0);
+
+        assert!(split_milestone(1_000, 5, 10_001).is_err());
+    }
+
+    #[test]
+    fn split_conserves_the_milestone() {
+        for amount in [0, 1, 3, 999, 1_000_001] {
+            for share in [0, 1, 3_333, 5_000, 9_999, 10_000] {
+                let split = split_milestone(amount, 7, share).unwrap();
+                assert_eq!(split.to_recipient + split.to_payer, amount);
+                assert!(split.fee <= 7);
+            }
+        }
+    }
+
+    #[test]
+    fn bond_uses_basis_points() {
+        assert_eq!(dispute_bond(2_000_000, 500).unwrap(), 100_000);
+        assert_eq!(dispute_bond(2_000_000, 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn winner_takes_the_bond_pool_less_arbitration_fee() {
+        let settlement =
+            settle_bonds(100_000, 100_000, 1_000, BondOutcome::CounterpartyWins).unwrap();
+        assert_eq!(settlement.arbitration_fee, 20_000);
+        assert_eq!(settlement.to_counterparty, 180_000);
+        assert_eq!(settlement.to_disputer, 0);
+
+        let settlement = settle_bonds(100_000, 0, 1_000, BondOutcome::DisputerWins).unwrap();
+        assert_eq!(settlement.arbitration_fee, 10_000);
+        assert_eq!(settlement.to_disputer, 90_000);
+    }
+
+    #[test]
+    fn split_returns_bonds_less_their_share_of_the_fee() {
+        let settlement = settle_bonds(100_000, 50_000, 1_000, BondOutcome::Split).unwrap();
+        assert_eq!(settlement.arbitration_fee, 15_000);
+        assert_eq!(settlement.to_counterparty, 45_000);
+        assert_eq!(settlement.to_disputer, 90_000);
+    }
+
+    #[test]
+    fn withdrawal_returns_bonds_in_full() {
+        let settlement = settle_bonds(100_000, 50_000, 1_000, BondOutcome::Withdrawn).unwrap();
+        assert_eq!(
+            settlement,
+            BondSettlement {
+                to_disputer: 100_000,
+                to_counterparty: 50_000,
+                arbitration_fee: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn bond_settlements_conserve_the_pool() {
+        let outcomes = [
+            BondOutcome::DisputerWins,
+            BondOutcome::CounterpartyWins,
+            BondOutcome::Split,
+            BondOutcome::Withdrawn,
+        ];
+        for (disputer_bond, counter_bond) in [(0, 0), (1, 0), (7, 3), (99_999, 12_345)] {
+            for fee_bps in [0, 1, 333, 10_000] {
+                for outcome in outcomes {
+                    let settlement =
+                        settle_bonds(disputer_bond, counter_bond, fee_bps, outcome).unwrap();
+                    assert!(is_conserved(
+                        disputer_bond + counter_bond,
+                        settlement.to_disputer + settlement.to_counterparty,
+                        settlement.arbitration_fee,
+                        0,
+                    ));
+                }
+            }
+        }
+    }
+}
@@ -7,7 +7,7 @@ use anchor_spl::{
 use crate::constants::*;
 use crate::error::TrustPayError;
 use crate::instructions::shared::*;
-use crate::state::TrustPay;
+use crate::state::{GlobalState, MintStats, TrustPay};
 
 #[derive(Accounts)]
 pub struct AcceptContract<'info> {
@@ -44,15 +44,38 @@ pub struct AcceptContract<'info> {
     )]
     pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    #[account(
+        seeds = [b"global-state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"mint-stats", mint.key().as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Box<Account<'info, MintStats>>,
+
     pub system_program: Program<'info, System>,
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
-pub fn accept_contract(ctx: Context<AcceptContract>, deadline_duration_seconds: u64) -> Result<()> {
+pub fn accept_contract<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AcceptContract<'info>>,
+    deadline_duration_seconds: u64,
+) -> Result<()> {
     let trust_pay_key = ctx.accounts.trust_pay.key();
     let current_time = Clock::get()?.unix_timestamp;
 
+    require!(
+        !ctx.accounts
+            .global_state
+            .is_paused(PAUSE_ACCEPTANCES, current_time),
+        TrustPayError::OperationPaused
+    );
+
     // Validate deadline duration
     require!(
         deadline_duration_seconds > 0,
@@ -94,6 +117,15 @@ pub fn accept_contract(ctx: Context<AcceptContract>, deadline_duration_seconds:
         .checked_add(ctx.accounts.trust_pay.fee)
         .ok_or(TrustPayError::CalculationError)?;
 
+    // Wrap the deposit first when the contract is in native SOL
+    wrap_native_deposit(
+        &ctx.accounts.payer_token_account,
+        total_deposit,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        &ctx.accounts.token_program,
+    )?;
+
     // Transfer tokens to vault (payer accepts and deposits)
     transfer_tokens(
         &ctx.accounts.payer_token_account,
@@ -102,6 +134,12 @@ pub fn accept_contract(ctx: Context<AcceptContract>, deadline_duration_seconds:
         &ctx.accounts.mint,
         &ctx.accounts.payer,
         &ctx.accounts.token_program,
+        ctx.remaining_accounts,
+    )?;
+    close_empty_native_account(
+        &mut ctx.accounts.payer_token_account,
+        &ctx.accounts.payer,
+        &ctx.accounts.token_program,
     )?;
 
     // Update the trust_pay account with acceptance details
@@ -109,6 +147,8 @@ pub fn accept_contract(ctx: Context<AcceptContract>, deadline_duration_seconds:
     trust_pay.deadline = Some(calculated_deadline as u64);
     trust_pay.acceptance_timestamp = Some(current_time);
     trust_pay.contract_status = CONTRACT_STATUS_IN_PROGRESS;
+    trust_pay.fee_deposited = trust_pay.fee;
+    ctx.accounts.mint_stats.record_escrow_opened(total_deposit)?;
 
     // Emit contract accepted event
     emit!(crate::ContractAcceptedEvent {
@@ -0,0 +1,501 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::constants::*;
+use crate::error::TrustPayError;
+use crate::fee_math::{dispute_bond, BASIS_POINTS};
+use crate::instructions::panel_voting::record_panel_resolution;
+use crate::instructions::settle_dispute::*;
+use crate::instructions::shared::*;
+use crate::state::{
+    Arbitrator, ContractReview, Dispute, DisputePanel, GlobalState, MintStats, Reputation,
+    Treasury, TrustPay,
+};
+
+#[derive(Accounts)]
+pub struct AppealRuling<'info> {
+    #[account(mut)]
+    pub appellant: Signer<'info>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    // Native SOL parties may have closed their wSOL account after depositing
+    #[account(
+        init_if_needed,
+        payer = appellant,
+        associated_token::mint = mint,
+        associated_token::authority = appellant,
+        associated_token::token_program = token_program
+    )]
+    pub appellant_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = mint,
+        seeds = [b"trust-pay", trust_pay.payer.key().as_ref(), trust_pay.seed.to_le_bytes().as_ref()],
+        bump = trust_pay.bump,
+        constraint = trust_pay.has_active_disputes() @ TrustPayError::ContractNotDisputed
+    )]
+    pub trust_pay: Account<'info, TrustPay>,
+
+    #[account(
+        mut,
+        has_one = trust_pay @ TrustPayError::DisputeMismatch,
+        seeds = [b"dispute", dispute.dispute_id.to_le_bytes().as_ref()],
+        bump = dispute.bump
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = trust_pay,
+        associated_token::token_program = token_program
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [b"global-state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"mint-stats", mint.key().as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Box<Account<'info, MintStats>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeRuling<'info> {
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        has_one = payer,
+        has_one = recipient,
+        has_one = mint,
+        seeds = [b"trust-pay", payer.key().as_ref(), trust_pay.seed.to_le_bytes().as_ref()],
+        bump = trust_pay.bump,
+        constraint = trust_pay.has_active_disputes() @ TrustPayError::ContractNotDisputed
+    )]
+    pub trust_pay: Account<'info, TrustPay>,
+
+    #[account(
+        mut,
+        has_one = trust_pay @ TrustPayError::DisputeMismatch,
+        seeds = [b"dispute", dispute.dispute_id.to_le_bytes().as_ref()],
+        bump = dispute.bump
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = trust_pay,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: temporary wSOL account for a native SOL payout, created and closed in this instruction
+    #[account(
+        mut,
+        seeds = [b"native-payout", trust_pay.key().as_ref()],
+        bump
+    )]
+    pub native_payout: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = caller,
+        associated_token::mint = mint,
+        associated_token::authority = payer,
+        associated_token::token_program = token_program,
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = caller,
+        associated_token::mint = mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    // Token account of the single resolver who ruled; panel rulings pay the treasury
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub resolver_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        has_one = mint,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump = treasury.bump,
+        constraint = treasury.key() == trust_pay.fee_destination @ TrustPayError::InvalidFeeDestination
+    )]
+    pub treasury: Box<Account<'info, Treasury>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program,
+    )]
+    pub treasury_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"global-state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"reputation", payer.key().as_ref()],
+        bump = payer_reputation.bump
+    )]
+    pub payer_reputation: Box<Account<'info, Reputation>>,
+
+    #[account(
+        mut,
+        seeds = [b"reputation", recipient.key().as_ref()],
+        bump = recipient_reputation.bump
+    )]
+    pub recipient_reputation: Box<Account<'info, Reputation>>,
+
+    #[account(
+        mut,
+        seeds = [b"review", trust_pay.key().as_ref()],
+        bump = contract_review.bump
+    )]
+    pub contract_review: Box<Account<'info, ContractReview>>,
+
+    #[account(
+        mut,
+        seeds = [b"mint-stats", mint.key().as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Box<Account<'info, MintStats>>,
+
+    // Registry entry of the assigned arbitrator who ruled, credited with the case
+    #[account(mut)]
+    pub arbitrator: Option<Box<Account<'info, Arbitrator>>>,
+
+    // Panel that ruled; its deciding members' registry accounts lead the remaining accounts
+    #[account(
+        has_one = trust_pay,
+        seeds = [b"panel", dispute.key().as_ref()],
+        bump = panel.bump
+    )]
+    pub panel: Option<Box<Account<'info, DisputePanel>>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+// Record a first-instance ruling on the dispute's milestone, or on the whole contract; no funds
+// move until the appeal window closes
+pub fn record_ruling(
+    trust_pay: &mut Account<TrustPay>,
+    dispute: &mut Account<Dispute>,
+    recipient_share_bps: u16,
+    resolution: u8,
+    ruled_by: Pubkey,
+    resolution_reason: String,
+    appeal_window: i64,
+) -> Result<()> {
+    require!(
+        recipient_share_bps as u64 <= BASIS_POINTS,
+        TrustPayError::InvalidResolution
+    );
+
+    let trust_pay_key = trust_pay.key();
+    let milestone_index = dispute.milestone_index;
+    let now = Clock::get()?.unix_timestamp;
+    let appeal_deadline = now
+        .checked_add(appeal_window)
+        .ok_or(TrustPayError::CalculationError)?;
+
+    trust_pay.require_open_dispute(milestone_index, dispute.dispute_id)?;
+    let milestone = trust_pay.dispute_state_mut(milestone_index)?;
+    milestone.ruling = Some(recipient_share_bps);
+    milestone.ruling_resolution = resolution;
+    milestone.ruled_by = Some(ruled_by);
+    milestone.ruled_at = Some(now);
+    milestone.appeal_deadline = Some(appeal_deadline);
+    dispute.status = DISPUTE_STATUS_RULED;
+    dispute.updated_at = now;
+
+    emit!(crate::DisputeRuledEvent {
+        trust_pay: trust_pay_key,
+        milestone_index,
+        resolver: ruled_by,
+        recipient_share_bps,
+        resolution,
+        resolution_reason,
+        appeal_deadline,
+        ruled_at: now,
+    });
+
+    msg!(
+        "Ruling of {} bps to recipient recorded on milestone {}, appealable until {}",
+        recipient_share_bps,
+        milestone_index,
+        appeal_deadline
+    );
+
+    Ok(())
+}
+
+pub(crate) fn appeal_ruling<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AppealRuling<'info>>,
+    milestone_index: u8,
+) -> Result<()> {
+    let trust_pay_key = ctx.accounts.trust_pay.key();
+    let appellant_key = ctx.accounts.appellant.key();
+    let now = Clock::get()?.unix_timestamp;
+
+    // An appeal both deposits a bond and reopens the dispute
+    require!(
+        !ctx.accounts
+            .global_state
+            .is_paused(PAUSE_NEW_CONTRACTS | PAUSE_DISPUTE_RESOLUTION, now),
+        TrustPayError::OperationPaused
+    );
+
+    let ruling;
+    let escalated_to_panel;
+    let bond_amount;
+    {
+        let trust_pay = &ctx.accounts.trust_pay;
+        let global_state = &ctx.accounts.global_state;
+        let milestone = trust_pay.dispute_state(milestone_index)?;
+        require!(
+            milestone.dispute_id == Some(ctx.accounts.dispute.dispute_id),
+            TrustPayError::DisputeMismatch
+        );
+        ruling = milestone.ruling.ok_or(TrustPayError::NoPendingRuling)?;
+        require!(
+            now <= milestone.appeal_deadline.unwrap_or_default(),
+            TrustPayError::AppealWindowClosed
+        );
+
+        // Either party can appeal a ruling that does not give them the full milestone; only the
+        // disputer can appeal the dismissal of a contract dispute
+        let is_losing_party = if milestone.ruling_resolution == RESOLUTION_CONTINUE {
+            milestone.disputer == Some(appellant_key)
+        } else {
+            (appellant_key == trust_pay.payer && ruling > 0)
+                || (appellant_key == trust_pay.recipient && (ruling as u64) < BASIS_POINTS)
+        };
+        require!(is_losing_party, TrustPayError::UnauthorizedAppeal);
+
+        // Panel decisions are appealed to the authority; single rulings go to a panel when
+        // panels are enabled, otherwise to the authority unless it made the ruling
+        escalated_to_panel = !milestone.panel_required && global_state.panel_threshold > 0;
+        if !milestone.panel_required && !escalated_to_panel {
+            require!(
+                milestone.ruled_by != Some(global_state.authority),
+                TrustPayError::AppealUnavailable
+            );
+        }
+
+        bond_amount = dispute_bond(milestone.amount, global_state.appeal_bond_bps)?;
+    }
+
+    // Post the appeal bond into the vault
+    if bond_amount > 0 {
+        // Wrap the bond first when the contract is in native SOL
+        wrap_native_deposit(
+            &ctx.accounts.appellant_token_account,
+            bond_amount,
+            &ctx.accounts.appellant,
+            &ctx.accounts.system_program,
+            &ctx.accounts.token_program,
+        )?;
+        transfer_tokens(
+            &ctx.accounts.appellant_token_account,
+            &ctx.accounts.vault,
+            &bond_amount,
+            &ctx.accounts.mint,
+            &ctx.accounts.appellant,
+            &ctx.accounts.token_program,
+            ctx.remaining_accounts,
+        )?;
+        close_empty_native_account(
+            &mut ctx.accounts.appellant_token_account,
+            &ctx.accounts.appellant,
+            &ctx.accounts.token_program,
+        )?;
+        ctx.accounts.mint_stats.record_deposit(bond_amount)?;
+    }
+
+    {
+        let milestone = ctx.accounts.trust_pay.dispute_state_mut(milestone_index)?;
+        milestone.appeal_level = 1;
+        milestone.appellant = Some(appellant_key);
+        milestone.appeal_bond = bond_amount;
+        milestone.appealed_ruling = Some(ruling);
+        milestone.appealed_resolution = milestone.ruling_resolution;
+        milestone.ruling = None;
+        milestone.ruled_by = None;
+        milestone.ruled_at = None;
+        milestone.appeal_deadline = None;
+        milestone.panel_required = escalated_to_panel;
+        milestone.escalated_at = Some(now);
+        milestone.arbitrator = None;
+
+        let dispute = &mut ctx.accounts.dispute;
+        dispute.status = DISPUTE_STATUS_APPEALED;
+        dispute.arbitrator = None;
+        dispute.updated_at = now;
+    }
+
+    emit!(crate::DisputeAppealedEvent {
+        trust_pay: trust_pay_key,
+        milestone_index,
+        appellant: appellant_key,
+        appealed_ruling: ruling,
+        appeal_bond: bond_amount,
+        escalated_to_panel,
+        appealed_at: now,
+    });
+
+    msg!(
+        "Ruling on milestone {} appealed by {}, bond: {}",
+        milestone_index,
+        appellant_key,
+        bond_amount
+    );
+
+    Ok(())
+}
+
+// Anyone can execute a ruling once its appeal window has closed
+pub(crate) fn finalize_ruling<'info>(
+    ctx: Context<'_, '_, 'info, 'info, FinalizeRuling<'info>>,
+    milestone_index: u8,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        !ctx.accounts
+            .global_state
+            .is_paused(PAUSE_DISPUTE_RESOLUTION, now),
+        TrustPayError::OperationPaused
+    );
+
+    let milestone = ctx.accounts.trust_pay.dispute_state(milestone_index)?;
+    let ruling = milestone.ruling.ok_or(TrustPayError::NoPendingRuling)?;
+    let resolution = milestone.ruling_resolution;
+    let ruled_by = milestone.ruled_by.ok_or(TrustPayError::NoPendingRuling)?;
+    require!(
+        now > milestone.appeal_deadline.unwrap_or_default(),
+        TrustPayError::AppealWindowOpen
+    );
+    let panel_ruling = milestone.panel_required;
+    let assigned_ruling = !panel_ruling && milestone.arbitrator == Some(ruled_by);
+    let ruled_at = milestone.ruled_at.unwrap_or(now);
+    let resolution_time = ruled_at - milestone.disputed_at.unwrap_or(ruled_at);
+
+    // The ruling settles now, so it counts towards the stats of whoever made it
+    let mut hook_accounts = ctx.remaining_accounts;
+    if panel_ruling {
+        let panel = ctx
+            .accounts
+            .panel
+            .as_ref()
+            .ok_or(TrustPayError::ArbitratorMismatch)?;
+        require!(panel.key() == ruled_by, TrustPayError::ArbitratorMismatch);
+        hook_accounts = record_panel_resolution(panel, resolution_time, ctx.remaining_accounts)?;
+    } else if let Some(arbitrator) = ctx.accounts.arbitrator.as_mut() {
+        require!(
+            arbitrator.arbitrator == ruled_by,
+            TrustPayError::ArbitratorMismatch
+        );
+        arbitrator.record_resolution(resolution_time)?;
+    } else {
+        require!(!assigned_ruling, TrustPayError::ArbitratorMismatch);
+    }
+
+    let accounts = ctx.accounts;
+    let resolver_token_account_info = match accounts.resolver_token_account.as_ref() {
+        Some(account) if !panel_ruling => {
+            require!(
+                account.owner == ruled_by,
+                TrustPayError::InvalidFeeDestination
+            );
+            Some(account.to_account_info())
+        }
+        None if !panel_ruling => return Err(TrustPayError::InvalidFeeDestination.into()),
+        _ => None,
+    };
+    let payer_info = accounts.payer.to_account_info();
+    let recipient_info = accounts.recipient.to_account_info();
+    let payer_token_account_info = accounts.payer_token_account.to_account_info();
+    let recipient_token_account_info = accounts.recipient_token_account.to_account_info();
+    let treasury_token_account_info = accounts.treasury_token_account.to_account_info();
+    let token_program_info = accounts.token_program.to_account_info();
+    let native_payout_info = accounts.native_payout.to_account_info();
+    let funder_info = accounts.caller.to_account_info();
+    let system_program_info = accounts.system_program.to_account_info();
+
+    settle_with_resolution(
+        DisputeSettlement {
+            payer: &payer_info,
+            recipient: &recipient_info,
+            mint: &accounts.mint,
+            trust_pay: &mut accounts.trust_pay,
+            dispute: &mut accounts.dispute,
+            vault: &mut accounts.vault,
+            payer_token_account: &payer_token_account_info,
+            recipient_token_account: &recipient_token_account_info,
+            arbitration_fee_account: resolver_token_account_info.as_ref(),
+            treasury: &mut accounts.treasury,
+            treasury_token_account: &treasury_token_account_info,
+            global_state: &mut accounts.global_state,
+            mint_stats: &mut accounts.mint_stats,
+            payer_reputation: &mut accounts.payer_reputation,
+            recipient_reputation: &mut accounts.recipient_reputation,
+            contract_review: &mut accounts.contract_review,
+            native_payout: &native_payout_info,
+            native_payout_bump: ctx.bumps.native_payout,
+            funder: &funder_info,
+            system_program: &system_program_info,
+            token_program: &token_program_info,
+            remaining_accounts: hook_accounts,
+        },
+        milestone_index,
+        ruling,
+        resolution,
+        DISPUTE_STATUS_RESOLVED,
+        ruled_by,
+        "Ruling final after the appeal window".to_string(),
+    )
+}
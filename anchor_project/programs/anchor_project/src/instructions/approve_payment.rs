@@ -2,16 +2,21 @@ use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
     token_interface::{
-        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
-        TransferChecked,
+        close_account, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
     },
 };
 
 use crate::constants::*;
 use crate::error::TrustPayError;
-use crate::instructions::shared::*;
-use crate::state::TrustPay;
-use crate::{update_on_payment_approval, update_on_trust_pay_close, GlobalState};
+use crate::fee_math::{fee_refund, is_conserved};
+use crate::instructions::shared::{
+    close_empty_native_account, close_native_account, pay_out_native, transfer_checked_with_hook,
+    NativePayout,
+};
+use crate::state::{ContractReview, MintStats, Reputation, Treasury, TrustPay};
+use crate::{
+    record_fee_collection, update_on_payment_approval, update_on_trust_pay_close, GlobalState,
+};
 
 #[derive(Accounts)]
 pub struct ApproveMilestonePayment<'info> {
@@ -27,7 +32,6 @@ pub struct ApproveMilestonePayment<'info> {
         has_one = payer,
         has_one = recipient,
         has_one = mint,
-        has_one = fee_destination,
         seeds = [b"trust-pay", payer.key().as_ref(), trust_pay.seed.to_le_bytes().as_ref()],
         bump = trust_pay.bump,
         constraint = trust_pay.contract_status == CONTRACT_STATUS_IN_PROGRESS @ TrustPayError::ContractNotInProgress
@@ -44,6 +48,23 @@ pub struct ApproveMilestonePayment<'info> {
     )]
     pub vault: InterfaceAccount<'info, TokenAccount>,
 
+    /// CHECK: temporary wSOL account for a native SOL payout, created and closed in this instruction
+    #[account(
+        mut,
+        seeds = [b"native-payout", trust_pay.key().as_ref()],
+        bump
+    )]
+    pub native_payout: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = payer,
+        associated_token::token_program = token_program,
+    )]
+    pub payer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
     #[account(
         init_if_needed,
         payer = payer,
@@ -53,17 +74,22 @@ pub struct ApproveMilestonePayment<'info> {
     )]
     pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
 
-    /// CHECK: This is the fee destination account
-    pub fee_destination: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        has_one = mint,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump = treasury.bump,
+        constraint = treasury.key() == trust_pay.fee_destination @ TrustPayError::InvalidFeeDestination
+    )]
+    pub treasury: Box<Account<'info, Treasury>>,
 
     #[account(
-        init_if_needed,
-        payer = payer,
+        mut,
         associated_token::mint = mint,
-        associated_token::authority = fee_destination,
+        associated_token::authority = treasury,
         associated_token::token_program = token_program,
     )]
-    pub fee_destination_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub treasury_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
@@ -72,15 +98,50 @@ pub struct ApproveMilestonePayment<'info> {
     )]
     pub global_state: Account<'info, GlobalState>,
 
+    #[account(
+        mut,
+        seeds = [b"reputation", payer.key().as_ref()],
+        bump = payer_reputation.bump
+    )]
+    pub payer_reputation: Box<Account<'info, Reputation>>,
+
+    #[account(
+        mut,
+        seeds = [b"reputation", recipient.key().as_ref()],
+        bump = recipient_reputation.bump
+    )]
+    pub recipient_reputation: Box<Account<'info, Reputation>>,
+
+    #[account(
+        mut,
+        seeds = [b"review", trust_pay.key().as_ref()],
+        bump = contract_review.bump
+    )]
+    pub contract_review: Box<Account<'info, ContractReview>>,
+
+    #[account(
+        mut,
+        seeds = [b"mint-stats", mint.key().as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Box<Account<'info, MintStats>>,
+
     pub system_program: Program<'info, System>,
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
-pub fn approve_milestone_payment(
-    ctx: Context<ApproveMilestonePayment>,
+pub fn approve_milestone_payment<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ApproveMilestonePayment<'info>>,
     milestone_index: u8,
 ) -> Result<()> {
+    require!(
+        !ctx.accounts
+            .global_state
+            .is_paused(PAUSE_PAYOUTS, Clock::get()?.unix_timestamp),
+        TrustPayError::OperationPaused
+    );
+
     let trust_pay_seed = ctx.accounts.trust_pay.seed;
     let trust_pay_bump = ctx.accounts.trust_pay.bump;
     let trust_pay_key = ctx.accounts.trust_pay.key();
@@ -123,70 +184,136 @@ pub fn approve_milestone_payment(
         milestone_amount = milestone.amount;
         milestone_description = milestone.description.clone();
 
-        // Calculate the proportional fee for this milestone
-        fee_amount = milestone_amount
-            .checked_mul(trust_pay.fee_percentage as u64)
-            .ok_or(TrustPayError::CalculationError)?
-            .checked_div(10000)
-            .ok_or(TrustPayError::CalculationError)?;
+        // The milestone fee was fixed within the fee caps when the contract was created
+        fee_amount = milestone.fee;
     }
 
     let vault_info = ctx.accounts.vault.to_account_info();
+    let payer_token_account_info = ctx.accounts.payer_token_account.to_account_info();
     let recipient_token_account_info = ctx.accounts.recipient_token_account.to_account_info();
-    let fee_destination_token_account_info =
-        ctx.accounts.fee_destination_token_account.to_account_info();
+    let treasury_token_account_info = ctx.accounts.treasury_token_account.to_account_info();
     let mint_info = ctx.accounts.mint.to_account_info();
     let trust_pay_info = ctx.accounts.trust_pay.to_account_info();
     let token_program_info = ctx.accounts.token_program.to_account_info();
+    let native = ctx.accounts.vault.is_native();
+
+    // Transfer milestone amount to recipient, as lamports for a native SOL contract
+    if native {
+        pay_out_native(
+            NativePayout {
+                vault: &vault_info,
+                mint: &ctx.accounts.mint,
+                trust_pay: &trust_pay_info,
+                payout_account: &ctx.accounts.native_payout.to_account_info(),
+                funder: &ctx.accounts.payer.to_account_info(),
+                system_program: &ctx.accounts.system_program.to_account_info(),
+                token_program: &token_program_info,
+            },
+            &ctx.accounts.recipient.to_account_info(),
+            milestone_amount,
+            ctx.bumps.native_payout,
+            &signer_seeds,
+        )?;
+    } else {
+        let transfer_accounts = TransferChecked {
+            from: vault_info.clone(),
+            to: recipient_token_account_info.clone(),
+            mint: mint_info.clone(),
+            authority: trust_pay_info.clone(),
+        };
 
-    // Transfer milestone amount to recipient
-    let transfer_accounts = TransferChecked {
-        from: vault_info.clone(),
-        to: recipient_token_account_info.clone(),
-        mint: mint_info.clone(),
-        authority: trust_pay_info.clone(),
-    };
-
-    let transfer_ctx =
-        CpiContext::new_with_signer(token_program_info.clone(), transfer_accounts, &signer_seeds);
-
-    transfer_checked(transfer_ctx, milestone_amount, mint_decimals)?;
+        transfer_checked_with_hook(
+            &token_program_info,
+            transfer_accounts,
+            ctx.remaining_accounts,
+            milestone_amount,
+            mint_decimals,
+            &signer_seeds,
+        )?;
+    }
+    ctx.accounts.mint_stats.record_withdrawal(milestone_amount)?;
 
     // Transfer fee if any
     if fee_amount > 0 {
         let fee_transfer_accounts = TransferChecked {
             from: vault_info.clone(),
-            to: fee_destination_token_account_info.clone(),
+            to: treasury_token_account_info.clone(),
             mint: mint_info.clone(),
             authority: trust_pay_info.clone(),
         };
 
-        let fee_transfer_ctx = CpiContext::new_with_signer(
-            token_program_info.clone(),
+        transfer_checked_with_hook(
+            &token_program_info,
             fee_transfer_accounts,
+            ctx.remaining_accounts,
+            fee_amount,
+            mint_decimals,
             &signer_seeds,
-        );
-
-        transfer_checked(fee_transfer_ctx, fee_amount, mint_decimals)?;
+        )?;
+        ctx.accounts.mint_stats.record_withdrawal(fee_amount)?;
+
+        record_fee_collection(
+            &mut ctx.accounts.treasury,
+            &mut ctx.accounts.mint_stats,
+            trust_pay_key,
+            fee_amount,
+        )?;
     }
 
     // Update milestone status and check if contract is complete
     let contract_completed;
     {
         let trust_pay = &mut ctx.accounts.trust_pay;
+        trust_pay.amount_released = trust_pay
+            .amount_released
+            .checked_add(milestone_amount)
+            .ok_or(TrustPayError::CalculationError)?;
+        trust_pay.fee_charged = trust_pay
+            .fee_charged
+            .checked_add(fee_amount)
+            .ok_or(TrustPayError::CalculationError)?;
+
         let milestone = &mut trust_pay.milestones[index];
         milestone.status = MILESTONE_STATUS_APPROVED_BY_PAYER;
         milestone.approved_at = Some(Clock::get()?.unix_timestamp);
 
-        // Check if all milestones are approved
-        contract_completed = trust_pay.is_all_milestones_approved();
+        // Check if all milestones are settled
+        contract_completed = trust_pay.is_all_milestones_settled();
         if contract_completed {
             trust_pay.contract_status = CONTRACT_STATUS_COMPLETED;
         }
     }
 
+    // Update both parties' reputation
+    {
+        let now = Clock::get()?.unix_timestamp;
+        let completed_at = ctx.accounts.trust_pay.milestones[index]
+            .completed_at
+            .unwrap_or(now);
+        ctx.accounts
+            .payer_reputation
+            .record_approval(now - completed_at, now)?;
+
+        if contract_completed {
+            let volume = ctx.accounts.trust_pay.amount_released;
+            ctx.accounts
+                .payer_reputation
+                .record_completion(volume, now)?;
+            ctx.accounts
+                .recipient_reputation
+                .record_completion(volume, now)?;
+
+            // Both parties may now rate each other
+            ctx.accounts.contract_review.completed_at = Some(now);
+        }
+    }
+
     // Update global statistics
-    update_on_payment_approval(&mut ctx.accounts.global_state, milestone_amount)?;
+    update_on_payment_approval(
+        &mut ctx.accounts.global_state,
+        &mut ctx.accounts.mint_stats,
+        milestone_amount,
+    )?;
 
     // Emit milestone approved event
     emit!(crate::MilestoneApprovedEvent {
@@ -202,42 +329,92 @@ pub fn approve_milestone_payment(
 
     // If contract is completed, handle cleanup
     if contract_completed {
-        // Check for any remaining dust in vault
+        // Refund any fee that was deposited but never charged
+        let fee_refunded = fee_refund(
+            ctx.accounts.trust_pay.fee_deposited,
+            ctx.accounts.trust_pay.fee_charged,
+        )?;
+        {
+            let trust_pay = &mut ctx.accounts.trust_pay;
+            trust_pay.amount_refunded = trust_pay
+                .amount_refunded
+                .checked_add(fee_refunded)
+                .ok_or(TrustPayError::CalculationError)?;
+
+            let total_deposited = trust_pay
+                .total_contract_amount
+                .checked_add(trust_pay.fee_deposited)
+                .ok_or(TrustPayError::CalculationError)?;
+            require!(
+                is_conserved(
+                    total_deposited,
+                    trust_pay.amount_released,
+                    trust_pay.fee_charged,
+                    trust_pay.amount_refunded,
+                ),
+                TrustPayError::FundsNotConserved
+            );
+        }
+
+        // Anything sent to the vault outside the contract also goes back to the payer
         ctx.accounts.vault.reload()?;
         let remaining_balance = ctx.accounts.vault.amount;
+        require!(
+            remaining_balance >= fee_refunded,
+            TrustPayError::FundsNotConserved
+        );
 
-        if remaining_balance > 0 {
-            // Transfer any remaining dust to fee destination
-            let dust_transfer_accounts = TransferChecked {
+        if native {
+            // Closing a native SOL vault refunds the payer in lamports
+            close_native_account(
+                &vault_info,
+                &trust_pay_info,
+                &ctx.accounts.payer.to_account_info(),
+                remaining_balance,
+                &token_program_info,
+                &signer_seeds,
+            )?;
+        } else if remaining_balance > 0 {
+            let refund_accounts = TransferChecked {
                 from: vault_info.clone(),
-                to: fee_destination_token_account_info.clone(),
+                to: payer_token_account_info.clone(),
                 mint: mint_info.clone(),
                 authority: trust_pay_info.clone(),
             };
 
-            let dust_transfer_ctx = CpiContext::new_with_signer(
-                token_program_info.clone(),
-                dust_transfer_accounts,
+            transfer_checked_with_hook(
+                &token_program_info,
+                refund_accounts,
+                ctx.remaining_accounts,
+                remaining_balance,
+                mint_decimals,
                 &signer_seeds,
-            );
-
-            transfer_checked(dust_transfer_ctx, remaining_balance, mint_decimals)?;
+            )?;
         }
 
+        // Only the fee refund is still counted in the mint's TVL; the rest was never deposited here
+        ctx.accounts.mint_stats.record_withdrawal(fee_refunded)?;
+
         // Close vault account
-        let close_accounts = CloseAccount {
-            account: vault_info.clone(),
-            destination: ctx.accounts.payer.to_account_info(),
-            authority: trust_pay_info.clone(),
-        };
+        if !native {
+            let close_accounts = CloseAccount {
+                account: vault_info.clone(),
+                destination: ctx.accounts.payer.to_account_info(),
+                authority: trust_pay_info.clone(),
+            };
 
-        let close_ctx =
-            CpiContext::new_with_signer(token_program_info.clone(), close_accounts, &signer_seeds);
+            let close_ctx = CpiContext::new_with_signer(
+                token_program_info.clone(),
+                close_accounts,
+                &signer_seeds,
+            );
 
-        close_account(close_ctx)?;
+            close_account(close_ctx)?;
+        }
 
         // Update global statistics for contract closure
         update_on_trust_pay_close(&mut ctx.accounts.global_state)?;
+        ctx.accounts.mint_stats.record_escrow_closed()?;
 
         // Close TrustPay account and return rent to payer
         let payer_starting_lamports = ctx.accounts.payer.lamports();
@@ -260,12 +437,21 @@ pub fn approve_milestone_payment(
             payer: payer_key,
             recipient: recipient_key,
             total_amount: ctx.accounts.trust_pay.total_contract_amount,
+            fee_charged: ctx.accounts.trust_pay.fee_charged,
+            fee_refunded,
             completed_at: Clock::get()?.unix_timestamp,
         });
 
         msg!("Contract completed and closed successfully");
     }
 
+    // The payer's wSOL account was only opened for this instruction
+    close_empty_native_account(
+        &mut ctx.accounts.payer_token_account,
+        &ctx.accounts.payer,
+        &ctx.accounts.token_program,
+    )?;
+
     msg!(
         "Milestone {} approved: {} tokens transferred to recipient, {} fee collected",
         milestone_index,
@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::error::TrustPayError;
+use crate::state::{Arbitrator, Dispute, GlobalState, TrustPay};
+
+#[derive(Accounts)]
+pub struct AssignDispute<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"global-state"],
+        bump = global_state.bump,
+        has_one = authority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"trust-pay", trust_pay.payer.key().as_ref(), trust_pay.seed.to_le_bytes().as_ref()],
+        bump = trust_pay.bump,
+        constraint = trust_pay.has_active_disputes() @ TrustPayError::ContractNotDisputed,
+        constraint = trust_pay.arbitrator.is_none() @ TrustPayError::ContractArbitratorSet
+    )]
+    pub trust_pay: Account<'info, TrustPay>,
+
+    #[account(
+        mut,
+        has_one = trust_pay @ TrustPayError::DisputeMismatch,
+        seeds = [b"dispute", dispute.dispute_id.to_le_bytes().as_ref()],
+        bump = dispute.bump
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    #[account(
+        mut,
+        seeds = [b"arbitrator", arbitrator_account.arbitrator.as_ref()],
+        bump = arbitrator_account.bump,
+        constraint = arbitrator_account.active @ TrustPayError::ArbitratorInactive
+    )]
+    pub arbitrator_account: Account<'info, Arbitrator>,
+}
+
+pub(crate) fn assign_dispute(ctx: Context<AssignDispute>, milestone_index: u8) -> Result<()> {
+    let arbitrator = ctx.accounts.arbitrator_account.arbitrator;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        !ctx.accounts
+            .global_state
+            .is_paused(PAUSE_DISPUTE_RESOLUTION, now),
+        TrustPayError::OperationPaused
+    );
+
+    // Validate the milestone, or the whole contract, is under this dispute
+    ctx.accounts
+        .trust_pay
+        .require_open_dispute(milestone_index, ctx.accounts.dispute.dispute_id)?;
+    require!(
+        !ctx.accounts
+            .trust_pay
+            .dispute_state(milestone_index)?
+            .panel_required,
+        TrustPayError::PanelRequired
+    );
+
+    // Like a panel member, the arbitrator must be independent of both parties
+    require!(
+        arbitrator != ctx.accounts.trust_pay.payer && arbitrator != ctx.accounts.trust_pay.recipient,
+        TrustPayError::InvalidPanelMembers
+    );
+
+    // Reassigning replaces the previous arbitrator
+    ctx.accounts
+        .trust_pay
+        .dispute_state_mut(milestone_index)?
+        .arbitrator = Some(arbitrator);
+    ctx.accounts.dispute.arbitrator = Some(arbitrator);
+    ctx.accounts.dispute.updated_at = now;
+
+    let arbitrator_account = &mut ctx.accounts.arbitrator_account;
+    arbitrator_account.cases_assigned = arbitrator_account
+        .cases_assigned
+        .checked_add(1)
+        .ok_or(TrustPayError::CalculationError)?;
+
+    emit!(crate::DisputeAssignedEvent {
+        trust_pay: ctx.accounts.trust_pay.key(),
+        milestone_index,
+        arbitrator,
+        assigned_at: now,
+    });
+
+    msg!(
+        "Dispute on milestone {} assigned to arbitrator {}",
+        milestone_index,
+        arbitrator
+    );
+
+    Ok(())
+}
@@ -2,14 +2,14 @@ use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
     token_interface::{
-        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
-        TransferChecked,
+        close_account, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
     },
 };
 
 use crate::constants::*;
 use crate::error::TrustPayError;
-use crate::state::TrustPay;
+use crate::instructions::shared::{close_native_account, transfer_checked_with_hook};
+use crate::state::{ContractReview, MintStats, Reputation, TrustPay};
 
 #[derive(Accounts)]
 pub struct CancelContract<'info> {
@@ -54,12 +54,37 @@ pub struct CancelContract<'info> {
     )]
     pub canceller_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    #[account(
+        mut,
+        seeds = [b"reputation", canceller.key().as_ref()],
+        bump = canceller_reputation.bump
+    )]
+    pub canceller_reputation: Box<Account<'info, Reputation>>,
+
+    // Review record opened at creation; its rent goes back to the recipient who created the contract
+    #[account(
+        mut,
+        close = recipient,
+        seeds = [b"review", trust_pay.key().as_ref()],
+        bump = contract_review.bump
+    )]
+    pub contract_review: Box<Account<'info, ContractReview>>,
+
+    #[account(
+        mut,
+        seeds = [b"mint-stats", mint.key().as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Box<Account<'info, MintStats>>,
+
     pub system_program: Program<'info, System>,
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
-pub fn cancel_contract(ctx: Context<CancelContract>) -> Result<()> {
+pub fn cancel_contract<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CancelContract<'info>>,
+) -> Result<()> {
     let trust_pay_seed = ctx.accounts.trust_pay.seed;
     let trust_pay_bump = ctx.accounts.trust_pay.bump;
     let trust_pay_key = ctx.accounts.trust_pay.key();
@@ -102,7 +127,8 @@ pub fn cancel_contract(ctx: Context<CancelContract>) -> Result<()> {
     let vault_balance = ctx.accounts.vault.amount;
 
     // If there are tokens in vault, refund them to the canceller (creator who deposited)
-    if vault_balance > 0 {
+    let native = ctx.accounts.vault.is_native();
+    if vault_balance > 0 && !native {
         let vault_info = ctx.accounts.vault.to_account_info();
         let canceller_token_account_info = ctx.accounts.canceller_token_account.to_account_info();
         let mint_info = ctx.accounts.mint.to_account_info();
@@ -117,13 +143,14 @@ pub fn cancel_contract(ctx: Context<CancelContract>) -> Result<()> {
             authority: trust_pay_info.clone(),
         };
 
-        let transfer_ctx = CpiContext::new_with_signer(
-            token_program_info.clone(),
+        transfer_checked_with_hook(
+            &token_program_info,
             transfer_accounts,
+            ctx.remaining_accounts,
+            vault_balance,
+            mint_decimals,
             &signer_seeds,
-        );
-
-        transfer_checked(transfer_ctx, vault_balance, mint_decimals)?;
+        )?;
 
         msg!(
             "Refunded {} tokens to creator {}",
@@ -132,20 +159,35 @@ pub fn cancel_contract(ctx: Context<CancelContract>) -> Result<()> {
         );
     }
 
-    // Close vault account
-    let close_accounts = CloseAccount {
-        account: ctx.accounts.vault.to_account_info(),
-        destination: ctx.accounts.payer.to_account_info(),
-        authority: ctx.accounts.trust_pay.to_account_info(),
-    };
+    // Only what funding the contract deposited counts towards the mint's TVL
+    let tracked_deposit = ctx.accounts.trust_pay.get_tracked_deposit()?;
+    ctx.accounts.mint_stats.record_withdrawal(tracked_deposit)?;
 
-    let close_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        close_accounts,
-        &signer_seeds,
-    );
+    // Close vault account; a native SOL vault refunds the creator in lamports
+    if native {
+        close_native_account(
+            &ctx.accounts.vault.to_account_info(),
+            &ctx.accounts.trust_pay.to_account_info(),
+            &ctx.accounts.canceller.to_account_info(),
+            vault_balance,
+            &ctx.accounts.token_program.to_account_info(),
+            &signer_seeds,
+        )?;
+    } else {
+        let close_accounts = CloseAccount {
+            account: ctx.accounts.vault.to_account_info(),
+            destination: ctx.accounts.payer.to_account_info(),
+            authority: ctx.accounts.trust_pay.to_account_info(),
+        };
+
+        let close_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            close_accounts,
+            &signer_seeds,
+        );
 
-    close_account(close_ctx)?;
+        close_account(close_ctx)?;
+    }
 
     // Close TrustPay account and return rent to payer
     let payer_starting_lamports = ctx.accounts.payer.lamports();
@@ -168,6 +210,11 @@ pub fn cancel_contract(ctx: Context<CancelContract>) -> Result<()> {
         *byte = 0;
     }
 
+    ctx.accounts
+        .canceller_reputation
+        .record_cancellation(Clock::get()?.unix_timestamp)?;
+    ctx.accounts.mint_stats.record_contract_closed()?;
+
     // Emit contract cancelled event
     emit!(crate::ContractCancelledEvent {
         trust_pay: trust_pay_key,
@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenInterface};
+
+use crate::constants::ANCHOR_DISCRIMINATOR;
+use crate::error::TrustPayError;
+use crate::fee_math::BASIS_POINTS;
+use crate::initialize_treasury_if_needed;
+use crate::state::{GlobalState, MintConfig, MintStats, Treasury};
+
+#[derive(Accounts)]
+pub struct ConfigureMint<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"global-state"],
+        bump = global_state.bump,
+        has_one = authority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = ANCHOR_DISCRIMINATOR + MintConfig::INIT_SPACE,
+        seeds = [b"mint-config", mint.key().as_ref()],
+        bump
+    )]
+    pub mint_config: Box<Account<'info, MintConfig>>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = ANCHOR_DISCRIMINATOR + Treasury::INIT_SPACE,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump
+    )]
+    pub treasury: Box<Account<'info, Treasury>>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = ANCHOR_DISCRIMINATOR + MintStats::INIT_SPACE,
+        seeds = [b"mint-stats", mint.key().as_ref()],
+        bump
+    )]
+    pub mint_stats: Box<Account<'info, MintStats>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub(crate) fn configure_mint(
+    ctx: Context<ConfigureMint>,
+    enabled: bool,
+    min_contract_amount: u64,
+    max_contract_amount: u64,
+    fee_override: Option<u16>,
+) -> Result<()> {
+    // A max_contract_amount of zero leaves contract size uncapped
+    require!(
+        (max_contract_amount == 0 || min_contract_amount <= max_contract_amount)
+            && fee_override.unwrap_or(0) as u64 <= BASIS_POINTS,
+        TrustPayError::InvalidMintConfig
+    );
+
+    initialize_treasury_if_needed(
+        &mut ctx.accounts.treasury,
+        ctx.accounts.mint.key(),
+        ctx.bumps.treasury,
+    );
+
+    let updated_at = Clock::get()?.unix_timestamp;
+
+    // Statistics start counting from the first configuration
+    let mint_stats = &mut ctx.accounts.mint_stats;
+    if mint_stats.mint == Pubkey::default() {
+        mint_stats.mint = ctx.accounts.mint.key();
+        mint_stats.updated_at = updated_at;
+        mint_stats.bump = ctx.bumps.mint_stats;
+    }
+
+    let mint_config = &mut ctx.accounts.mint_config;
+    mint_config.mint = ctx.accounts.mint.key();
+    mint_config.decimals = ctx.accounts.mint.decimals;
+    mint_config.enabled = enabled;
+    mint_config.min_contract_amount = min_contract_amount;
+    mint_config.max_contract_amount = max_contract_amount;
+    mint_config.fee_override = fee_override;
+    mint_config.treasury = ctx.accounts.treasury.key();
+    mint_config.updated_at = updated_at;
+    mint_config.bump = ctx.bumps.mint_config;
+
+    emit!(crate::MintConfiguredEvent {
+        mint: mint_config.mint,
+        treasury: mint_config.treasury,
+        enabled,
+        min_contract_amount,
+        max_contract_amount,
+        fee_override,
+        updated_at,
+    });
+
+    msg!(
+        "Mint {} {}, contract size {}-{}, fee: {} bps",
+        mint_config.mint,
+        if enabled { "enabled" } else { "disabled" },
+        min_contract_amount,
+        max_contract_amount,
+        mint_config.fee_percentage()
+    );
+
+    Ok(())
+}
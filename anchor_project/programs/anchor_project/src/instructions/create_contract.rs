@@ -8,6 +8,7 @@ use crate::error::TrustPayError;
 use crate::instructions::shared::*;
 use crate::state::*;
 use crate::constants::*;
+use crate::fee_math::{allocate_fee, calculate_fee};
 use crate::GlobalState;
 
 #[derive(Accounts)]
@@ -22,6 +23,7 @@ use crate::GlobalState;
     total_amount: u64,
     milestones: Vec<MilestoneInput>,    
     deadline_duration_seconds: u64,
+    arbitrator: Option<Pubkey>,
 )]
 pub struct CreateContract<'info> {
     #[account(mut)]
@@ -39,6 +41,20 @@ pub struct CreateContract<'info> {
     )]
     pub creator_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    // Only mints enabled by the authority can be escrowed
+    #[account(
+        seeds = [b"mint-config", mint.key().as_ref()],
+        bump = mint_config.bump,
+        constraint = mint_config.enabled @ TrustPayError::MintNotEnabled
+    )]
+    pub mint_config: Box<Account<'info, MintConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"mint-stats", mint.key().as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Box<Account<'info, MintStats>>,
 
     #[account(
         init,
@@ -58,18 +74,56 @@ pub struct CreateContract<'info> {
     )]
     pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// CHECK: This is the fee destination account, validated in the instruction
-    pub fee_destination: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump = treasury.bump,
+        address = mint_config.treasury
+    )]
+    pub treasury: Box<Account<'info, Treasury>>,
 
     #[account(
         init_if_needed,
         payer = creator,
-        space = 8 + GlobalState::INIT_SPACE,
+        associated_token::mint = mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
         seeds = [b"global-state"],
-        bump
+        bump = global_state.bump
     )]
     pub global_state: Account<'info, GlobalState>,
 
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = ANCHOR_DISCRIMINATOR + Reputation::INIT_SPACE,
+        seeds = [b"reputation", creator.key().as_ref()],
+        bump
+    )]
+    pub creator_reputation: Box<Account<'info, Reputation>>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = ANCHOR_DISCRIMINATOR + Reputation::INIT_SPACE,
+        seeds = [b"reputation", other_party.as_ref()],
+        bump
+    )]
+    pub other_party_reputation: Box<Account<'info, Reputation>>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = ANCHOR_DISCRIMINATOR + ContractReview::INIT_SPACE,
+        seeds = [b"review", trust_pay.key().as_ref()],
+        bump
+    )]
+    pub contract_review: Box<Account<'info, ContractReview>>,
+
     pub system_program: Program<'info, System>,
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -81,8 +135,8 @@ pub struct MilestoneInput {
     pub amount: u64,
 }
 
-pub fn create_contract(
-    ctx: Context<CreateContract>,
+pub fn create_contract<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateContract<'info>>,
     seed: u64,
     creator_role: u8,
     payer_pubkey: Pubkey,
@@ -93,30 +147,43 @@ pub fn create_contract(
     total_amount: u64,
     milestone_inputs: Vec<MilestoneInput>,
     deadline_duration_seconds: u64,
+    arbitrator: Option<Pubkey>,
 ) -> Result<()> {
     // Validations
+    require!(
+        !ctx.accounts
+            .global_state
+            .is_paused(PAUSE_NEW_CONTRACTS, Clock::get()?.unix_timestamp),
+        TrustPayError::OperationPaused
+    );
+
+    // Determine who is payer and who is recipient
     let creator = ctx.accounts.creator.key();
     let (payer, recipient) = match creator_role {
         ROLE_PAYER => (creator, other_party),
         ROLE_RECIPIENT => (other_party, creator),
         _ => return Err(TrustPayError::InvalidRole.into()),
     };
+    require!(payer != recipient, TrustPayError::SelfContract);
 
     require!(
         payer == payer_pubkey,
         TrustPayError::PayerMismatch 
     );
 
-    require!(
-        creator_role == ROLE_PAYER || creator_role == ROLE_RECIPIENT,
-        TrustPayError::InvalidRole
-    );
     require!(
         contract_type == CONTRACT_TYPE_ONE_TIME || contract_type == CONTRACT_TYPE_MILESTONE,
         TrustPayError::InvalidContractType
     );
 
+    // Transfer fees and similar extensions would break the vault accounting
+    require_supported_mint(&ctx.accounts.mint)?;
+
     require!(total_amount > 0, TrustPayError::InvalidAmount);
+    require!(
+        ctx.accounts.mint_config.allows_amount(total_amount),
+        TrustPayError::ContractAmountOutOfRange
+    );
     require!(
         terms_and_conditions.len() > 0 && terms_and_conditions.len() <= 200,
         TrustPayError::TermsAndConditionsTooLong
@@ -127,13 +194,13 @@ pub fn create_contract(
         TrustPayError::InvalidDeadline
     );
 
-    // Determine who is payer and who is recipient
-    let creator = ctx.accounts.creator.key();
-    let (payer, recipient) = match creator_role {
-        ROLE_PAYER => (creator, other_party),
-        ROLE_RECIPIENT => (other_party, creator),
-        _ => return Err(TrustPayError::InvalidRole.into()),
-    };
+    // A contract-specific arbitrator must be independent of both parties
+    if let Some(arbitrator) = arbitrator {
+        require!(
+            arbitrator != payer && arbitrator != recipient,
+            TrustPayError::InvalidArbitrator
+        );
+    }
 
     // Validate milestones based on contract type
     if contract_type == CONTRACT_TYPE_MILESTONE {
@@ -159,15 +226,26 @@ pub fn create_contract(
     }
 
     //Fee percentage
-    let fee_percentage: u16 = 5;
-    let fee_destination = ctx.accounts.fee_destination.key();
-
-    // Calculate fee amount
-    let fee_amount = total_amount
-        .checked_mul(fee_percentage as u64)
-        .ok_or(TrustPayError::CalculationError)?
-        .checked_div(10000)
-        .ok_or(TrustPayError::CalculationError)?;
+    let fee_percentage: u16 = ctx.accounts.mint_config.fee_percentage();
+
+    // Fees are collected by the program-owned treasury for this mint
+    let fee_destination = ctx.accounts.treasury.key();
+
+    // Calculate fee amount within the mint's fee caps
+    let fee_amount = calculate_fee(
+        total_amount,
+        fee_percentage,
+        ctx.accounts.mint_config.min_fee,
+        ctx.accounts.mint_config.max_fee,
+    )?;
+
+    // Spread the fee over the milestones so approvals charge exactly what was deposited
+    let milestone_amounts: Vec<u64> = if contract_type == CONTRACT_TYPE_MILESTONE {
+        milestone_inputs.iter().map(|m| m.amount).collect()
+    } else {
+        vec![total_amount]
+    };
+    let milestone_fees = allocate_fee(fee_amount, total_amount, &milestone_amounts)?;
 
     // Transfer tokens to vault
     let total_deposit = total_amount
@@ -177,6 +255,15 @@ pub fn create_contract(
     // Only deposit tokens if creator is the payer
     let should_deposit = creator_role == ROLE_PAYER;
     let contract_status = if should_deposit {
+        // Wrap the deposit first when the contract is in native SOL
+        wrap_native_deposit(
+            &ctx.accounts.creator_token_account,
+            total_deposit,
+            &ctx.accounts.creator,
+            &ctx.accounts.system_program,
+            &ctx.accounts.token_program,
+        )?;
+
         // Transfer tokens to vault
         transfer_tokens(
             &ctx.accounts.creator_token_account,
@@ -185,6 +272,12 @@ pub fn create_contract(
             &ctx.accounts.mint,
             &ctx.accounts.creator,
             &ctx.accounts.token_program,
+            ctx.remaining_accounts,
+        )?;
+        close_empty_native_account(
+            &mut ctx.accounts.creator_token_account,
+            &ctx.accounts.creator,
+            &ctx.accounts.token_program,
         )?;
         CONTRACT_STATUS_IN_PROGRESS
     } else {
@@ -196,40 +289,21 @@ pub fn create_contract(
     let milestones: Vec<Milestone> = if contract_type == CONTRACT_TYPE_MILESTONE {
         milestone_inputs
             .into_iter()
-            .map(|input| Milestone {
-                description: input.description,
-                amount: input.amount,
-                status: MILESTONE_STATUS_PENDING,
-                completed_at: None,
-                approved_at: None,
-                dispute_reason: None,
-                dispute_id: None,
-            })
+            .zip(milestone_fees)
+            .map(|(input, fee)| Milestone::new(input.description, input.amount, fee))
             .collect()
     } else {
         // For one-time payment, create a single milestone
-        vec![Milestone {
-            description: "One-time payment".to_string(),
-            amount: total_amount,
-            status: MILESTONE_STATUS_PENDING,
-            completed_at: None,
-            approved_at: None,
-            dispute_reason: None,
-            dispute_id: None,
-        }]
+        vec![Milestone::new(
+            "One-time payment".to_string(),
+            total_amount,
+            fee_amount,
+        )]
     };
 
     // Calculate deadline timestamp
     let clock = Clock::get()?;
 
-    // Initialize global state if needed
-    initialize_global_state_if_needed(
-        &mut ctx.accounts.global_state,
-        &ctx.accounts.creator,
-        &ctx.accounts.mint,
-        ctx.bumps.global_state,
-    );
-
     // Initialize TrustPay contract
     ctx.accounts.trust_pay.set_inner(TrustPay {
         seed,
@@ -251,13 +325,43 @@ pub fn create_contract(
         fee_percentage,
         fee_destination,
         fee: fee_amount,
+        fee_deposited: if should_deposit { fee_amount } else { 0 },
+        fee_charged: 0,
+        amount_released: 0,
+        amount_refunded: 0,
         milestones,
+        arbitrator,
+        contract_dispute: Milestone::new(String::new(), 0, 0),
         bump: ctx.bumps.trust_pay,
     });
 
-    // Update global statistics
+    // Review record, opened for ratings once the contract completes
+    ctx.accounts.contract_review.set_inner(ContractReview {
+        trust_pay: ctx.accounts.trust_pay.key(),
+        payer,
+        recipient,
+        completed_at: None,
+        payer_review: None,
+        recipient_review: None,
+        bump: ctx.bumps.contract_review,
+    });
+
+    // Update global and per-mint statistics
     let global_state = &mut ctx.accounts.global_state;
     global_state.total_trust_pay_created += 1;
+    ctx.accounts.mint_stats.record_contract_created()?;
+    if should_deposit {
+        ctx.accounts.mint_stats.record_escrow_opened(total_deposit)?;
+    }
+
+    // Open reputation profiles for both parties on their first contract
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts
+        .creator_reputation
+        .open_if_new(creator, ctx.bumps.creator_reputation, now);
+    ctx.accounts
+        .other_party_reputation
+        .open_if_new(other_party, ctx.bumps.other_party_reputation, now);
 
     // Emit event
     emit!(crate::ContractCreatedEvent {
@@ -271,6 +375,7 @@ pub fn create_contract(
         deadline: deadline_duration_seconds as i64,
         fee_percentage,
         fee_destination,
+        arbitrator,
     });
 
     msg!(
@@ -287,36 +392,3 @@ pub fn create_contract(
 
     Ok(())
 }
-
-fn initialize_global_state_if_needed(
-    global_state: &mut Account<GlobalState>,
-    payer: &Signer,
-    mint: &InterfaceAccount<Mint>,
-    bump: u8,
-) {
-    if global_state.total_trust_pay_created == 0
-        && global_state.total_trust_pay_closed == 0
-        && global_state.total_confirmations == 0
-    {
-        // In testing mode, the payer is used as authority
-        
-        let authority = payer.key();
-        
-        //TODO: use the RESOLVER_AUTHORITY
-        // In production, the hardcoded RESOLVER_AUTHORITY is the authority        
-        //let authority = crate::RESOLVER_AUTHORITY;
-        
-        global_state.authority = authority;
-        global_state.total_trust_pay_created = 0;
-        global_state.total_trust_pay_closed = 0;
-        global_state.total_confirmations = 0;
-        global_state.total_volume = 0;
-        global_state.high_watermark_volume = 0;
-        global_state.last_volume_update = Clock::get().unwrap().unix_timestamp;
-        global_state.token_decimals = mint.decimals;
-        global_state.total_disputes = 0;
-        global_state.bump = bump;
-
-        msg!("Global state initialized with resolver authority: {}", authority);
-    }
-}
\ No newline at end of file
@@ -1,23 +1,49 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::hash::hash;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
 
 use crate::constants::*;
 use crate::error::TrustPayError;
-use crate::state::{GlobalState, TrustPay};
+use crate::fee_math::dispute_bond;
+use crate::instructions::shared::*;
+use crate::state::{Dispute, GlobalState, Milestone, MintStats, Reputation, TrustPay};
 
 #[derive(Accounts)]
 pub struct DisputeContract<'info> {
     #[account(mut)]
     pub disputer: Signer<'info>,
 
+    #[account(mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = disputer,
+        associated_token::mint = mint,
+        associated_token::authority = disputer,
+        associated_token::token_program = token_program
+    )]
+    pub disputer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
     #[account(
         mut,
+        has_one = mint,
         seeds = [b"trust-pay", trust_pay.payer.key().as_ref(), trust_pay.seed.to_le_bytes().as_ref()],
         bump = trust_pay.bump,
         constraint = trust_pay.contract_status == CONTRACT_STATUS_IN_PROGRESS @ TrustPayError::ContractNotInProgress
     )]
     pub trust_pay: Account<'info, TrustPay>,
 
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = trust_pay,
+        associated_token::token_program = token_program
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
     #[account(
         mut,
         seeds = [b"global-state"],
@@ -25,32 +51,37 @@ pub struct DisputeContract<'info> {
     )]
     pub global_state: Account<'info, GlobalState>,
 
-    pub system_program: Program<'info, System>,
-}
-
-fn generate_dispute_id(trust_pay_pubkey: &Pubkey, milestone_index: u8, timestamp: i64) -> String {
-    let seed = format!(
-        "{}:{}:{}",
-        trust_pay_pubkey.to_string(),
-        milestone_index,
-        timestamp
-    );
+    // Numbered by the global dispute counter, kept after the contract closes
+    #[account(
+        init,
+        payer = disputer,
+        space = ANCHOR_DISCRIMINATOR + Dispute::INIT_SPACE,
+        seeds = [b"dispute", global_state.total_disputes.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
 
-    let hash_result = hash(seed.as_bytes());
-    let hash_bytes = hash_result.to_bytes();
+    #[account(
+        mut,
+        seeds = [b"reputation", disputer.key().as_ref()],
+        bump = disputer_reputation.bump
+    )]
+    pub disputer_reputation: Box<Account<'info, Reputation>>,
 
-    let alpha1 = ((hash_bytes[0] % 26) + b'A') as char;
-    let alpha2 = ((hash_bytes[1] % 26) + b'A') as char;
-    let num1 = (hash_bytes[2] % 10) as u8;
-    let num2 = (hash_bytes[3] % 10) as u8;
-    let num3 = (hash_bytes[4] % 10) as u8;
-    let num4 = (hash_bytes[5] % 10) as u8;
+    #[account(
+        mut,
+        seeds = [b"mint-stats", mint.key().as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Box<Account<'info, MintStats>>,
 
-    format!("{}{}{}{}{}{}", alpha1, alpha2, num1, num2, num3, num4)
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
-pub fn dispute_contract(
-    ctx: Context<DisputeContract>,
+pub fn dispute_contract<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DisputeContract<'info>>,
     milestone_index: u8,
     dispute_reason: String,
 ) -> Result<()> {
@@ -58,15 +89,24 @@ pub fn dispute_contract(
     let trust_pay_key = ctx.accounts.trust_pay.key();
     let disputer_key = ctx.accounts.disputer.key();
 
+    // Opening a dispute deposits a bond
+    require!(
+        !ctx.accounts
+            .global_state
+            .is_paused(PAUSE_NEW_CONTRACTS, Clock::get()?.unix_timestamp),
+        TrustPayError::OperationPaused
+    );
+
     // Validate dispute reason
     require!(
         dispute_reason.len() >= 10 && dispute_reason.len() <= 500,
         TrustPayError::InvalidDisputeReason
     );
 
-    // Validate milestone index
+    // Validate milestone index; CONTRACT_DISPUTE_INDEX disputes the contract as a whole
+    let whole_contract = milestone_index == CONTRACT_DISPUTE_INDEX;
     require!(
-        index < ctx.accounts.trust_pay.milestones.len(),
+        whole_contract || index < ctx.accounts.trust_pay.milestones.len(),
         TrustPayError::InvalidMilestoneIndex
     );
 
@@ -79,38 +119,111 @@ pub fn dispute_contract(
         );
     }
 
-    // Validate milestone status - must be completed by service provider
+    // Pending milestones can be disputed too, e.g. when the payer withholds what the work
+    // needs; a contract dispute covers every milestone not yet paid out or refunded
+    let milestone_description;
+    let milestone_amount;
     {
-        let milestone = &ctx.accounts.trust_pay.milestones[index];
-        require!(
-            milestone.status == MILESTONE_STATUS_COMPLETED_BY_SP,
-            TrustPayError::MilestoneNotDisputable
-        );
+        let trust_pay = &ctx.accounts.trust_pay;
+        if whole_contract {
+            require!(
+                !trust_pay.has_active_disputes(),
+                TrustPayError::MilestoneDisputesOpen
+            );
+            milestone_description = trust_pay.title.clone();
+            milestone_amount = trust_pay.get_unsettled_amount()?;
+            require!(milestone_amount > 0, TrustPayError::MilestoneNotDisputable);
+        } else {
+            let milestone = &trust_pay.milestones[index];
+            require!(
+                milestone.is_unsettled(),
+                TrustPayError::MilestoneNotDisputable
+            );
+            milestone_description = milestone.description.clone();
+            milestone_amount = milestone.amount;
+        }
     }
 
-    // Generate unique dispute ID
     let current_timestamp = Clock::get()?.unix_timestamp;
-    let dispute_id = generate_dispute_id(&trust_pay_key, milestone_index, current_timestamp);
+    let dispute_id = ctx.accounts.global_state.total_disputes;
 
-    // Store milestone info before mutation
-    let milestone_description;
-    let milestone_amount;
-    {
-        let milestone = &ctx.accounts.trust_pay.milestones[index];
-        milestone_description = milestone.description.clone();
-        milestone_amount = milestone.amount;
+    // Post the dispute bond into the vault
+    let bond_amount = dispute_bond(milestone_amount, ctx.accounts.global_state.dispute_bond_bps)?;
+    if bond_amount > 0 {
+        // Wrap the bond first when the contract is in native SOL
+        wrap_native_deposit(
+            &ctx.accounts.disputer_token_account,
+            bond_amount,
+            &ctx.accounts.disputer,
+            &ctx.accounts.system_program,
+            &ctx.accounts.token_program,
+        )?;
+        transfer_tokens(
+            &ctx.accounts.disputer_token_account,
+            &ctx.accounts.vault,
+            &bond_amount,
+            &ctx.accounts.mint,
+            &ctx.accounts.disputer,
+            &ctx.accounts.token_program,
+            ctx.remaining_accounts,
+        )?;
+        close_empty_native_account(
+            &mut ctx.accounts.disputer_token_account,
+            &ctx.accounts.disputer,
+            &ctx.accounts.token_program,
+        )?;
+        ctx.accounts.mint_stats.record_deposit(bond_amount)?;
     }
 
-    // Update milestone status to disputed
+    // A contract dispute freezes the whole contract, a milestone dispute only that milestone.
+    // High-value disputes, measured by everything unsettled for a contract dispute, go to a panel.
     {
+        let panel_required = ctx
+            .accounts
+            .global_state
+            .requires_panel(&ctx.accounts.trust_pay, milestone_amount);
+
         let trust_pay = &mut ctx.accounts.trust_pay;
-        let milestone = &mut trust_pay.milestones[index];
+        if whole_contract {
+            trust_pay.contract_status = CONTRACT_STATUS_DISPUTED;
+            trust_pay.contract_dispute =
+                Milestone::new(milestone_description.clone(), milestone_amount, 0);
+        }
+        let milestone = trust_pay.dispute_state_mut(milestone_index)?;
+        milestone.status_before_dispute = milestone.status;
         milestone.status = MILESTONE_STATUS_DISPUTED;
-        milestone.dispute_reason = Some(dispute_reason.clone());
-        milestone.dispute_id = Some(dispute_id.clone());
+        milestone.dispute_id = Some(dispute_id);
+        milestone.disputer = Some(disputer_key);
+        milestone.dispute_bond = bond_amount;
+        milestone.counter_bond = 0;
+        milestone.disputed_at = Some(current_timestamp);
+        milestone.escalated_at = None;
+        milestone.arbitrator = None;
+        milestone.panel_required = panel_required;
+        milestone.settlement_offer = None;
+    }
 
-        // Update contract status to disputed
-        trust_pay.contract_status = CONTRACT_STATUS_DISPUTED;
+    // Record the dispute
+    {
+        let trust_pay = &ctx.accounts.trust_pay;
+        let dispute = &mut ctx.accounts.dispute;
+        dispute.dispute_id = dispute_id;
+        dispute.trust_pay = trust_pay_key;
+        dispute.milestone_index = milestone_index;
+        dispute.payer = trust_pay.payer;
+        dispute.recipient = trust_pay.recipient;
+        dispute.disputer = disputer_key;
+        dispute.reason = dispute_reason.clone();
+        dispute.response = None;
+        dispute.amount = milestone_amount;
+        dispute.status = DISPUTE_STATUS_OPEN;
+        dispute.arbitrator = trust_pay.arbitrator;
+        dispute.outcome = None;
+        dispute.resolved_by = None;
+        dispute.opened_at = current_timestamp;
+        dispute.updated_at = current_timestamp;
+        dispute.closed_at = None;
+        dispute.bump = ctx.bumps.dispute;
     }
 
     // Update global state
@@ -122,6 +235,11 @@ pub fn dispute_contract(
             .ok_or(TrustPayError::CalculationError)?;
     }
 
+    ctx.accounts
+        .disputer_reputation
+        .record_dispute_opened(current_timestamp)?;
+    ctx.accounts.mint_stats.record_dispute_opened()?;
+
     // Emit dispute event
     emit!(crate::DisputeCreatedEvent {
         trust_pay: trust_pay_key,
@@ -132,15 +250,17 @@ pub fn dispute_contract(
         amount: milestone_amount,
         disputer: disputer_key,
         reason: dispute_reason,
-        dispute_id: dispute_id.clone(),
+        dispute_id,
+        bond_amount,
         disputed_at: current_timestamp,
     });
 
     msg!(
-        "Contract disputed - Milestone {}, Dispute ID: {}, Disputer: {}",
+        "Contract disputed - Milestone {}, Dispute ID: {}, Disputer: {}, Bond: {}",
         milestone_index,
         dispute_id,
-        disputer_key
+        disputer_key,
+        bond_amount
     );
 
     Ok(())
@@ -0,0 +1,234 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::constants::*;
+use crate::error::TrustPayError;
+use crate::instructions::settle_dispute::*;
+use crate::state::{ContractReview, Dispute, GlobalState, MintStats, Reputation, Treasury, TrustPay};
+
+#[derive(Accounts)]
+pub struct FinalizeStaleDispute<'info> {
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        has_one = payer,
+        has_one = recipient,
+        has_one = mint,
+        seeds = [b"trust-pay", payer.key().as_ref(), trust_pay.seed.to_le_bytes().as_ref()],
+        bump = trust_pay.bump,
+        constraint = trust_pay.has_active_disputes() @ TrustPayError::ContractNotDisputed
+    )]
+    pub trust_pay: Account<'info, TrustPay>,
+
+    #[account(
+        mut,
+        has_one = trust_pay @ TrustPayError::DisputeMismatch,
+        seeds = [b"dispute", dispute.dispute_id.to_le_bytes().as_ref()],
+        bump = dispute.bump
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = trust_pay,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: temporary wSOL account for a native SOL payout, created and closed in this instruction
+    #[account(
+        mut,
+        seeds = [b"native-payout", trust_pay.key().as_ref()],
+        bump
+    )]
+    pub native_payout: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = caller,
+        associated_token::mint = mint,
+        associated_token::authority = payer,
+        associated_token::token_program = token_program,
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = caller,
+        associated_token::mint = mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        has_one = mint,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump = treasury.bump,
+        constraint = treasury.key() == trust_pay.fee_destination @ TrustPayError::InvalidFeeDestination
+    )]
+    pub treasury: Box<Account<'info, Treasury>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program,
+    )]
+    pub treasury_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"global-state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"reputation", payer.key().as_ref()],
+        bump = payer_reputation.bump
+    )]
+    pub payer_reputation: Box<Account<'info, Reputation>>,
+
+    #[account(
+        mut,
+        seeds = [b"reputation", recipient.key().as_ref()],
+        bump = recipient_reputation.bump
+    )]
+    pub recipient_reputation: Box<Account<'info, Reputation>>,
+
+    #[account(
+        mut,
+        seeds = [b"review", trust_pay.key().as_ref()],
+        bump = contract_review.bump
+    )]
+    pub contract_review: Box<Account<'info, ContractReview>>,
+
+    #[account(
+        mut,
+        seeds = [b"mint-stats", mint.key().as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Box<Account<'info, MintStats>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+// Anyone can apply the default outcome once a dispute has gone unresolved for too long
+pub(crate) fn finalize_stale_dispute<'info>(
+    ctx: Context<'_, '_, 'info, 'info, FinalizeStaleDispute<'info>>,
+    milestone_index: u8,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let global_state = &ctx.accounts.global_state;
+
+    require!(
+        !global_state.is_paused(PAUSE_DISPUTE_RESOLUTION, now),
+        TrustPayError::OperationPaused
+    );
+    let max_resolution_time = global_state.max_resolution_time;
+    require!(
+        max_resolution_time > 0,
+        TrustPayError::DisputeTimeoutDisabled
+    );
+    let recipient_share_bps = global_state.default_recipient_share_bps;
+
+    // Validate the milestone, or the whole contract, is under dispute
+    let milestone = ctx.accounts.trust_pay.dispute_state(milestone_index)?;
+    require!(
+        milestone.status == MILESTONE_STATUS_DISPUTED,
+        TrustPayError::MilestoneNotDisputed
+    );
+
+    // A recorded ruling settles through finalize_ruling instead
+    require!(milestone.ruling.is_none(), TrustPayError::RulingPending);
+    // The clock restarts whenever the dispute is appealed or a panel is seated
+    let disputed_at = milestone.disputed_at.unwrap_or_default();
+    let stale_at = milestone
+        .escalated_at
+        .map_or(disputed_at, |escalated_at| escalated_at.max(disputed_at))
+        .checked_add(max_resolution_time)
+        .ok_or(TrustPayError::CalculationError)?;
+    require!(now > stale_at, TrustPayError::DisputeNotStale);
+
+    let accounts = ctx.accounts;
+    let trust_pay_key = accounts.trust_pay.key();
+    let caller_key = accounts.caller.key();
+    let payer_info = accounts.payer.to_account_info();
+    let recipient_info = accounts.recipient.to_account_info();
+    let payer_token_account_info = accounts.payer_token_account.to_account_info();
+    let recipient_token_account_info = accounts.recipient_token_account.to_account_info();
+    let treasury_token_account_info = accounts.treasury_token_account.to_account_info();
+    let token_program_info = accounts.token_program.to_account_info();
+    let native_payout_info = accounts.native_payout.to_account_info();
+    let funder_info = accounts.caller.to_account_info();
+    let system_program_info = accounts.system_program.to_account_info();
+
+    emit!(crate::DisputeTimedOutEvent {
+        trust_pay: trust_pay_key,
+        milestone_index,
+        disputed_at,
+        max_resolution_time,
+        recipient_share_bps,
+        finalized_by: caller_key,
+        timed_out_at: now,
+    });
+
+    // Nobody ruled, so every bond goes back to the party that posted it. A stale contract
+    // dispute terminates the contract with the default share.
+    let resolution = if milestone_index == CONTRACT_DISPUTE_INDEX {
+        RESOLUTION_TERMINATE
+    } else {
+        RESOLUTION_SPLIT
+    };
+    settle_with_resolution(
+        DisputeSettlement {
+            payer: &payer_info,
+            recipient: &recipient_info,
+            mint: &accounts.mint,
+            trust_pay: &mut accounts.trust_pay,
+            dispute: &mut accounts.dispute,
+            vault: &mut accounts.vault,
+            payer_token_account: &payer_token_account_info,
+            recipient_token_account: &recipient_token_account_info,
+            arbitration_fee_account: None,
+            treasury: &mut accounts.treasury,
+            treasury_token_account: &treasury_token_account_info,
+            global_state: &mut accounts.global_state,
+            mint_stats: &mut accounts.mint_stats,
+            payer_reputation: &mut accounts.payer_reputation,
+            recipient_reputation: &mut accounts.recipient_reputation,
+            contract_review: &mut accounts.contract_review,
+            native_payout: &native_payout_info,
+            native_payout_bump: ctx.bumps.native_payout,
+            funder: &funder_info,
+            system_program: &system_program_info,
+            token_program: &token_program_info,
+            remaining_accounts: ctx.remaining_accounts,
+        },
+        milestone_index,
+        recipient_share_bps,
+        resolution,
+        DISPUTE_STATUS_TIMED_OUT,
+        caller_key,
+        "Dispute timed out, default outcome applied".to_string(),
+    )
+}
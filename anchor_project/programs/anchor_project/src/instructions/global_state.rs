@@ -1,9 +1,10 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::Mint;
 
-use crate::constants::ANCHOR_DISCRIMINATOR;
+use crate::constants::{ANCHOR_DISCRIMINATOR, MAX_PANEL_SIZE, PAUSE_ALL, RESOLVER_AUTHORITY};
 use crate::error::TrustPayError;
-use crate::state::GlobalState;
+use crate::fee_math::BASIS_POINTS;
+use crate::program::AnchorProject;
+use crate::state::{GlobalState, MintStats};
 
 #[derive(Accounts)]
 pub struct InitializeGlobalState<'info> {
@@ -18,34 +19,46 @@ pub struct InitializeGlobalState<'info> {
     )]
     pub global_state: Account<'info, GlobalState>,
 
-    //CHECK: token mint
-    #[account()]
-    pub mint: Account<'info, Mint>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, AnchorProject>,
+
+    // Only the upgrade authority or the hardcoded resolver can bootstrap the program
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+            || authority.key() == RESOLVER_AUTHORITY @ TrustPayError::UnauthorizedInitializer
+    )]
+    pub program_data: Account<'info, ProgramData>,
 
     pub system_program: Program<'info, System>,
 }
 
-pub fn initialize_global_state(ctx: Context<InitializeGlobalState>) -> Result<()> {
+pub(crate) fn initialize_global_state(ctx: Context<InitializeGlobalState>) -> Result<()> {
     let global_state = &mut ctx.accounts.global_state;
     global_state.authority = ctx.accounts.authority.key();
+    global_state.pending_authority = None;
     global_state.total_trust_pay_created = 0;
     global_state.total_trust_pay_closed = 0;
     global_state.total_confirmations = 0;
-    global_state.total_volume = 0;
-    global_state.high_watermark_volume = 0;
     global_state.last_volume_update = Clock::get()?.unix_timestamp;
-    global_state.token_decimals = ctx.accounts.mint.decimals;
+    // Panels are off until a threshold is set; default to 2-of-3 with three days to vote
+    global_state.panel_threshold = 0;
+    global_state.panel_size = 3;
+    global_state.panel_quorum = 2;
+    global_state.panel_vote_period = 3 * 24 * 60 * 60;
+    global_state.appeal_window = 0;
+    global_state.appeal_bond_bps = 0;
+    global_state.max_resolution_time = 0;
+    global_state.default_recipient_share_bps = 0;
     global_state.bump = ctx.bumps.global_state;
     msg!(
-        "Global state initialized with token decimals: {}",
-        global_state.token_decimals
+        "Global state initialized with authority: {}",
+        global_state.authority
     );
     Ok(())
 }
 
 #[derive(Accounts)]
-pub struct UpdateVolumeMetrics<'info> {
-    #[account(mut)]
+pub struct ProposeAuthority<'info> {
     pub authority: Signer<'info>,
 
     #[account(
@@ -55,41 +68,227 @@ pub struct UpdateVolumeMetrics<'info> {
         has_one = authority
     )]
     pub global_state: Account<'info, GlobalState>,
+}
 
-    pub system_program: Program<'info, System>,
+// First step of an authority transfer; the new authority must accept before it takes effect
+pub(crate) fn propose_authority(
+    ctx: Context<ProposeAuthority>,
+    new_authority: Pubkey,
+) -> Result<()> {
+    let global_state = &mut ctx.accounts.global_state;
+    global_state.pending_authority = Some(new_authority);
+
+    emit!(crate::AuthorityProposedEvent {
+        authority: global_state.authority,
+        pending_authority: new_authority,
+        proposed_at: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Authority transfer proposed to {}", new_authority);
+
+    Ok(())
 }
 
-pub fn update_volume_metrics(ctx: Context<UpdateVolumeMetrics>, volume: u64) -> Result<()> {
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    pub pending_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"global-state"],
+        bump = global_state.bump,
+        constraint = global_state.pending_authority == Some(pending_authority.key()) @ TrustPayError::NotPendingAuthority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+}
+
+pub(crate) fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
     let global_state = &mut ctx.accounts.global_state;
+    let previous_authority = global_state.authority;
+    global_state.authority = ctx.accounts.pending_authority.key();
+    global_state.pending_authority = None;
 
-    //update volume only if the new is higher than the previous volume
-    if volume > global_state.high_watermark_volume {
-        global_state.high_watermark_volume = volume;
-    }
+    emit!(crate::AuthorityTransferredEvent {
+        previous_authority,
+        new_authority: global_state.authority,
+        transferred_at: Clock::get()?.unix_timestamp,
+    });
 
-    global_state.last_volume_update = Clock::get()?.unix_timestamp;
+    msg!(
+        "Authority transferred from {} to {}",
+        previous_authority,
+        global_state.authority
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateDisputeConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"global-state"],
+        bump = global_state.bump,
+        has_one = authority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+}
+
+pub(crate) fn update_dispute_config(
+    ctx: Context<UpdateDisputeConfig>,
+    dispute_bond_bps: u16,
+    arbitration_fee_bps: u16,
+) -> Result<()> {
+    require!(
+        dispute_bond_bps as u64 <= BASIS_POINTS && arbitration_fee_bps as u64 <= BASIS_POINTS,
+        TrustPayError::InvalidBasisPoints
+    );
+
+    let global_state = &mut ctx.accounts.global_state;
+    global_state.dispute_bond_bps = dispute_bond_bps;
+    global_state.arbitration_fee_bps = arbitration_fee_bps;
+
+    msg!(
+        "Dispute config updated. Bond: {} bps, arbitration fee: {} bps",
+        dispute_bond_bps,
+        arbitration_fee_bps
+    );
+
+    Ok(())
+}
+
+// Quorum must be a strict majority so two resolutions can never both reach it
+pub(crate) fn update_panel_config(
+    ctx: Context<UpdateDisputeConfig>,
+    panel_threshold: u64,
+    panel_size: u8,
+    panel_quorum: u8,
+    panel_vote_period: i64,
+) -> Result<()> {
+    require!(
+        panel_size >= 1
+            && panel_size as usize <= MAX_PANEL_SIZE
+            && panel_quorum > panel_size / 2
+            && panel_quorum <= panel_size
+            && panel_vote_period > 0,
+        TrustPayError::InvalidPanelConfig
+    );
+
+    let global_state = &mut ctx.accounts.global_state;
+    global_state.panel_threshold = panel_threshold;
+    global_state.panel_size = panel_size;
+    global_state.panel_quorum = panel_quorum;
+    global_state.panel_vote_period = panel_vote_period;
 
     msg!(
-        "Volume metrics updated. New high watermark: token={}",
-        global_state.high_watermark_volume
+        "Panel config updated. Threshold: {}, quorum: {} of {}, voting period: {}s",
+        panel_threshold,
+        panel_quorum,
+        panel_size,
+        panel_vote_period
     );
 
     Ok(())
 }
 
-// Update the global state on approval of payments
+pub(crate) fn update_appeal_config(
+    ctx: Context<UpdateDisputeConfig>,
+    appeal_window: i64,
+    appeal_bond_bps: u16,
+) -> Result<()> {
+    require!(
+        appeal_window >= 0 && appeal_bond_bps as u64 <= BASIS_POINTS,
+        TrustPayError::InvalidAppealConfig
+    );
+
+    let global_state = &mut ctx.accounts.global_state;
+    global_state.appeal_window = appeal_window;
+    global_state.appeal_bond_bps = appeal_bond_bps;
+
+    msg!(
+        "Appeal config updated. Window: {}s, appeal bond: {} bps",
+        appeal_window,
+        appeal_bond_bps
+    );
+
+    Ok(())
+}
+
+pub(crate) fn update_timeout_config(
+    ctx: Context<UpdateDisputeConfig>,
+    max_resolution_time: i64,
+    default_recipient_share_bps: u16,
+) -> Result<()> {
+    require!(
+        max_resolution_time >= 0 && default_recipient_share_bps as u64 <= BASIS_POINTS,
+        TrustPayError::InvalidTimeoutConfig
+    );
+
+    let global_state = &mut ctx.accounts.global_state;
+    global_state.max_resolution_time = max_resolution_time;
+    global_state.default_recipient_share_bps = default_recipient_share_bps;
+
+    msg!(
+        "Timeout config updated. Max resolution time: {}s, default outcome: {} bps to recipient",
+        max_resolution_time,
+        default_recipient_share_bps
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetPause<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"global-state"],
+        bump = global_state.bump,
+        has_one = authority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+}
+
+// Cancellations, declines and refunds to fund owners are never paused
+pub(crate) fn set_pause(ctx: Context<SetPause>, pause_flags: u8, paused_until: i64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        pause_flags & !PAUSE_ALL == 0 && (paused_until == 0 || paused_until > now),
+        TrustPayError::InvalidPauseConfig
+    );
+
+    let global_state = &mut ctx.accounts.global_state;
+    global_state.pause_flags = pause_flags;
+    global_state.paused_until = paused_until;
+
+    emit!(crate::PauseUpdatedEvent {
+        authority: global_state.authority,
+        pause_flags,
+        paused_until,
+        updated_at: now,
+    });
+
+    msg!(
+        "Pause flags set to {:#06b}, auto-unpause at {}",
+        pause_flags,
+        paused_until
+    );
+
+    Ok(())
+}
+
+// Update the global state on approval of payments; volume is only meaningful per mint
 pub fn update_on_payment_approval(
     global_state: &mut Account<'_, GlobalState>,
+    mint_stats: &mut MintStats,
     token_amount: u64,
 ) -> Result<()> {
     // Increase the number
     global_state.total_confirmations += 1;
-    global_state.total_volume += token_amount;
-
-    // update the volume
-    if global_state.total_volume > global_state.high_watermark_volume {
-        global_state.high_watermark_volume = global_state.total_volume;
-    }
+    mint_stats.record_volume(token_amount)?;
 
     // Update the time
     global_state.last_volume_update = Clock::get()?.unix_timestamp;
@@ -107,31 +306,3 @@ pub fn update_on_trust_pay_close(global_state: &mut Account<'_, GlobalState>) ->
 
     Ok(())
 }
-
-pub fn update_on_fee_collection(global_state: &mut GlobalState, fee_amount: u64) -> Result<()> {
-    global_state.total_fees_collected = global_state
-        .total_fees_collected
-        .checked_add(fee_amount)
-        .ok_or(TrustPayError::CalculationError)?;
-    Ok(())
-}
-// Function to handle refund
-pub fn handle_trust_pay_refund(global_state: &mut Account<'_, GlobalState>) -> Result<()> {
-    update_on_trust_pay_close(global_state)
-}
-
-// Function to ensure high watermark is captured during any global state operation
-pub fn ensure_high_watermark_preserved(global_state: &mut Account<'_, GlobalState>) -> Result<()> {
-    if global_state.total_volume < global_state.high_watermark_volume {
-        msg!(
-            "Warning: Total volume ({}) is less than high watermark ({})",
-            global_state.total_volume,
-            global_state.high_watermark_volume
-        );
-    }
-
-    // Update timestamp
-    global_state.last_volume_update = Clock::get()?.unix_timestamp;
-
-    Ok(())
-}
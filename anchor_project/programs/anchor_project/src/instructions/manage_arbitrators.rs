@@ -0,0 +1,122 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::error::TrustPayError;
+use crate::state::{Arbitrator, GlobalState};
+
+#[derive(Accounts)]
+#[instruction(arbitrator: Pubkey)]
+pub struct RegisterArbitrator<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"global-state"],
+        bump = global_state.bump,
+        has_one = authority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = ANCHOR_DISCRIMINATOR + Arbitrator::INIT_SPACE,
+        seeds = [b"arbitrator", arbitrator.as_ref()],
+        bump
+    )]
+    pub arbitrator_account: Account<'info, Arbitrator>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateArbitrator<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"global-state"],
+        bump = global_state.bump,
+        has_one = authority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"arbitrator", arbitrator_account.arbitrator.as_ref()],
+        bump = arbitrator_account.bump
+    )]
+    pub arbitrator_account: Account<'info, Arbitrator>,
+}
+
+fn validate_specialties(specialties: &[String]) -> Result<()> {
+    require!(
+        specialties.len() <= MAX_SPECIALTIES,
+        TrustPayError::InvalidSpecialties
+    );
+    require!(
+        specialties
+            .iter()
+            .all(|s| !s.is_empty() && s.len() <= MAX_SPECIALTY_LEN),
+        TrustPayError::InvalidSpecialties
+    );
+    Ok(())
+}
+
+pub(crate) fn register_arbitrator(
+    ctx: Context<RegisterArbitrator>,
+    arbitrator: Pubkey,
+    specialties: Vec<String>,
+) -> Result<()> {
+    validate_specialties(&specialties)?;
+
+    let registered_at = Clock::get()?.unix_timestamp;
+    ctx.accounts.arbitrator_account.set_inner(Arbitrator {
+        arbitrator,
+        active: true,
+        specialties: specialties.clone(),
+        cases_assigned: 0,
+        cases_resolved: 0,
+        total_resolution_time: 0,
+        average_resolution_time: 0,
+        registered_at,
+        bump: ctx.bumps.arbitrator_account,
+    });
+
+    emit!(crate::ArbitratorUpdatedEvent {
+        arbitrator,
+        active: true,
+        specialties,
+        updated_at: registered_at,
+    });
+
+    msg!("Arbitrator {} registered", arbitrator);
+
+    Ok(())
+}
+
+pub(crate) fn update_arbitrator(
+    ctx: Context<UpdateArbitrator>,
+    active: bool,
+    specialties: Vec<String>,
+) -> Result<()> {
+    validate_specialties(&specialties)?;
+
+    let arbitrator_account = &mut ctx.accounts.arbitrator_account;
+    arbitrator_account.active = active;
+    arbitrator_account.specialties = specialties.clone();
+
+    emit!(crate::ArbitratorUpdatedEvent {
+        arbitrator: arbitrator_account.arbitrator,
+        active,
+        specialties,
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "Arbitrator {} updated, active: {}",
+        arbitrator_account.arbitrator,
+        active
+    );
+
+    Ok(())
+}
@@ -0,0 +1,149 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::constants::*;
+use crate::error::TrustPayError;
+use crate::instructions::shared::*;
+use crate::state::{GlobalState, MintStats, TrustPay};
+
+#[derive(Accounts)]
+pub struct MatchDisputeBond<'info> {
+    #[account(mut)]
+    pub counterparty: Signer<'info>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    // Native SOL parties may have closed their wSOL account after depositing
+    #[account(
+        init_if_needed,
+        payer = counterparty,
+        associated_token::mint = mint,
+        associated_token::authority = counterparty,
+        associated_token::token_program = token_program
+    )]
+    pub counterparty_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = mint,
+        seeds = [b"trust-pay", trust_pay.payer.key().as_ref(), trust_pay.seed.to_le_bytes().as_ref()],
+        bump = trust_pay.bump,
+        constraint = trust_pay.has_active_disputes() @ TrustPayError::ContractNotDisputed
+    )]
+    pub trust_pay: Account<'info, TrustPay>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = trust_pay,
+        associated_token::token_program = token_program
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [b"global-state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"mint-stats", mint.key().as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Box<Account<'info, MintStats>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+pub(crate) fn match_dispute_bond<'info>(
+    ctx: Context<'_, '_, 'info, 'info, MatchDisputeBond<'info>>,
+    milestone_index: u8,
+) -> Result<()> {
+    let trust_pay_key = ctx.accounts.trust_pay.key();
+    let counterparty_key = ctx.accounts.counterparty.key();
+
+    require!(
+        !ctx.accounts
+            .global_state
+            .is_paused(PAUSE_NEW_CONTRACTS, Clock::get()?.unix_timestamp),
+        TrustPayError::OperationPaused
+    );
+
+    // Validate the milestone, or the whole contract, is under dispute
+    let bond_amount;
+    {
+        let trust_pay = &ctx.accounts.trust_pay;
+        let milestone = trust_pay.dispute_state(milestone_index)?;
+        require!(
+            milestone.status == MILESTONE_STATUS_DISPUTED,
+            TrustPayError::MilestoneNotDisputed
+        );
+
+        // Only the party on the other side of the dispute can match the bond
+        require!(
+            (counterparty_key == trust_pay.payer || counterparty_key == trust_pay.recipient)
+                && milestone.disputer != Some(counterparty_key),
+            TrustPayError::UnauthorizedBondMatch
+        );
+        require!(milestone.dispute_bond > 0, TrustPayError::NoBondToMatch);
+        require!(
+            milestone.counter_bond == 0,
+            TrustPayError::BondAlreadyMatched
+        );
+
+        bond_amount = milestone.dispute_bond;
+    }
+
+    // Wrap the bond first when the contract is in native SOL
+    wrap_native_deposit(
+        &ctx.accounts.counterparty_token_account,
+        bond_amount,
+        &ctx.accounts.counterparty,
+        &ctx.accounts.system_program,
+        &ctx.accounts.token_program,
+    )?;
+    transfer_tokens(
+        &ctx.accounts.counterparty_token_account,
+        &ctx.accounts.vault,
+        &bond_amount,
+        &ctx.accounts.mint,
+        &ctx.accounts.counterparty,
+        &ctx.accounts.token_program,
+        ctx.remaining_accounts,
+    )?;
+    close_empty_native_account(
+        &mut ctx.accounts.counterparty_token_account,
+        &ctx.accounts.counterparty,
+        &ctx.accounts.token_program,
+    )?;
+    ctx.accounts.mint_stats.record_deposit(bond_amount)?;
+
+    ctx.accounts
+        .trust_pay
+        .dispute_state_mut(milestone_index)?
+        .counter_bond = bond_amount;
+
+    emit!(crate::DisputeBondMatchedEvent {
+        trust_pay: trust_pay_key,
+        milestone_index,
+        counterparty: counterparty_key,
+        bond_amount,
+        matched_at: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "Dispute bond of {} matched by {} on milestone {}",
+        bond_amount,
+        counterparty_key,
+        milestone_index
+    );
+
+    Ok(())
+}
@@ -22,8 +22,53 @@ pub use approve_payment::*;
 pub mod dispute_contract;
 pub use dispute_contract::*;
 
+pub mod settle_dispute;
+pub use settle_dispute::*;
+
 pub mod resolve_dispute;
 pub use resolve_dispute::*;
 
 pub mod decline_contract;
 pub use decline_contract::*;
+
+pub mod withdraw_fees;
+pub use withdraw_fees::*;
+
+pub mod set_fee_caps;
+pub use set_fee_caps::*;
+
+pub mod match_dispute_bond;
+pub use match_dispute_bond::*;
+
+pub mod manage_arbitrators;
+pub use manage_arbitrators::*;
+
+pub mod assign_dispute;
+pub use assign_dispute::*;
+
+pub mod panel_voting;
+pub use panel_voting::*;
+
+pub mod appeal_dispute;
+pub use appeal_dispute::*;
+
+pub mod submit_evidence;
+pub use submit_evidence::*;
+
+pub mod respond_to_dispute;
+pub use respond_to_dispute::*;
+
+pub mod negotiate_settlement;
+pub use negotiate_settlement::*;
+
+pub mod finalize_stale_dispute;
+pub use finalize_stale_dispute::*;
+
+pub mod withdraw_dispute;
+pub use withdraw_dispute::*;
+
+pub mod submit_review;
+pub use submit_review::*;
+
+pub mod configure_mint;
+pub use configure_mint::*;
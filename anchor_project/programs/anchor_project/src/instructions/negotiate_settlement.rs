@@ -0,0 +1,306 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::constants::*;
+use crate::error::TrustPayError;
+use crate::fee_math::BASIS_POINTS;
+use crate::instructions::settle_dispute::*;
+use crate::state::{ContractReview, Dispute, GlobalState, MintStats, Reputation, Treasury, TrustPay};
+
+#[derive(Accounts)]
+pub struct OfferSettlement<'info> {
+    pub party: Signer<'info>,
+
+    #[account(
+        seeds = [b"global-state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"trust-pay", trust_pay.payer.key().as_ref(), trust_pay.seed.to_le_bytes().as_ref()],
+        bump = trust_pay.bump,
+        constraint = trust_pay.has_active_disputes() @ TrustPayError::ContractNotDisputed
+    )]
+    pub trust_pay: Account<'info, TrustPay>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptSettlement<'info> {
+    #[account(mut)]
+    pub party: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        has_one = payer,
+        has_one = recipient,
+        has_one = mint,
+        seeds = [b"trust-pay", payer.key().as_ref(), trust_pay.seed.to_le_bytes().as_ref()],
+        bump = trust_pay.bump,
+        constraint = trust_pay.has_active_disputes() @ TrustPayError::ContractNotDisputed
+    )]
+    pub trust_pay: Account<'info, TrustPay>,
+
+    #[account(
+        mut,
+        has_one = trust_pay @ TrustPayError::DisputeMismatch,
+        seeds = [b"dispute", dispute.dispute_id.to_le_bytes().as_ref()],
+        bump = dispute.bump
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = trust_pay,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: temporary wSOL account for a native SOL payout, created and closed in this instruction
+    #[account(
+        mut,
+        seeds = [b"native-payout", trust_pay.key().as_ref()],
+        bump
+    )]
+    pub native_payout: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = party,
+        associated_token::mint = mint,
+        associated_token::authority = payer,
+        associated_token::token_program = token_program,
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = party,
+        associated_token::mint = mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        has_one = mint,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump = treasury.bump,
+        constraint = treasury.key() == trust_pay.fee_destination @ TrustPayError::InvalidFeeDestination
+    )]
+    pub treasury: Box<Account<'info, Treasury>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program,
+    )]
+    pub treasury_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"global-state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"reputation", payer.key().as_ref()],
+        bump = payer_reputation.bump
+    )]
+    pub payer_reputation: Box<Account<'info, Reputation>>,
+
+    #[account(
+        mut,
+        seeds = [b"reputation", recipient.key().as_ref()],
+        bump = recipient_reputation.bump
+    )]
+    pub recipient_reputation: Box<Account<'info, Reputation>>,
+
+    #[account(
+        mut,
+        seeds = [b"review", trust_pay.key().as_ref()],
+        bump = contract_review.bump
+    )]
+    pub contract_review: Box<Account<'info, ContractReview>>,
+
+    #[account(
+        mut,
+        seeds = [b"mint-stats", mint.key().as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Box<Account<'info, MintStats>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+// The role of a party to the contract
+fn party_role(trust_pay: &TrustPay, party: Pubkey) -> Result<u8> {
+    if party == trust_pay.payer {
+        Ok(ROLE_PAYER)
+    } else if party == trust_pay.recipient {
+        Ok(ROLE_RECIPIENT)
+    } else {
+        err!(TrustPayError::UnauthorizedSettlement)
+    }
+}
+
+// Either party proposes a split; a new offer replaces any standing one
+pub(crate) fn offer_settlement(
+    ctx: Context<OfferSettlement>,
+    milestone_index: u8,
+    recipient_share_bps: u16,
+) -> Result<()> {
+    let trust_pay_key = ctx.accounts.trust_pay.key();
+    let party_key = ctx.accounts.party.key();
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        !ctx.accounts
+            .global_state
+            .is_paused(PAUSE_DISPUTE_RESOLUTION, now),
+        TrustPayError::OperationPaused
+    );
+    require!(
+        recipient_share_bps as u64 <= BASIS_POINTS,
+        TrustPayError::InvalidResolution
+    );
+
+    // On a contract dispute the offer is the recipient's share of terminating the contract
+    let trust_pay = &mut ctx.accounts.trust_pay;
+    let role = party_role(trust_pay, party_key)?;
+    let milestone = trust_pay.dispute_state_mut(milestone_index)?;
+    require!(
+        milestone.status == MILESTONE_STATUS_DISPUTED,
+        TrustPayError::MilestoneNotDisputed
+    );
+    milestone.settlement_offer = Some(recipient_share_bps);
+    milestone.settlement_offered_by = role;
+
+    emit!(crate::SettlementOfferedEvent {
+        trust_pay: trust_pay_key,
+        milestone_index,
+        offered_by: party_key,
+        recipient_share_bps,
+        offered_at: now,
+    });
+
+    msg!(
+        "Settlement offered on milestone {}: {} bps to recipient",
+        milestone_index,
+        recipient_share_bps
+    );
+
+    Ok(())
+}
+
+// The other party accepts the standing offer, which settles the dispute without a resolver.
+// The accepted share is passed in so a replaced offer cannot be accepted by mistake.
+pub(crate) fn accept_settlement<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AcceptSettlement<'info>>,
+    milestone_index: u8,
+    recipient_share_bps: u16,
+) -> Result<()> {
+    let party_key = ctx.accounts.party.key();
+
+    let role = party_role(&ctx.accounts.trust_pay, party_key)?;
+    let milestone = ctx.accounts.trust_pay.dispute_state(milestone_index)?;
+    let offer = milestone
+        .settlement_offer
+        .ok_or(TrustPayError::NoSettlementOffer)?;
+    require!(
+        milestone.settlement_offered_by != role,
+        TrustPayError::UnauthorizedSettlement
+    );
+    require!(
+        offer == recipient_share_bps,
+        TrustPayError::SettlementOfferChanged
+    );
+
+    require!(
+        !ctx.accounts
+            .global_state
+            .is_paused(PAUSE_DISPUTE_RESOLUTION, Clock::get()?.unix_timestamp),
+        TrustPayError::OperationPaused
+    );
+
+    let accounts = ctx.accounts;
+    let trust_pay_key = accounts.trust_pay.key();
+    let payer_info = accounts.payer.to_account_info();
+    let recipient_info = accounts.recipient.to_account_info();
+    let payer_token_account_info = accounts.payer_token_account.to_account_info();
+    let recipient_token_account_info = accounts.recipient_token_account.to_account_info();
+    let treasury_token_account_info = accounts.treasury_token_account.to_account_info();
+    let token_program_info = accounts.token_program.to_account_info();
+    let native_payout_info = accounts.native_payout.to_account_info();
+    let funder_info = accounts.party.to_account_info();
+    let system_program_info = accounts.system_program.to_account_info();
+
+    // A contract dispute settles by terminating the contract with the agreed share
+    let resolution = if milestone_index == CONTRACT_DISPUTE_INDEX {
+        RESOLUTION_TERMINATE
+    } else {
+        RESOLUTION_SPLIT
+    };
+
+    settle_with_resolution(
+        DisputeSettlement {
+            payer: &payer_info,
+            recipient: &recipient_info,
+            mint: &accounts.mint,
+            trust_pay: &mut accounts.trust_pay,
+            dispute: &mut accounts.dispute,
+            vault: &mut accounts.vault,
+            payer_token_account: &payer_token_account_info,
+            recipient_token_account: &recipient_token_account_info,
+            arbitration_fee_account: None,
+            treasury: &mut accounts.treasury,
+            treasury_token_account: &treasury_token_account_info,
+            global_state: &mut accounts.global_state,
+            mint_stats: &mut accounts.mint_stats,
+            payer_reputation: &mut accounts.payer_reputation,
+            recipient_reputation: &mut accounts.recipient_reputation,
+            contract_review: &mut accounts.contract_review,
+            native_payout: &native_payout_info,
+            native_payout_bump: ctx.bumps.native_payout,
+            funder: &funder_info,
+            system_program: &system_program_info,
+            token_program: &token_program_info,
+            remaining_accounts: ctx.remaining_accounts,
+        },
+        milestone_index,
+        recipient_share_bps,
+        resolution,
+        DISPUTE_STATUS_SETTLED,
+        party_key,
+        "Settled by agreement of the parties".to_string(),
+    )?;
+
+    emit!(crate::SettlementAcceptedEvent {
+        trust_pay: trust_pay_key,
+        milestone_index,
+        accepted_by: party_key,
+        recipient_share_bps,
+        accepted_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
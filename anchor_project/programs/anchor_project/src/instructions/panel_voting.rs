@@ -0,0 +1,633 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::constants::*;
+use crate::error::TrustPayError;
+use crate::fee_math::BASIS_POINTS;
+use crate::instructions::appeal_dispute::record_ruling;
+use crate::instructions::settle_dispute::*;
+use crate::state::{
+    Arbitrator, ContractReview, Dispute, DisputePanel, GlobalState, MintStats, Reputation, Treasury, TrustPay,
+};
+
+#[derive(Accounts)]
+#[instruction(milestone_index: u8)]
+pub struct OpenPanel<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"global-state"],
+        bump = global_state.bump,
+        has_one = authority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"trust-pay", trust_pay.payer.key().as_ref(), trust_pay.seed.to_le_bytes().as_ref()],
+        bump = trust_pay.bump,
+        constraint = trust_pay.has_active_disputes() @ TrustPayError::ContractNotDisputed
+    )]
+    pub trust_pay: Account<'info, TrustPay>,
+
+    #[account(
+        has_one = trust_pay @ TrustPayError::DisputeMismatch,
+        seeds = [b"dispute", dispute.dispute_id.to_le_bytes().as_ref()],
+        bump = dispute.bump
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    // One panel per dispute, so a contract can be re-disputed after a panel ruled
+    #[account(
+        init,
+        payer = authority,
+        space = ANCHOR_DISCRIMINATOR + DisputePanel::INIT_SPACE,
+        seeds = [b"panel", dispute.key().as_ref()],
+        bump
+    )]
+    pub panel: Account<'info, DisputePanel>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(milestone_index: u8)]
+pub struct CastPanelVote<'info> {
+    pub arbitrator: Signer<'info>,
+
+    #[account(
+        seeds = [b"global-state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        seeds = [b"trust-pay", trust_pay.payer.key().as_ref(), trust_pay.seed.to_le_bytes().as_ref()],
+        bump = trust_pay.bump,
+    )]
+    pub trust_pay: Account<'info, TrustPay>,
+
+    #[account(
+        has_one = trust_pay @ TrustPayError::DisputeMismatch,
+        seeds = [b"dispute", dispute.dispute_id.to_le_bytes().as_ref()],
+        bump = dispute.bump,
+        constraint = dispute.milestone_index == milestone_index @ TrustPayError::DisputeMismatch
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    #[account(
+        mut,
+        has_one = trust_pay,
+        seeds = [b"panel", dispute.key().as_ref()],
+        bump = panel.bump
+    )]
+    pub panel: Account<'info, DisputePanel>,
+}
+
+#[derive(Accounts)]
+#[instruction(milestone_index: u8)]
+pub struct ReassignPanel<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"global-state"],
+        bump = global_state.bump,
+        has_one = authority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"trust-pay", trust_pay.payer.key().as_ref(), trust_pay.seed.to_le_bytes().as_ref()],
+        bump = trust_pay.bump,
+    )]
+    pub trust_pay: Account<'info, TrustPay>,
+
+    #[account(
+        has_one = trust_pay @ TrustPayError::DisputeMismatch,
+        seeds = [b"dispute", dispute.dispute_id.to_le_bytes().as_ref()],
+        bump = dispute.bump,
+        constraint = dispute.milestone_index == milestone_index @ TrustPayError::DisputeMismatch
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    #[account(
+        mut,
+        has_one = trust_pay,
+        seeds = [b"panel", dispute.key().as_ref()],
+        bump = panel.bump
+    )]
+    pub panel: Account<'info, DisputePanel>,
+}
+
+#[derive(Accounts)]
+#[instruction(milestone_index: u8)]
+pub struct ExecutePanelDecision<'info> {
+    #[account(mut)]
+    pub executor: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        has_one = payer,
+        has_one = recipient,
+        has_one = mint,
+        seeds = [b"trust-pay", payer.key().as_ref(), trust_pay.seed.to_le_bytes().as_ref()],
+        bump = trust_pay.bump,
+        constraint = trust_pay.has_active_disputes() @ TrustPayError::ContractNotDisputed
+    )]
+    pub trust_pay: Account<'info, TrustPay>,
+
+    #[account(
+        mut,
+        has_one = trust_pay @ TrustPayError::DisputeMismatch,
+        seeds = [b"dispute", dispute.dispute_id.to_le_bytes().as_ref()],
+        bump = dispute.bump
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = trust_pay,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: temporary wSOL account for a native SOL payout, created and closed in this instruction
+    #[account(
+        mut,
+        seeds = [b"native-payout", trust_pay.key().as_ref()],
+        bump
+    )]
+    pub native_payout: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = executor,
+        associated_token::mint = mint,
+        associated_token::authority = payer,
+        associated_token::token_program = token_program,
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = executor,
+        associated_token::mint = mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        has_one = mint,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump = treasury.bump,
+        constraint = treasury.key() == trust_pay.fee_destination @ TrustPayError::InvalidFeeDestination
+    )]
+    pub treasury: Box<Account<'info, Treasury>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program,
+    )]
+    pub treasury_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"global-state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"reputation", payer.key().as_ref()],
+        bump = payer_reputation.bump
+    )]
+    pub payer_reputation: Box<Account<'info, Reputation>>,
+
+    #[account(
+        mut,
+        seeds = [b"reputation", recipient.key().as_ref()],
+        bump = recipient_reputation.bump
+    )]
+    pub recipient_reputation: Box<Account<'info, Reputation>>,
+
+    #[account(
+        mut,
+        seeds = [b"review", trust_pay.key().as_ref()],
+        bump = contract_review.bump
+    )]
+    pub contract_review: Box<Account<'info, ContractReview>>,
+
+    #[account(
+        mut,
+        seeds = [b"mint-stats", mint.key().as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Box<Account<'info, MintStats>>,
+
+    #[account(
+        mut,
+        has_one = trust_pay,
+        seeds = [b"panel", dispute.key().as_ref()],
+        bump = panel.bump,
+        constraint = panel.status == PANEL_STATUS_DECIDED @ TrustPayError::PanelNotDecided
+    )]
+    pub panel: Box<Account<'info, DisputePanel>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+// Members must be distinct active arbitrators from outside the contract. Their registry
+// accounts are passed as remaining accounts in member order and count the new case.
+fn assign_members<'info>(
+    members: &[Pubkey],
+    trust_pay: &TrustPay,
+    panel_size: u8,
+    arbitrator_accounts: &'info [AccountInfo<'info>],
+) -> Result<()> {
+    require!(
+        members.len() == panel_size as usize && arbitrator_accounts.len() == members.len(),
+        TrustPayError::InvalidPanelMembers
+    );
+
+    for (i, (member, account_info)) in members.iter().zip(arbitrator_accounts).enumerate() {
+        require!(
+            !members[..i].contains(member)
+                && *member != trust_pay.payer
+                && *member != trust_pay.recipient,
+            TrustPayError::InvalidPanelMembers
+        );
+
+        let mut arbitrator = Account::<Arbitrator>::try_from(account_info)?;
+        require!(
+            arbitrator.arbitrator == *member,
+            TrustPayError::InvalidPanelMembers
+        );
+        require!(arbitrator.active, TrustPayError::ArbitratorInactive);
+
+        arbitrator.cases_assigned = arbitrator
+            .cases_assigned
+            .checked_add(1)
+            .ok_or(TrustPayError::CalculationError)?;
+        arbitrator.exit(&crate::ID)?;
+    }
+
+    Ok(())
+}
+
+// Count the settled case for each member whose vote formed the decision. Their registry
+// accounts lead the remaining accounts in member order; the rest are transfer hook accounts.
+pub fn record_panel_resolution<'info>(
+    panel: &DisputePanel,
+    resolution_time: i64,
+    remaining_accounts: &'info [AccountInfo<'info>],
+) -> Result<&'info [AccountInfo<'info>]> {
+    require!(
+        remaining_accounts.len() >= panel.members.len(),
+        TrustPayError::ArbitratorMismatch
+    );
+    let (arbitrator_accounts, hook_accounts) = remaining_accounts.split_at(panel.members.len());
+
+    for ((member, vote), account_info) in panel
+        .members
+        .iter()
+        .zip(&panel.votes)
+        .zip(arbitrator_accounts)
+    {
+        let mut arbitrator = Account::<Arbitrator>::try_from(account_info)?;
+        require!(
+            arbitrator.arbitrator == *member,
+            TrustPayError::ArbitratorMismatch
+        );
+        if *vote == panel.decision {
+            arbitrator.record_resolution(resolution_time)?;
+            arbitrator.exit(&crate::ID)?;
+        }
+    }
+
+    Ok(hook_accounts)
+}
+
+// The recipient share and resolution a panel decision settles with. A milestone panel splits the
+// milestone; a contract panel terminates the contract with its share, or dismisses the dispute.
+fn panel_resolution(milestone_index: u8, decision: u16) -> (u16, u8) {
+    match (milestone_index, decision) {
+        (CONTRACT_DISPUTE_INDEX, DISMISS_VOTE) => (0, RESOLUTION_CONTINUE),
+        (CONTRACT_DISPUTE_INDEX, _) => (decision, RESOLUTION_TERMINATE),
+        _ => (decision, RESOLUTION_SPLIT),
+    }
+}
+
+pub(crate) fn open_panel<'info>(
+    ctx: Context<'_, '_, 'info, 'info, OpenPanel<'info>>,
+    milestone_index: u8,
+    members: Vec<Pubkey>,
+) -> Result<()> {
+    let trust_pay = &ctx.accounts.trust_pay;
+    let global_state = &ctx.accounts.global_state;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        !global_state.is_paused(PAUSE_DISPUTE_RESOLUTION, now),
+        TrustPayError::OperationPaused
+    );
+
+    // Validate the milestone, or the whole contract, is under this dispute
+    trust_pay.require_open_dispute(milestone_index, ctx.accounts.dispute.dispute_id)?;
+    require!(
+        trust_pay.dispute_state(milestone_index)?.panel_required,
+        TrustPayError::PanelNotRequired
+    );
+
+    assign_members(
+        &members,
+        trust_pay,
+        global_state.panel_size,
+        ctx.remaining_accounts,
+    )?;
+
+    let panel = &mut ctx.accounts.panel;
+    panel.trust_pay = trust_pay.key();
+    panel.milestone_index = milestone_index;
+    panel.votes = vec![None; members.len()];
+    panel.members = members;
+    panel.quorum = global_state.panel_quorum;
+    panel.round = 0;
+    panel.opened_at = now;
+    panel.voting_deadline = now
+        .checked_add(global_state.panel_vote_period)
+        .ok_or(TrustPayError::CalculationError)?;
+    panel.status = PANEL_STATUS_VOTING;
+    panel.decision = None;
+    panel.bump = ctx.bumps.panel;
+
+    // Seating a panel restarts the dispute timeout
+    ctx.accounts
+        .trust_pay
+        .dispute_state_mut(milestone_index)?
+        .escalated_at = Some(now);
+
+    emit!(crate::PanelOpenedEvent {
+        trust_pay: panel.trust_pay,
+        milestone_index,
+        members: panel.members.clone(),
+        quorum: panel.quorum,
+        round: panel.round,
+        voting_deadline: panel.voting_deadline,
+        opened_at: now,
+    });
+
+    msg!(
+        "Panel of {} opened on milestone {}, quorum {}",
+        panel.members.len(),
+        milestone_index,
+        panel.quorum
+    );
+
+    Ok(())
+}
+
+pub(crate) fn cast_panel_vote(
+    ctx: Context<CastPanelVote>,
+    milestone_index: u8,
+    recipient_share_bps: u16,
+) -> Result<()> {
+    let arbitrator_key = ctx.accounts.arbitrator.key();
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        !ctx.accounts
+            .global_state
+            .is_paused(PAUSE_DISPUTE_RESOLUTION, now),
+        TrustPayError::OperationPaused
+    );
+    let panel = &mut ctx.accounts.panel;
+
+    // A contract panel may also vote to dismiss the dispute
+    require!(
+        recipient_share_bps as u64 <= BASIS_POINTS
+            || (recipient_share_bps == DISMISS_VOTE
+                && milestone_index == CONTRACT_DISPUTE_INDEX),
+        TrustPayError::InvalidResolution
+    );
+    require!(
+        panel.status == PANEL_STATUS_VOTING && now <= panel.voting_deadline,
+        TrustPayError::PanelVotingClosed
+    );
+
+    let seat = panel
+        .members
+        .iter()
+        .position(|m| *m == arbitrator_key)
+        .ok_or(TrustPayError::NotPanelMember)?;
+    require!(panel.votes[seat].is_none(), TrustPayError::AlreadyVoted);
+    panel.votes[seat] = Some(recipient_share_bps);
+
+    emit!(crate::PanelVoteCastEvent {
+        trust_pay: panel.trust_pay,
+        milestone_index,
+        arbitrator: arbitrator_key,
+        recipient_share_bps,
+        round: panel.round,
+        voted_at: now,
+    });
+
+    // The vote that completes a quorum decides the dispute
+    if let Some(decision) = panel.tally() {
+        panel.status = PANEL_STATUS_DECIDED;
+        panel.decision = Some(decision);
+
+        emit!(crate::PanelDecidedEvent {
+            trust_pay: panel.trust_pay,
+            milestone_index,
+            decision,
+            round: panel.round,
+            decided_at: now,
+        });
+    }
+
+    msg!(
+        "Panel vote cast by {} on milestone {}: {} bps to recipient",
+        arbitrator_key,
+        milestone_index,
+        recipient_share_bps
+    );
+
+    Ok(())
+}
+
+// Replace a panel that timed out or can no longer reach quorum; votes start over
+pub(crate) fn reassign_panel<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ReassignPanel<'info>>,
+    milestone_index: u8,
+    members: Vec<Pubkey>,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let global_state = &ctx.accounts.global_state;
+    require!(
+        !global_state.is_paused(PAUSE_DISPUTE_RESOLUTION, now),
+        TrustPayError::OperationPaused
+    );
+    require!(
+        ctx.accounts.panel.is_deadlocked(now),
+        TrustPayError::PanelNotDeadlocked
+    );
+
+    assign_members(
+        &members,
+        &ctx.accounts.trust_pay,
+        global_state.panel_size,
+        ctx.remaining_accounts,
+    )?;
+
+    let panel = &mut ctx.accounts.panel;
+    panel.votes = vec![None; members.len()];
+    panel.members = members;
+    panel.quorum = global_state.panel_quorum;
+    panel.round = panel
+        .round
+        .checked_add(1)
+        .ok_or(TrustPayError::CalculationError)?;
+    panel.voting_deadline = now
+        .checked_add(global_state.panel_vote_period)
+        .ok_or(TrustPayError::CalculationError)?;
+
+    ctx.accounts
+        .trust_pay
+        .dispute_state_mut(milestone_index)?
+        .escalated_at = Some(now);
+
+    emit!(crate::PanelOpenedEvent {
+        trust_pay: panel.trust_pay,
+        milestone_index,
+        members: panel.members.clone(),
+        quorum: panel.quorum,
+        round: panel.round,
+        voting_deadline: panel.voting_deadline,
+        opened_at: now,
+    });
+
+    msg!(
+        "Panel on milestone {} reassigned, round {}",
+        milestone_index,
+        panel.round
+    );
+
+    Ok(())
+}
+
+// Anyone can execute a decided panel; the arbitration fee goes to the treasury
+pub(crate) fn execute_panel_decision<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ExecutePanelDecision<'info>>,
+    milestone_index: u8,
+) -> Result<()> {
+    require!(
+        !ctx.accounts
+            .global_state
+            .is_paused(PAUSE_DISPUTE_RESOLUTION, Clock::get()?.unix_timestamp),
+        TrustPayError::OperationPaused
+    );
+
+    let milestone = ctx.accounts.trust_pay.dispute_state(milestone_index)?;
+    require!(milestone.panel_required, TrustPayError::PanelNotRequired);
+    require!(milestone.ruling.is_none(), TrustPayError::RulingPending);
+    let on_appeal = milestone.appeal_level > 0;
+
+    let accounts = ctx.accounts;
+    let decision = accounts
+        .panel
+        .decision
+        .ok_or(TrustPayError::PanelNotDecided)?;
+    accounts.panel.status = PANEL_STATUS_EXECUTED;
+    let (recipient_share_bps, resolution) = panel_resolution(milestone_index, decision);
+
+    // A first-instance decision becomes a ruling that waits out the appeal window
+    let appeal_window = accounts.global_state.appeal_window;
+    if appeal_window > 0 && !on_appeal {
+        return record_ruling(
+            &mut accounts.trust_pay,
+            &mut accounts.dispute,
+            recipient_share_bps,
+            resolution,
+            accounts.panel.key(),
+            format!("Panel decision, round {}", accounts.panel.round),
+            appeal_window,
+        );
+    }
+
+    let panel_key = accounts.panel.key();
+    let round = accounts.panel.round;
+
+    // The decision settles now, so it counts towards the deciding members' stats
+    let now = Clock::get()?.unix_timestamp;
+    let disputed_at = accounts
+        .trust_pay
+        .dispute_state(milestone_index)?
+        .disputed_at;
+    let hook_accounts = record_panel_resolution(
+        &accounts.panel,
+        now - disputed_at.unwrap_or(now),
+        ctx.remaining_accounts,
+    )?;
+    let payer_info = accounts.payer.to_account_info();
+    let recipient_info = accounts.recipient.to_account_info();
+    let payer_token_account_info = accounts.payer_token_account.to_account_info();
+    let recipient_token_account_info = accounts.recipient_token_account.to_account_info();
+    let treasury_token_account_info = accounts.treasury_token_account.to_account_info();
+    let token_program_info = accounts.token_program.to_account_info();
+    let native_payout_info = accounts.native_payout.to_account_info();
+    let funder_info = accounts.executor.to_account_info();
+    let system_program_info = accounts.system_program.to_account_info();
+
+    settle_with_resolution(
+        DisputeSettlement {
+            payer: &payer_info,
+            recipient: &recipient_info,
+            mint: &accounts.mint,
+            trust_pay: &mut accounts.trust_pay,
+            dispute: &mut accounts.dispute,
+            vault: &mut accounts.vault,
+            payer_token_account: &payer_token_account_info,
+            recipient_token_account: &recipient_token_account_info,
+            arbitration_fee_account: None,
+            treasury: &mut accounts.treasury,
+            treasury_token_account: &treasury_token_account_info,
+            global_state: &mut accounts.global_state,
+            mint_stats: &mut accounts.mint_stats,
+            payer_reputation: &mut accounts.payer_reputation,
+            recipient_reputation: &mut accounts.recipient_reputation,
+            contract_review: &mut accounts.contract_review,
+            native_payout: &native_payout_info,
+            native_payout_bump: ctx.bumps.native_payout,
+            funder: &funder_info,
+            system_program: &system_program_info,
+            token_program: &token_program_info,
+            remaining_accounts: hook_accounts,
+        },
+        milestone_index,
+        recipient_share_bps,
+        resolution,
+        DISPUTE_STATUS_RESOLVED,
+        panel_key,
+        format!("Panel decision, round {}", round),
+    )
+}
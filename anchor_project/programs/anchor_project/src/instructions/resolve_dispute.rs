@@ -1,16 +1,16 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_interface::{
-        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
-        TransferChecked,
-    },
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
 use crate::constants::*;
 use crate::error::TrustPayError;
-use crate::state::{GlobalState, TrustPay};
-use crate::{update_on_payment_approval, update_on_trust_pay_close};
+use crate::instructions::appeal_dispute::record_ruling;
+use crate::instructions::settle_dispute::*;
+use crate::state::{
+    Arbitrator, ContractReview, Dispute, GlobalState, MintStats, Reputation, Treasury, TrustPay,
+};
 
 #[derive(Accounts)]
 pub struct ResolveDispute<'info> {
@@ -30,13 +30,20 @@ pub struct ResolveDispute<'info> {
         has_one = payer,
         has_one = recipient,
         has_one = mint,
-        has_one = fee_destination,
         seeds = [b"trust-pay", payer.key().as_ref(), trust_pay.seed.to_le_bytes().as_ref()],
         bump = trust_pay.bump,
-        constraint = trust_pay.contract_status == CONTRACT_STATUS_DISPUTED @ TrustPayError::ContractNotDisputed
+        constraint = trust_pay.has_active_disputes() @ TrustPayError::ContractNotDisputed
     )]
     pub trust_pay: Account<'info, TrustPay>,
 
+    #[account(
+        mut,
+        has_one = trust_pay @ TrustPayError::DisputeMismatch,
+        seeds = [b"dispute", dispute.dispute_id.to_le_bytes().as_ref()],
+        bump = dispute.bump
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
     #[account(
         mut,
         associated_token::mint = mint,
@@ -45,6 +52,14 @@ pub struct ResolveDispute<'info> {
     )]
     pub vault: InterfaceAccount<'info, TokenAccount>,
 
+    /// CHECK: temporary wSOL account for a native SOL payout, created and closed in this instruction
+    #[account(
+        mut,
+        seeds = [b"native-payout", trust_pay.key().as_ref()],
+        bump
+    )]
+    pub native_payout: UncheckedAccount<'info>,
+
     #[account(
         init_if_needed,
         payer = resolver,
@@ -63,17 +78,31 @@ pub struct ResolveDispute<'info> {
     )]
     pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
 
-    /// CHECK: This is the fee destination account validated by has_one constraint on trust_pay
-    pub fee_destination: UncheckedAccount<'info>,
-
     #[account(
         init_if_needed,
         payer = resolver,
         associated_token::mint = mint,
-        associated_token::authority = fee_destination,
+        associated_token::authority = resolver,
+        associated_token::token_program = token_program,
+    )]
+    pub resolver_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = mint,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump = treasury.bump,
+        constraint = treasury.key() == trust_pay.fee_destination @ TrustPayError::InvalidFeeDestination
+    )]
+    pub treasury: Box<Account<'info, Treasury>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = treasury,
         associated_token::token_program = token_program,
     )]
-    pub fee_destination_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub treasury_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
@@ -82,312 +111,189 @@ pub struct ResolveDispute<'info> {
     )]
     pub global_state: Account<'info, GlobalState>,
 
+    #[account(
+        mut,
+        seeds = [b"reputation", payer.key().as_ref()],
+        bump = payer_reputation.bump
+    )]
+    pub payer_reputation: Box<Account<'info, Reputation>>,
+
+    #[account(
+        mut,
+        seeds = [b"reputation", recipient.key().as_ref()],
+        bump = recipient_reputation.bump
+    )]
+    pub recipient_reputation: Box<Account<'info, Reputation>>,
+
+    #[account(
+        mut,
+        seeds = [b"review", trust_pay.key().as_ref()],
+        bump = contract_review.bump
+    )]
+    pub contract_review: Box<Account<'info, ContractReview>>,
+
+    #[account(
+        mut,
+        seeds = [b"mint-stats", mint.key().as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Box<Account<'info, MintStats>>,
+
+    // Registry entry of the resolver, required when an assigned arbitrator resolves
+    #[account(
+        mut,
+        seeds = [b"arbitrator", resolver.key().as_ref()],
+        bump = arbitrator.bump
+    )]
+    pub arbitrator: Option<Box<Account<'info, Arbitrator>>>,
+
     pub system_program: Program<'info, System>,
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
-pub fn resolve_dispute(
-    ctx: Context<ResolveDispute>,
+pub fn resolve_dispute<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ResolveDispute<'info>>,
     milestone_index: u8,
-    resolution: u8, // 0 = favor payer (refund), 1 = favor recipient (pay), 2 = split
+    recipient_share_bps: u16, // 0 = full refund to the payer, 10000 = full payment to the recipient
+    resolution: u8, // RESOLUTION_SPLIT, RESOLUTION_TERMINATE or RESOLUTION_CONTINUE
     resolution_reason: String,
 ) -> Result<()> {
-    // Authorization check at the beginning of the instruction
-    require!(
-        ctx.accounts.resolver.key() == ctx.accounts.global_state.authority,
-        TrustPayError::UnauthorizedResolver
-    );
-
-    let index = milestone_index as usize;
-    let trust_pay_seed = ctx.accounts.trust_pay.seed;
-    let trust_pay_bump = ctx.accounts.trust_pay.bump;
-    let trust_pay_key = ctx.accounts.trust_pay.key();
-    let payer_key = ctx.accounts.payer.key();
-    let recipient_key = ctx.accounts.recipient.key();
-    let mint_decimals = ctx.accounts.mint.decimals;
-
-    // Validate resolution
-    require!(resolution <= 2, TrustPayError::InvalidResolution);
-
-    // Validate milestone index and status
+    // Validate the milestone, or the whole contract, is under dispute and the resolution fits it
+    let whole_contract = milestone_index == CONTRACT_DISPUTE_INDEX;
+    let milestone = ctx.accounts.trust_pay.dispute_state(milestone_index)?;
     require!(
-        index < ctx.accounts.trust_pay.milestones.len(),
-        TrustPayError::InvalidMilestoneIndex
+        milestone.status == MILESTONE_STATUS_DISPUTED,
+        TrustPayError::MilestoneNotDisputed
     );
-
     require!(
-        ctx.accounts.trust_pay.milestones[index].status == MILESTONE_STATUS_DISPUTED,
-        TrustPayError::MilestoneNotDisputed
+        if whole_contract {
+            resolution == RESOLUTION_TERMINATE || resolution == RESOLUTION_CONTINUE
+        } else {
+            resolution == RESOLUTION_SPLIT || resolution == RESOLUTION_TERMINATE
+        },
+        TrustPayError::InvalidResolution
     );
 
-    // Create PDA signer seeds
-    let seeds = &[
-        b"trust-pay",
-        payer_key.as_ref(),
-        &trust_pay_seed.to_le_bytes()[..],
-        &[trust_pay_bump],
-    ];
-    let signer_seeds = [&seeds[..]];
-
-    // Get milestone details
-    let milestone_amount;
-    let milestone_description;
-    let fee_amount;
-    {
-        let milestone = &ctx.accounts.trust_pay.milestones[index];
-        milestone_amount = milestone.amount;
-        milestone_description = milestone.description.clone();
-
-        let trust_pay = &ctx.accounts.trust_pay;
-        fee_amount = milestone_amount
-            .checked_mul(trust_pay.fee_percentage as u64)
-            .ok_or(TrustPayError::CalculationError)?
-            .checked_div(10000)
+    require!(!milestone.panel_required, TrustPayError::PanelRequired);
+    require!(milestone.ruling.is_none(), TrustPayError::RulingPending);
+    let on_appeal = milestone.appeal_level > 0;
+
+    // Terminating from a milestone dispute settles the whole contract, so it is held to the
+    // panel threshold on everything unsettled
+    if resolution == RESOLUTION_TERMINATE && !whole_contract {
+        let at_stake = ctx
+            .accounts
+            .trust_pay
+            .get_unsettled_amount()?
+            .checked_add(milestone.amount)
             .ok_or(TrustPayError::CalculationError)?;
+        require!(
+            !ctx.accounts
+                .global_state
+                .requires_panel(&ctx.accounts.trust_pay, at_stake),
+            TrustPayError::PanelRequired
+        );
     }
 
-    let vault_info = ctx.accounts.vault.to_account_info();
-    let payer_token_account_info = ctx.accounts.payer_token_account.to_account_info();
-    let recipient_token_account_info = ctx.accounts.recipient_token_account.to_account_info();
-    let fee_destination_token_account_info =
-        ctx.accounts.fee_destination_token_account.to_account_info();
-    let mint_info = ctx.accounts.mint.to_account_info();
-    let trust_pay_info = ctx.accounts.trust_pay.to_account_info();
-    let token_program_info = ctx.accounts.token_program.to_account_info();
-
-    // Handle resolution based on decision
-    match resolution {
-        0 => {
-            // Favor payer - refund milestone amount (no fee charged)
-            let transfer_accounts = TransferChecked {
-                from: vault_info.clone(),
-                to: payer_token_account_info.clone(),
-                mint: mint_info.clone(),
-                authority: trust_pay_info.clone(),
-            };
-
-            let transfer_ctx = CpiContext::new_with_signer(
-                token_program_info.clone(),
-                transfer_accounts,
-                &signer_seeds,
-            );
-
-            transfer_checked(transfer_ctx, milestone_amount, mint_decimals)?;
-
-            // Return the fee as well
-            if fee_amount > 0 {
-                let fee_refund_accounts = TransferChecked {
-                    from: vault_info.clone(),
-                    to: payer_token_account_info.clone(),
-                    mint: mint_info.clone(),
-                    authority: trust_pay_info.clone(),
-                };
-
-                let fee_refund_ctx = CpiContext::new_with_signer(
-                    token_program_info.clone(),
-                    fee_refund_accounts,
-                    &signer_seeds,
-                );
-
-                transfer_checked(fee_refund_ctx, fee_amount, mint_decimals)?;
-            }
-        }
-        1 => {
-            // Favor recipient - pay out milestone amount
-            let transfer_accounts = TransferChecked {
-                from: vault_info.clone(),
-                to: recipient_token_account_info.clone(),
-                mint: mint_info.clone(),
-                authority: trust_pay_info.clone(),
-            };
-
-            let transfer_ctx = CpiContext::new_with_signer(
-                token_program_info.clone(),
-                transfer_accounts,
-                &signer_seeds,
-            );
-
-            transfer_checked(transfer_ctx, milestone_amount, mint_decimals)?;
-
-            // Transfer fee to fee destination
-            if fee_amount > 0 {
-                let fee_transfer_accounts = TransferChecked {
-                    from: vault_info.clone(),
-                    to: fee_destination_token_account_info.clone(),
-                    mint: mint_info.clone(),
-                    authority: trust_pay_info.clone(),
-                };
-
-                let fee_transfer_ctx = CpiContext::new_with_signer(
-                    token_program_info.clone(),
-                    fee_transfer_accounts,
-                    &signer_seeds,
-                );
-
-                transfer_checked(fee_transfer_ctx, fee_amount, mint_decimals)?;
-            }
-
-            // Update global stats for successful payment
-            update_on_payment_approval(&mut ctx.accounts.global_state, milestone_amount)?;
-        }
-        2 => {
-            // Split - 50/50 between payer and recipient (no fee charged)
-            let split_amount = milestone_amount
-                .checked_div(2)
-                .ok_or(TrustPayError::CalculationError)?;
-
-            // Transfer to recipient
-            let recipient_transfer_accounts = TransferChecked {
-                from: vault_info.clone(),
-                to: recipient_token_account_info.clone(),
-                mint: mint_info.clone(),
-                authority: trust_pay_info.clone(),
-            };
-
-            let recipient_transfer_ctx = CpiContext::new_with_signer(
-                token_program_info.clone(),
-                recipient_transfer_accounts,
-                &signer_seeds,
-            );
-
-            transfer_checked(recipient_transfer_ctx, split_amount, mint_decimals)?;
-
-            // Transfer remainder to payer (includes any dust from division)
-            let remaining = milestone_amount
-                .checked_add(fee_amount)
-                .ok_or(TrustPayError::CalculationError)?
-                .checked_sub(split_amount)
-                .ok_or(TrustPayError::CalculationError)?;
-
-            let payer_transfer_accounts = TransferChecked {
-                from: vault_info.clone(),
-                to: payer_token_account_info.clone(),
-                mint: mint_info.clone(),
-                authority: trust_pay_info.clone(),
-            };
-
-            let payer_transfer_ctx = CpiContext::new_with_signer(
-                token_program_info.clone(),
-                payer_transfer_accounts,
-                &signer_seeds,
-            );
-
-            transfer_checked(payer_transfer_ctx, remaining, mint_decimals)?;
-        }
-        _ => return Err(TrustPayError::InvalidResolution.into()),
+    // Appeals are heard by the admin. Otherwise a contract-specific arbitrator replaces the
+    // protocol resolvers entirely, or the assigned arbitrator or the admin can resolve.
+    let resolver_key = ctx.accounts.resolver.key();
+    let contract_arbitrator = ctx.accounts.trust_pay.arbitrator;
+    let is_admin = resolver_key == ctx.accounts.global_state.authority;
+    let is_assigned = milestone.arbitrator == Some(resolver_key);
+    match contract_arbitrator {
+        _ if on_appeal => require!(is_admin, TrustPayError::UnauthorizedResolver),
+        Some(arbitrator) => require!(
+            resolver_key == arbitrator,
+            TrustPayError::UnauthorizedResolver
+        ),
+        None => require!(is_admin || is_assigned, TrustPayError::UnauthorizedResolver),
     }
-
-    // Update milestone status
-    let contract_completed;
-    {
-        let trust_pay = &mut ctx.accounts.trust_pay;
-        let milestone = &mut trust_pay.milestones[index];
-
-        // Mark as approved if resolved in favor of recipient, otherwise mark as cancelled
-        milestone.status = if resolution == 1 {
-            MILESTONE_STATUS_APPROVED_BY_PAYER
-        } else {
-            MILESTONE_STATUS_PENDING // Reset to pending or could use a new CANCELLED status
-        };
-
-        milestone.approved_at = Some(Clock::get()?.unix_timestamp);
-
-        // Check if all milestones are resolved
-        let all_resolved = trust_pay.milestones.iter().all(|m| {
-            m.status == MILESTONE_STATUS_APPROVED_BY_PAYER || m.status == MILESTONE_STATUS_PENDING
-        });
-
-        if all_resolved {
-            trust_pay.contract_status = CONTRACT_STATUS_COMPLETED;
-            contract_completed = true;
-        } else {
-            trust_pay.contract_status = CONTRACT_STATUS_IN_PROGRESS;
-            contract_completed = false;
-        }
+    if contract_arbitrator.is_none() && !is_admin {
+        let arbitrator = ctx
+            .accounts
+            .arbitrator
+            .as_ref()
+            .ok_or(TrustPayError::UnauthorizedResolver)?;
+        require!(arbitrator.active, TrustPayError::ArbitratorInactive);
     }
 
-    // Emit dispute resolved event
-    emit!(crate::DisputeResolvedEvent {
-        trust_pay: trust_pay_key,
-        payer: payer_key,
-        recipient: recipient_key,
-        milestone_index,
-        description: milestone_description,
-        amount: milestone_amount,
-        fee_amount,
-        resolver: ctx.accounts.resolver.key(),
-        resolution,
-        resolution_reason: resolution_reason.clone(),
-        resolved_at: Clock::get()?.unix_timestamp,
-    });
-
-    // If contract is completed, close accounts
-    if contract_completed {
-        ctx.accounts.vault.reload()?;
-        let remaining_balance = ctx.accounts.vault.amount;
-
-        if remaining_balance > 0 {
-            let dust_transfer_accounts = TransferChecked {
-                from: vault_info.clone(),
-                to: fee_destination_token_account_info.clone(),
-                mint: mint_info.clone(),
-                authority: trust_pay_info.clone(),
-            };
-
-            let dust_transfer_ctx = CpiContext::new_with_signer(
-                token_program_info.clone(),
-                dust_transfer_accounts,
-                &signer_seeds,
-            );
-
-            transfer_checked(dust_transfer_ctx, remaining_balance, mint_decimals)?;
-        }
-
-        // Close vault account
-        let close_accounts = CloseAccount {
-            account: vault_info.clone(),
-            destination: ctx.accounts.payer.to_account_info(),
-            authority: trust_pay_info.clone(),
-        };
-
-        let close_ctx =
-            CpiContext::new_with_signer(token_program_info.clone(), close_accounts, &signer_seeds);
-
-        close_account(close_ctx)?;
-
-        // Update global statistics
-        update_on_trust_pay_close(&mut ctx.accounts.global_state)?;
-
-        // Close TrustPay account
-        let payer_starting_lamports = ctx.accounts.payer.lamports();
-        let trust_pay_lamports = trust_pay_info.lamports();
-
-        **ctx.accounts.payer.lamports.borrow_mut() = payer_starting_lamports
-            .checked_add(trust_pay_lamports)
-            .ok_or(TrustPayError::CalculationError)?;
-        **trust_pay_info.lamports.borrow_mut() = 0;
+    require!(
+        !ctx.accounts
+            .global_state
+            .is_paused(PAUSE_DISPUTE_RESOLUTION, Clock::get()?.unix_timestamp),
+        TrustPayError::OperationPaused
+    );
 
-        let mut trust_pay_data = trust_pay_info.try_borrow_mut_data()?;
-        for byte in trust_pay_data.iter_mut() {
-            *byte = 0;
-        }
+    // First-instance rulings, terminations and contract rulings included, wait out the appeal
+    // window when one is configured
+    let appeal_window = ctx.accounts.global_state.appeal_window;
+    if appeal_window > 0 && !on_appeal {
+        return record_ruling(
+            &mut ctx.accounts.trust_pay,
+            &mut ctx.accounts.dispute,
+            recipient_share_bps,
+            resolution,
+            resolver_key,
+            resolution_reason,
+            appeal_window,
+        );
+    }
 
-        emit!(crate::ContractCompletedEvent {
-            trust_pay: trust_pay_key,
-            payer: payer_key,
-            recipient: recipient_key,
-            total_amount: ctx.accounts.trust_pay.total_contract_amount,
-            completed_at: Clock::get()?.unix_timestamp,
-        });
+    // The ruling settles now, so it counts towards the resolving arbitrator's stats
+    let resolved_at = Clock::get()?.unix_timestamp;
+    let disputed_at = ctx
+        .accounts
+        .trust_pay
+        .dispute_state(milestone_index)?
+        .disputed_at;
+    if let Some(arbitrator) = ctx.accounts.arbitrator.as_mut() {
+        arbitrator.record_resolution(resolved_at - disputed_at.unwrap_or(resolved_at))?;
     }
 
-    msg!(
-        "Dispute resolved - Milestone {}, Resolution: {}, Reason: {}",
+    let accounts = ctx.accounts;
+    let payer_info = accounts.payer.to_account_info();
+    let recipient_info = accounts.recipient.to_account_info();
+    let payer_token_account_info = accounts.payer_token_account.to_account_info();
+    let recipient_token_account_info = accounts.recipient_token_account.to_account_info();
+    let resolver_token_account_info = accounts.resolver_token_account.to_account_info();
+    let treasury_token_account_info = accounts.treasury_token_account.to_account_info();
+    let token_program_info = accounts.token_program.to_account_info();
+    let native_payout_info = accounts.native_payout.to_account_info();
+    let funder_info = accounts.resolver.to_account_info();
+    let system_program_info = accounts.system_program.to_account_info();
+
+    settle_with_resolution(
+        DisputeSettlement {
+            payer: &payer_info,
+            recipient: &recipient_info,
+            mint: &accounts.mint,
+            trust_pay: &mut accounts.trust_pay,
+            dispute: &mut accounts.dispute,
+            vault: &mut accounts.vault,
+            payer_token_account: &payer_token_account_info,
+            recipient_token_account: &recipient_token_account_info,
+            arbitration_fee_account: Some(&resolver_token_account_info),
+            treasury: &mut accounts.treasury,
+            treasury_token_account: &treasury_token_account_info,
+            global_state: &mut accounts.global_state,
+            mint_stats: &mut accounts.mint_stats,
+            payer_reputation: &mut accounts.payer_reputation,
+            recipient_reputation: &mut accounts.recipient_reputation,
+            contract_review: &mut accounts.contract_review,
+            native_payout: &native_payout_info,
+            native_payout_bump: ctx.bumps.native_payout,
+            funder: &funder_info,
+            system_program: &system_program_info,
+            token_program: &token_program_info,
+            remaining_accounts: ctx.remaining_accounts,
+        },
         milestone_index,
+        recipient_share_bps,
         resolution,
-        resolution_reason
-    );
-
-    Ok(())
+        DISPUTE_STATUS_RESOLVED,
+        resolver_key,
+        resolution_reason,
+    )
 }
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+
+use crate::error::TrustPayError;
+use crate::state::{Dispute, TrustPay};
+
+#[derive(Accounts)]
+pub struct RespondToDispute<'info> {
+    pub responder: Signer<'info>,
+
+    #[account(
+        seeds = [b"trust-pay", trust_pay.payer.key().as_ref(), trust_pay.seed.to_le_bytes().as_ref()],
+        bump = trust_pay.bump,
+        constraint = trust_pay.has_active_disputes() @ TrustPayError::ContractNotDisputed
+    )]
+    pub trust_pay: Account<'info, TrustPay>,
+
+    #[account(
+        mut,
+        has_one = trust_pay @ TrustPayError::DisputeMismatch,
+        seeds = [b"dispute", dispute.dispute_id.to_le_bytes().as_ref()],
+        bump = dispute.bump
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+}
+
+pub(crate) fn respond_to_dispute(
+    ctx: Context<RespondToDispute>,
+    milestone_index: u8,
+    response: String,
+) -> Result<()> {
+    let trust_pay_key = ctx.accounts.trust_pay.key();
+    let responder_key = ctx.accounts.responder.key();
+
+    // Validate response
+    require!(
+        response.len() >= 10 && response.len() <= 200,
+        TrustPayError::InvalidDisputeResponse
+    );
+
+    // Validate the milestone, or the whole contract, is under this dispute
+    let trust_pay = &ctx.accounts.trust_pay;
+    trust_pay.require_open_dispute(milestone_index, ctx.accounts.dispute.dispute_id)?;
+
+    // Only the party on the other side of the dispute responds, once
+    let dispute = &mut ctx.accounts.dispute;
+    let is_party = responder_key == trust_pay.payer || responder_key == trust_pay.recipient;
+    require!(
+        is_party && dispute.disputer != responder_key,
+        TrustPayError::UnauthorizedResponder
+    );
+    require!(dispute.response.is_none(), TrustPayError::AlreadyResponded);
+
+    let responded_at = Clock::get()?.unix_timestamp;
+    dispute.response = Some(response.clone());
+    dispute.updated_at = responded_at;
+
+    emit!(crate::DisputeRespondedEvent {
+        trust_pay: trust_pay_key,
+        milestone_index,
+        responder: responder_key,
+        response,
+        responded_at,
+    });
+
+    msg!(
+        "Dispute response recorded - Milestone {}, Responder: {}",
+        milestone_index,
+        responder_key
+    );
+
+    Ok(())
+}
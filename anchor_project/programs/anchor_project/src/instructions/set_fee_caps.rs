@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::error::TrustPayError;
+use crate::state::{GlobalState, MintConfig};
+
+#[derive(Accounts)]
+pub struct SetFeeCaps<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"global-state"],
+        bump = global_state.bump,
+        has_one = authority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    // Caps live next to the fee override, so the mint must be configured first
+    #[account(
+        mut,
+        seeds = [b"mint-config", mint.key().as_ref()],
+        bump = mint_config.bump
+    )]
+    pub mint_config: Box<Account<'info, MintConfig>>,
+}
+
+pub(crate) fn set_fee_caps(ctx: Context<SetFeeCaps>, min_fee: u64, max_fee: u64) -> Result<()> {
+    // A max_fee of zero leaves the fee uncapped
+    require!(
+        max_fee == 0 || min_fee <= max_fee,
+        TrustPayError::InvalidFeeCaps
+    );
+
+    let mint_config = &mut ctx.accounts.mint_config;
+    mint_config.min_fee = min_fee;
+    mint_config.max_fee = max_fee;
+    mint_config.updated_at = Clock::get()?.unix_timestamp;
+
+    emit!(crate::FeeCapsUpdatedEvent {
+        mint_config: mint_config.key(),
+        mint: mint_config.mint,
+        min_fee,
+        max_fee,
+        updated_at: mint_config.updated_at,
+    });
+
+    msg!(
+        "Fee caps for mint {} set to min: {}, max: {}",
+        mint_config.mint,
+        min_fee,
+        max_fee
+    );
+
+    Ok(())
+}
@@ -0,0 +1,787 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    close_account, CloseAccount, Mint, TokenAccount, TransferChecked,
+};
+
+use crate::constants::*;
+use crate::error::TrustPayError;
+use crate::fee_math::{
+    fee_refund, is_conserved, settle_bonds, split_milestone, BondOutcome, MilestoneSplit,
+    BASIS_POINTS,
+};
+use crate::instructions::shared::{
+    close_native_account, pay_out_native, transfer_checked_with_hook, NativePayout,
+};
+use crate::state::{
+    ContractReview, Dispute, GlobalState, Milestone, MintStats, Reputation, Treasury, TrustPay,
+};
+use crate::{record_fee_collection, update_on_payment_approval, update_on_trust_pay_close};
+
+// Accounts needed to pay out a disputed milestone, shared by every resolution path
+pub struct DisputeSettlement<'a, 'info> {
+    pub payer: &'a AccountInfo<'info>,
+    pub recipient: &'a AccountInfo<'info>,
+    pub mint: &'a InterfaceAccount<'info, Mint>,
+    pub trust_pay: &'a mut Account<'info, TrustPay>,
+    pub dispute: &'a mut Account<'info, Dispute>,
+    pub vault: &'a mut InterfaceAccount<'info, TokenAccount>,
+    pub payer_token_account: &'a AccountInfo<'info>,
+    pub recipient_token_account: &'a AccountInfo<'info>,
+    pub arbitration_fee_account: Option<&'a AccountInfo<'info>>, // None = the treasury
+    pub treasury: &'a mut Account<'info, Treasury>,
+    pub treasury_token_account: &'a AccountInfo<'info>,
+    pub global_state: &'a mut Account<'info, GlobalState>,
+    pub mint_stats: &'a mut Account<'info, MintStats>,
+    pub payer_reputation: &'a mut Account<'info, Reputation>,
+    pub recipient_reputation: &'a mut Account<'info, Reputation>,
+    pub contract_review: &'a mut Account<'info, ContractReview>,
+    pub native_payout: &'a AccountInfo<'info>, // Temporary wSOL account, seeds ["native-payout", trust_pay]
+    pub native_payout_bump: u8,
+    pub funder: &'a AccountInfo<'info>, // Fronts the temporary account's rent for a native SOL payout
+    pub system_program: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub remaining_accounts: &'a [AccountInfo<'info>], // Transfer hook accounts, if the mint has a hook
+}
+
+impl<'info> DisputeSettlement<'_, 'info> {
+    // Move tokens out of the vault, signed by the contract PDA, and take them off the mint's TVL
+    fn transfer_from_vault(&mut self, destination: &AccountInfo<'info>, amount: u64) -> Result<()> {
+        self.send_from_vault(destination, amount)?;
+        self.mint_stats.record_withdrawal(amount)
+    }
+
+    // Move tokens out of the vault without touching the mint's TVL
+    fn send_from_vault(&mut self, destination: &AccountInfo<'info>, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let payer_key = self.payer.key();
+        let seed = self.trust_pay.seed.to_le_bytes();
+        let seeds = &[
+            b"trust-pay",
+            payer_key.as_ref(),
+            &seed[..],
+            &[self.trust_pay.bump],
+        ];
+        let signer_seeds = [&seeds[..]];
+
+        let transfer_accounts = TransferChecked {
+            from: self.vault.to_account_info(),
+            to: destination.clone(),
+            mint: self.mint.to_account_info(),
+            authority: self.trust_pay.to_account_info(),
+        };
+
+        transfer_checked_with_hook(
+            self.token_program,
+            transfer_accounts,
+            self.remaining_accounts,
+            amount,
+            self.mint.decimals,
+            &signer_seeds,
+        )
+    }
+
+    // Pay each party its portion out of the vault. A native SOL contract pays lamports to the
+    // wallets, one payout per party however many milestones and bonds the amount covers.
+    fn pay_parties(&mut self, to_payer: u64, to_recipient: u64) -> Result<()> {
+        if !self.vault.is_native() {
+            let recipient_token_account_info = self.recipient_token_account.clone();
+            let payer_token_account_info = self.payer_token_account.clone();
+            self.transfer_from_vault(&recipient_token_account_info, to_recipient)?;
+            return self.transfer_from_vault(&payer_token_account_info, to_payer);
+        }
+
+        let payer_key = self.payer.key();
+        let seed = self.trust_pay.seed.to_le_bytes();
+        let seeds = &[
+            b"trust-pay",
+            payer_key.as_ref(),
+            &seed[..],
+            &[self.trust_pay.bump],
+        ];
+        let signer_seeds = [&seeds[..]];
+        let vault_info = self.vault.to_account_info();
+        let trust_pay_info = self.trust_pay.to_account_info();
+
+        for (to, amount) in [(self.recipient, to_recipient), (self.payer, to_payer)] {
+            if amount == 0 {
+                continue;
+            }
+
+            pay_out_native(
+                NativePayout {
+                    vault: &vault_info,
+                    mint: self.mint,
+                    trust_pay: &trust_pay_info,
+                    payout_account: self.native_payout,
+                    funder: self.funder,
+                    system_program: self.system_program,
+                    token_program: self.token_program,
+                },
+                to,
+                amount,
+                self.native_payout_bump,
+                &signer_seeds,
+            )?;
+            self.mint_stats.record_withdrawal(amount)?;
+        }
+
+        Ok(())
+    }
+
+    // Pay the fee on a milestone split and record the split on the contract and global stats.
+    // The parties' portions are left for pay_parties.
+    fn pay_split(&mut self, split: MilestoneSplit) -> Result<()> {
+        let treasury_token_account_info = self.treasury_token_account.clone();
+
+        // The fee is charged on the recipient's portion only
+        self.transfer_from_vault(&treasury_token_account_info, split.fee)?;
+
+        let trust_pay_key = self.trust_pay.key();
+        record_fee_collection(self.treasury, self.mint_stats, trust_pay_key, split.fee)?;
+
+        // Update global stats for the amount paid to the recipient
+        if split.to_recipient > 0 {
+            update_on_payment_approval(self.global_state, self.mint_stats, split.to_recipient)?;
+        }
+
+        let trust_pay = &mut self.trust_pay;
+        trust_pay.amount_released = trust_pay
+            .amount_released
+            .checked_add(split.to_recipient)
+            .ok_or(TrustPayError::CalculationError)?;
+        trust_pay.amount_refunded = trust_pay
+            .amount_refunded
+            .checked_add(split.to_payer)
+            .ok_or(TrustPayError::CalculationError)?;
+        trust_pay.fee_charged = trust_pay
+            .fee_charged
+            .checked_add(split.fee)
+            .ok_or(TrustPayError::CalculationError)?;
+
+        Ok(())
+    }
+
+    // Count a lost dispute against whichever party the outcome ruled against
+    fn record_dispute_loser(
+        &mut self,
+        outcome: BondOutcome,
+        disputer_is_payer: bool,
+    ) -> Result<()> {
+        let payer_lost = match outcome {
+            BondOutcome::DisputerWins => !disputer_is_payer,
+            BondOutcome::CounterpartyWins => disputer_is_payer,
+            BondOutcome::Split | BondOutcome::Withdrawn => return Ok(()),
+        };
+        let now = Clock::get()?.unix_timestamp;
+        if payer_lost {
+            self.payer_reputation.record_dispute_lost(now)
+        } else {
+            self.recipient_reputation.record_dispute_lost(now)
+        }
+    }
+
+    // Settle the dispute, counter and appeal bonds of a closing dispute. The losing side forfeits
+    // its bond and the resolver is paid from the bond pool; an appeal bond is returned only if
+    // the appeal was won, otherwise the other party takes it. Only the arbitration fee is paid
+    // here, the parties' bonds are left for pay_parties.
+    fn pay_bonds(
+        &mut self,
+        state: &Milestone,
+        outcome: BondOutcome,
+        appeal_won: bool,
+    ) -> Result<BondPayout> {
+        let payer_key = self.payer.key();
+        let disputer_is_payer = state.disputer == Some(payer_key);
+        let bonds = settle_bonds(
+            state.dispute_bond,
+            state.counter_bond,
+            self.global_state.arbitration_fee_bps,
+            outcome,
+        )?;
+        let (bond_to_payer, bond_to_recipient) = if disputer_is_payer {
+            (bonds.to_disputer, bonds.to_counterparty)
+        } else {
+            (bonds.to_counterparty, bonds.to_disputer)
+        };
+        self.record_dispute_loser(outcome, disputer_is_payer)?;
+
+        let appellant_is_payer = state.appellant == Some(payer_key);
+        let appeal_bond_to_appellant = if appeal_won { state.appeal_bond } else { 0 };
+        let appeal_bond_to_other = state.appeal_bond - appeal_bond_to_appellant;
+        let (appeal_bond_to_payer, appeal_bond_to_recipient) = if appellant_is_payer {
+            (appeal_bond_to_appellant, appeal_bond_to_other)
+        } else {
+            (appeal_bond_to_other, appeal_bond_to_appellant)
+        };
+
+        let arbitration_fee_info = self
+            .arbitration_fee_account
+            .unwrap_or(self.treasury_token_account)
+            .clone();
+        self.transfer_from_vault(&arbitration_fee_info, bonds.arbitration_fee)?;
+
+        // Without a fee account the arbitration fee is protocol revenue
+        if self.arbitration_fee_account.is_none() {
+            let trust_pay_key = self.trust_pay.key();
+            record_fee_collection(
+                self.treasury,
+                self.mint_stats,
+                trust_pay_key,
+                bonds.arbitration_fee,
+            )?;
+        }
+
+        Ok(BondPayout {
+            bond_to_payer,
+            bond_to_recipient,
+            arbitration_fee: bonds.arbitration_fee,
+            appeal_bond_to_payer,
+            appeal_bond_to_recipient,
+        })
+    }
+}
+
+// Bonds paid out to each side when a dispute closes
+struct BondPayout {
+    bond_to_payer: u64,
+    bond_to_recipient: u64,
+    arbitration_fee: u64,
+    appeal_bond_to_payer: u64,
+    appeal_bond_to_recipient: u64,
+}
+
+impl BondPayout {
+    // Every bond returned or awarded to the payer
+    fn to_payer(&self) -> Result<u64> {
+        self.bond_to_payer
+            .checked_add(self.appeal_bond_to_payer)
+            .ok_or(TrustPayError::CalculationError.into())
+    }
+
+    // Every bond returned or awarded to the recipient
+    fn to_recipient(&self) -> Result<u64> {
+        self.bond_to_recipient
+            .checked_add(self.appeal_bond_to_recipient)
+            .ok_or(TrustPayError::CalculationError.into())
+    }
+}
+
+// The losing side forfeits its bond; a share between the extremes splits the pool
+fn bond_outcome(recipient_share_bps: u16, disputer_is_payer: bool) -> BondOutcome {
+    match (recipient_share_bps as u64, disputer_is_payer) {
+        (0, true) | (BASIS_POINTS, false) => BondOutcome::DisputerWins,
+        (0, false) | (BASIS_POINTS, true) => BondOutcome::CounterpartyWins,
+        _ => BondOutcome::Split,
+    }
+}
+
+// Terminate the contract: split every milestone not yet paid out or refunded by the recipient's
+// share, compensating the recipient for work on milestones that can no longer be completed
+pub fn terminate_unsettled(
+    accounts: &mut DisputeSettlement,
+    recipient_share_bps: u16,
+    resolver: Pubkey,
+) -> Result<()> {
+    let mut milestones_terminated: u8 = 0;
+    let mut total = MilestoneSplit::default();
+
+    for index in 0..accounts.trust_pay.milestones.len() {
+        let milestone = &accounts.trust_pay.milestones[index];
+        if !milestone.is_unsettled() {
+            continue;
+        }
+
+        let split = split_milestone(milestone.amount, milestone.fee, recipient_share_bps)?;
+        accounts.pay_split(split)?;
+
+        let milestone = &mut accounts.trust_pay.milestones[index];
+        milestone.status = if recipient_share_bps as u64 == BASIS_POINTS {
+            MILESTONE_STATUS_APPROVED_BY_PAYER
+        } else {
+            MILESTONE_STATUS_RESOLVED
+        };
+        milestone.approved_at = Some(Clock::get()?.unix_timestamp);
+
+        milestones_terminated += 1;
+        total.to_recipient = total
+            .to_recipient
+            .checked_add(split.to_recipient)
+            .ok_or(TrustPayError::CalculationError)?;
+        total.to_payer = total
+            .to_payer
+            .checked_add(split.to_payer)
+            .ok_or(TrustPayError::CalculationError)?;
+        total.fee = total
+            .fee
+            .checked_add(split.fee)
+            .ok_or(TrustPayError::CalculationError)?;
+    }
+
+    accounts.pay_parties(total.to_payer, total.to_recipient)?;
+
+    emit!(crate::ContractTerminatedEvent {
+        trust_pay: accounts.trust_pay.key(),
+        dispute_id: accounts.dispute.dispute_id,
+        resolver,
+        recipient_share_bps,
+        milestones_terminated,
+        amount_to_recipient: total.to_recipient,
+        amount_to_payer: total.to_payer,
+        fee_amount: total.fee,
+        terminated_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Pay out a disputed milestone, settle its bonds and close the contract once every milestone is settled
+pub fn settle_dispute(
+    mut accounts: DisputeSettlement,
+    milestone_index: u8,
+    recipient_share_bps: u16, // Share of the milestone paid to the recipient
+    dispute_status: u8, // RESOLVED, or SETTLED/TIMED_OUT where nobody ruled and every bond is returned
+    resolver: Pubkey,
+    resolution_reason: String,
+) -> Result<()> {
+    let index = milestone_index as usize;
+
+    let trust_pay_key = accounts.trust_pay.key();
+    let payer_key = accounts.payer.key();
+    let recipient_key = accounts.recipient.key();
+
+    // Validate milestone status and its dispute record
+    require!(
+        accounts.trust_pay.milestones[index].status == MILESTONE_STATUS_DISPUTED,
+        TrustPayError::MilestoneNotDisputed
+    );
+    require!(
+        accounts.trust_pay.milestones[index].dispute_id == Some(accounts.dispute.dispute_id),
+        TrustPayError::DisputeMismatch
+    );
+    let dispute_id = accounts.dispute.dispute_id;
+    let bonds_returned = dispute_status != DISPUTE_STATUS_RESOLVED;
+
+    // The milestone fee was fixed within the fee caps when the contract was created
+    let milestone = accounts.trust_pay.milestones[index].clone();
+    let split = split_milestone(milestone.amount, milestone.fee, recipient_share_bps)?;
+
+    // An appeal is won if it moved the ruling in the appellant's favour
+    let disputer_is_payer = milestone.disputer == Some(payer_key);
+    let outcome = if bonds_returned {
+        BondOutcome::Withdrawn
+    } else {
+        bond_outcome(recipient_share_bps, disputer_is_payer)
+    };
+    let appellant_is_payer = milestone.appellant == Some(payer_key);
+    let appeal_won = bonds_returned
+        || milestone.appealed_ruling.is_some_and(|appealed| {
+            if appellant_is_payer {
+                recipient_share_bps < appealed
+            } else {
+                recipient_share_bps > appealed
+            }
+        });
+
+    // Pay out each side's portion together with its bonds
+    accounts.pay_split(split)?;
+    let bonds = accounts.pay_bonds(&milestone, outcome, appeal_won)?;
+    let to_payer = split
+        .to_payer
+        .checked_add(bonds.to_payer()?)
+        .ok_or(TrustPayError::CalculationError)?;
+    let to_recipient = split
+        .to_recipient
+        .checked_add(bonds.to_recipient()?)
+        .ok_or(TrustPayError::CalculationError)?;
+    accounts.pay_parties(to_payer, to_recipient)?;
+
+    // Update milestone status
+    let contract_completed;
+    {
+        let trust_pay = &mut accounts.trust_pay;
+        let milestone = &mut trust_pay.milestones[index];
+
+        // Mark as approved if the recipient is paid in full, otherwise as resolved
+        milestone.status = if recipient_share_bps as u64 == BASIS_POINTS {
+            MILESTONE_STATUS_APPROVED_BY_PAYER
+        } else {
+            MILESTONE_STATUS_RESOLVED
+        };
+
+        milestone.approved_at = Some(Clock::get()?.unix_timestamp);
+        milestone.dispute_bond = 0;
+        milestone.counter_bond = 0;
+        milestone.appeal_bond = 0;
+        milestone.ruling = None;
+        milestone.ruled_by = None;
+        milestone.ruled_at = None;
+        milestone.appeal_deadline = None;
+        milestone.settlement_offer = None;
+
+        // Check if all milestones are settled
+        if trust_pay.is_all_milestones_settled() {
+            trust_pay.contract_status = CONTRACT_STATUS_COMPLETED;
+            contract_completed = true;
+        } else {
+            trust_pay.contract_status = CONTRACT_STATUS_IN_PROGRESS;
+            contract_completed = false;
+        }
+    }
+
+    let resolved_at = Clock::get()?.unix_timestamp;
+
+    // Close the dispute record
+    {
+        let dispute = &mut accounts.dispute;
+        dispute.status = dispute_status;
+        dispute.outcome = Some(recipient_share_bps);
+        dispute.resolved_by = Some(resolver);
+        dispute.updated_at = resolved_at;
+        dispute.closed_at = Some(resolved_at);
+    }
+    accounts.mint_stats.record_dispute_closed()?;
+
+    // Emit dispute resolved event
+    emit!(crate::DisputeResolvedEvent {
+        trust_pay: trust_pay_key,
+        dispute_id,
+        payer: payer_key,
+        recipient: recipient_key,
+        milestone_index,
+        description: milestone.description,
+        amount: milestone.amount,
+        fee_amount: split.fee,
+        resolver,
+        recipient_share_bps,
+        amount_to_payer: split.to_payer,
+        amount_to_recipient: split.to_recipient,
+        resolution_reason: resolution_reason.clone(),
+        bond_to_payer: bonds.bond_to_payer,
+        bond_to_recipient: bonds.bond_to_recipient,
+        arbitration_fee: bonds.arbitration_fee,
+        appeal_bond_to_payer: bonds.appeal_bond_to_payer,
+        appeal_bond_to_recipient: bonds.appeal_bond_to_recipient,
+        resolved_at,
+    });
+
+    // If contract is completed, close accounts
+    if contract_completed {
+        close_settled_contract(&mut accounts)?;
+    }
+
+    msg!(
+        "Dispute settled - Milestone {}, Recipient share: {} bps, Reason: {}",
+        milestone_index,
+        recipient_share_bps,
+        resolution_reason
+    );
+
+    Ok(())
+}
+
+// Resolve a dispute over the whole contract, either terminating it with the recipient's share
+// of everything unsettled as compensation or dismissing the dispute so work carries on
+pub fn settle_contract_dispute(
+    mut accounts: DisputeSettlement,
+    recipient_share_bps: u16,
+    resolution: u8, // RESOLUTION_TERMINATE or RESOLUTION_CONTINUE
+    dispute_status: u8, // RESOLVED, or SETTLED/TIMED_OUT where nobody ruled and every bond is returned
+    resolver: Pubkey,
+    resolution_reason: String,
+) -> Result<()> {
+    let trust_pay_key = accounts.trust_pay.key();
+    let payer_key = accounts.payer.key();
+    let recipient_key = accounts.recipient.key();
+    let dispute_id = accounts.dispute.dispute_id;
+    let terminated = resolution == RESOLUTION_TERMINATE;
+    let bonds_returned = dispute_status != DISPUTE_STATUS_RESOLVED;
+
+    accounts
+        .trust_pay
+        .require_open_dispute(CONTRACT_DISPUTE_INDEX, dispute_id)?;
+    require!(
+        terminated || resolution == RESOLUTION_CONTINUE,
+        TrustPayError::InvalidResolution
+    );
+    require!(
+        recipient_share_bps as u64 <= BASIS_POINTS,
+        TrustPayError::InvalidResolution
+    );
+
+    // Dismissing the dispute rules against the disputer
+    let record = accounts.trust_pay.contract_dispute.clone();
+    let disputer_is_payer = record.disputer == Some(payer_key);
+    let outcome = if bonds_returned {
+        BondOutcome::Withdrawn
+    } else if terminated {
+        bond_outcome(recipient_share_bps, disputer_is_payer)
+    } else {
+        BondOutcome::CounterpartyWins
+    };
+
+    // An appeal is won if it overturned the ruling in the appellant's favour: a dismissal turned
+    // into a termination, a termination dismissed at the other party's request, or a better share
+    let appellant_is_payer = record.appellant == Some(payer_key);
+    let appellant_is_disputer = record.appellant == record.disputer;
+    let appeal_won = bonds_returned
+        || record.appealed_ruling.is_some_and(|appealed| {
+            match (record.appealed_resolution, resolution) {
+                (RESOLUTION_CONTINUE, _) => terminated,
+                (_, RESOLUTION_CONTINUE) => !appellant_is_disputer,
+                _ if appellant_is_payer => recipient_share_bps < appealed,
+                _ => recipient_share_bps > appealed,
+            }
+        });
+
+    if terminated {
+        terminate_unsettled(&mut accounts, recipient_share_bps, resolver)?;
+    }
+    let bonds = accounts.pay_bonds(&record, outcome, appeal_won)?;
+    accounts.pay_parties(bonds.to_payer()?, bonds.to_recipient()?)?;
+
+    let contract_completed;
+    {
+        let trust_pay = &mut accounts.trust_pay;
+        let record = &mut trust_pay.contract_dispute;
+        record.status = MILESTONE_STATUS_RESOLVED;
+        record.approved_at = Some(Clock::get()?.unix_timestamp);
+        record.dispute_bond = 0;
+        record.counter_bond = 0;
+        record.appeal_bond = 0;
+        record.ruling = None;
+        record.ruled_by = None;
+        record.ruled_at = None;
+        record.appeal_deadline = None;
+        record.settlement_offer = None;
+
+        if trust_pay.is_all_milestones_settled() {
+            trust_pay.contract_status = CONTRACT_STATUS_COMPLETED;
+            contract_completed = true;
+        } else {
+            trust_pay.contract_status = CONTRACT_STATUS_IN_PROGRESS;
+            contract_completed = false;
+        }
+    }
+
+    let resolved_at = Clock::get()?.unix_timestamp;
+
+    // Close the dispute record; a dismissal has no payout share
+    {
+        let dispute = &mut accounts.dispute;
+        dispute.status = dispute_status;
+        dispute.outcome = terminated.then_some(recipient_share_bps);
+        dispute.resolved_by = Some(resolver);
+        dispute.updated_at = resolved_at;
+        dispute.closed_at = Some(resolved_at);
+    }
+    accounts.mint_stats.record_dispute_closed()?;
+
+    emit!(crate::ContractDisputeResolvedEvent {
+        trust_pay: trust_pay_key,
+        dispute_id,
+        payer: payer_key,
+        recipient: recipient_key,
+        resolver,
+        resolution,
+        recipient_share_bps,
+        resolution_reason: resolution_reason.clone(),
+        bond_to_payer: bonds.bond_to_payer,
+        bond_to_recipient: bonds.bond_to_recipient,
+        arbitration_fee: bonds.arbitration_fee,
+        appeal_bond_to_payer: bonds.appeal_bond_to_payer,
+        appeal_bond_to_recipient: bonds.appeal_bond_to_recipient,
+        resolved_at,
+    });
+
+    if contract_completed {
+        close_settled_contract(&mut accounts)?;
+    }
+
+    msg!(
+        "Contract dispute resolved - Resolution: {}, Recipient share: {} bps, Reason: {}",
+        resolution,
+        recipient_share_bps,
+        resolution_reason
+    );
+
+    Ok(())
+}
+
+// Settle the dispute on a milestone, or on the whole contract for CONTRACT_DISPUTE_INDEX.
+// Terminating from a milestone dispute settles every other milestone first, so settling the
+// disputed one closes the contract.
+pub fn settle_with_resolution(
+    mut accounts: DisputeSettlement,
+    milestone_index: u8,
+    recipient_share_bps: u16,
+    resolution: u8, // RESOLUTION_SPLIT or RESOLUTION_TERMINATE, or RESOLUTION_CONTINUE on a contract
+    dispute_status: u8,
+    resolver: Pubkey,
+    resolution_reason: String,
+) -> Result<()> {
+    if milestone_index == CONTRACT_DISPUTE_INDEX {
+        return settle_contract_dispute(
+            accounts,
+            recipient_share_bps,
+            resolution,
+            dispute_status,
+            resolver,
+            resolution_reason,
+        );
+    }
+
+    require!(
+        resolution == RESOLUTION_SPLIT || resolution == RESOLUTION_TERMINATE,
+        TrustPayError::InvalidResolution
+    );
+    if resolution == RESOLUTION_TERMINATE {
+        let other_disputes = accounts
+            .trust_pay
+            .milestones
+            .iter()
+            .enumerate()
+            .any(|(i, m)| i != milestone_index as usize && m.status == MILESTONE_STATUS_DISPUTED);
+        require!(!other_disputes, TrustPayError::MilestoneDisputesOpen);
+        require!(
+            recipient_share_bps as u64 <= BASIS_POINTS,
+            TrustPayError::InvalidResolution
+        );
+        terminate_unsettled(&mut accounts, recipient_share_bps, resolver)?;
+    }
+
+    settle_dispute(
+        accounts,
+        milestone_index,
+        recipient_share_bps,
+        dispute_status,
+        resolver,
+        resolution_reason,
+    )
+}
+
+// Refund whatever is left in the vault to the payer and close the vault and the contract
+fn close_settled_contract(accounts: &mut DisputeSettlement) -> Result<()> {
+    let trust_pay_key = accounts.trust_pay.key();
+    let payer_key = accounts.payer.key();
+    let trust_pay_seed = accounts.trust_pay.seed;
+    let trust_pay_bump = accounts.trust_pay.bump;
+
+    // Refund any fee that was deposited but never charged
+    let fee_refunded = fee_refund(
+        accounts.trust_pay.fee_deposited,
+        accounts.trust_pay.fee_charged,
+    )?;
+    {
+        let trust_pay = &mut accounts.trust_pay;
+        trust_pay.amount_refunded = trust_pay
+            .amount_refunded
+            .checked_add(fee_refunded)
+            .ok_or(TrustPayError::CalculationError)?;
+
+        let total_deposited = trust_pay
+            .total_contract_amount
+            .checked_add(trust_pay.fee_deposited)
+            .ok_or(TrustPayError::CalculationError)?;
+        require!(
+            is_conserved(
+                total_deposited,
+                trust_pay.amount_released,
+                trust_pay.fee_charged,
+                trust_pay.amount_refunded,
+            ),
+            TrustPayError::FundsNotConserved
+        );
+    }
+
+    // Anything sent to the vault outside the contract also goes back to the payer
+    accounts.vault.reload()?;
+    let remaining_balance = accounts.vault.amount;
+    require!(
+        remaining_balance >= fee_refunded,
+        TrustPayError::FundsNotConserved
+    );
+
+    let seeds = &[
+        b"trust-pay",
+        payer_key.as_ref(),
+        &trust_pay_seed.to_le_bytes()[..],
+        &[trust_pay_bump],
+    ];
+    let signer_seeds = [&seeds[..]];
+    let trust_pay_info = accounts.trust_pay.to_account_info();
+
+    if accounts.vault.is_native() {
+        // Closing a native SOL vault refunds the payer in lamports
+        close_native_account(
+            &accounts.vault.to_account_info(),
+            &trust_pay_info,
+            accounts.payer,
+            remaining_balance,
+            accounts.token_program,
+            &signer_seeds,
+        )?;
+    } else {
+        let payer_token_account_info = accounts.payer_token_account.clone();
+        accounts.send_from_vault(&payer_token_account_info, remaining_balance)?;
+
+        // Close vault account
+        let close_accounts = CloseAccount {
+            account: accounts.vault.to_account_info(),
+            destination: accounts.payer.to_account_info(),
+            authority: trust_pay_info.clone(),
+        };
+
+        let close_ctx = CpiContext::new_with_signer(
+            accounts.token_program.clone(),
+            close_accounts,
+            &signer_seeds,
+        );
+
+        close_account(close_ctx)?;
+    }
+
+    // Only the fee refund is still counted in the mint's TVL; the rest was never deposited here
+    accounts.mint_stats.record_withdrawal(fee_refunded)?;
+
+    // Update global statistics and both parties' reputation
+    update_on_trust_pay_close(accounts.global_state)?;
+    accounts.mint_stats.record_escrow_closed()?;
+    let now = Clock::get()?.unix_timestamp;
+    let volume = accounts.trust_pay.amount_released;
+    accounts.payer_reputation.record_completion(volume, now)?;
+    accounts
+        .recipient_reputation
+        .record_completion(volume, now)?;
+    accounts.contract_review.completed_at = Some(now);
+
+    // Close TrustPay account
+    let payer_starting_lamports = accounts.payer.lamports();
+    let trust_pay_lamports = trust_pay_info.lamports();
+
+    **accounts.payer.lamports.borrow_mut() = payer_starting_lamports
+        .checked_add(trust_pay_lamports)
+        .ok_or(TrustPayError::CalculationError)?;
+    **trust_pay_info.lamports.borrow_mut() = 0;
+
+    {
+        let mut trust_pay_data = trust_pay_info.try_borrow_mut_data()?;
+        for byte in trust_pay_data.iter_mut() {
+            *byte = 0;
+        }
+    }
+
+    emit!(crate::ContractCompletedEvent {
+        trust_pay: trust_pay_key,
+        payer: payer_key,
+        recipient: accounts.recipient.key(),
+        total_amount: accounts.trust_pay.total_contract_amount,
+        fee_charged: accounts.trust_pay.fee_charged,
+        fee_refunded,
+        completed_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
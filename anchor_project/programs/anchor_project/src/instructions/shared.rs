@@ -1,8 +1,29 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::system_program::{create_account, transfer, CreateAccount, Transfer};
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+    onchain::invoke_transfer_checked,
+    state::{Account as TokenAccountState, Mint as MintState},
+};
 use anchor_spl::token_interface::{
-    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+    close_account, initialize_account3, sync_native, CloseAccount, InitializeAccount3, Mint,
+    SyncNative, TokenAccount, TokenInterface, TransferChecked,
 };
 
+use crate::error::TrustPayError;
+
+// Token-2022 extensions the escrow cannot honour. A transfer fee leaves the vault holding less
+// than the contract records; the others block transfers or let someone else move vault funds.
+// Transfer hooks are supported through transfer_checked_with_hook.
+const UNSUPPORTED_MINT_EXTENSIONS: [ExtensionType; 4] = [
+    ExtensionType::TransferFeeConfig,
+    ExtensionType::NonTransferable,
+    ExtensionType::PermanentDelegate,
+    ExtensionType::ConfidentialTransferMint,
+];
+
 pub fn transfer_tokens<'info>(
     from: &InterfaceAccount<'info, TokenAccount>,
     to: &InterfaceAccount<'info, TokenAccount>,
@@ -10,6 +31,7 @@ pub fn transfer_tokens<'info>(
     mint: &InterfaceAccount<'info, Mint>,
     authority: &Signer<'info>,
     token_program: &Interface<'info, TokenInterface>,
+    remaining_accounts: &[AccountInfo<'info>],
 ) -> Result<()> {
     let transfer_accounts_options = TransferChecked {
         from: from.to_account_info(),
@@ -18,7 +40,234 @@ pub fn transfer_tokens<'info>(
         authority: authority.to_account_info(),
     };
 
-    let cpi_context = CpiContext::new(token_program.to_account_info(), transfer_accounts_options);
+    transfer_checked_with_hook(
+        &token_program.to_account_info(),
+        transfer_accounts_options,
+        remaining_accounts,
+        *amount,
+        mint.decimals,
+        &[],
+    )
+}
+
+// Every transfer in or out of an escrow goes through here. For a mint with a transfer hook, the
+// hook program, its extra account metas and the accounts they resolve to are looked up in
+// remaining_accounts and forwarded with the CPI; other mints transfer as plain transfer_checked.
+pub fn transfer_checked_with_hook<'info>(
+    token_program: &AccountInfo<'info>,
+    accounts: TransferChecked<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    amount: u64,
+    decimals: u8,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    invoke_transfer_checked(
+        token_program.key,
+        accounts.from,
+        accounts.mint,
+        accounts.to,
+        accounts.authority,
+        remaining_accounts,
+        amount,
+        decimals,
+        signer_seeds,
+    )
+    .map_err(Into::into)
+}
+
+// Native SOL contracts escrow wrapped SOL. Top up the depositor's wSOL account from their
+// lamports so the deposit that follows works exactly like any other token deposit. Payouts unwrap
+// back to lamports through close_native_account and pay_out_native.
+pub fn wrap_native_deposit<'info>(
+    token_account: &InterfaceAccount<'info, TokenAccount>,
+    amount: u64,
+    depositor: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    if !token_account.is_native() {
+        return Ok(());
+    }
+
+    // Already-wrapped SOL counts towards the deposit
+    let shortfall = amount.saturating_sub(token_account.amount);
+    if shortfall == 0 {
+        return Ok(());
+    }
+
+    let transfer_ctx = CpiContext::new(
+        system_program.to_account_info(),
+        Transfer {
+            from: depositor.to_account_info(),
+            to: token_account.to_account_info(),
+        },
+    );
+    transfer(transfer_ctx, shortfall)?;
+
+    let sync_ctx = CpiContext::new(
+        token_program.to_account_info(),
+        SyncNative {
+            account: token_account.to_account_info(),
+        },
+    );
+    sync_native(sync_ctx)
+}
+
+// The deposit leaves the depositor's wSOL account empty when the program opened it for the
+// deposit; close it so its rent goes back to them instead of sitting in an unused account.
+pub fn close_empty_native_account<'info>(
+    token_account: &mut InterfaceAccount<'info, TokenAccount>,
+    owner: &Signer<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    if !token_account.is_native() {
+        return Ok(());
+    }
+
+    token_account.reload()?;
+    if token_account.amount > 0 {
+        return Ok(());
+    }
+
+    let close_ctx = CpiContext::new(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: token_account.to_account_info(),
+            destination: owner.to_account_info(),
+            authority: owner.to_account_info(),
+        },
+    );
+    close_account(close_ctx)
+}
+
+// Native SOL leaves escrow as lamports. Closing a wSOL account owned by the contract releases its
+// balance along with its rent into the contract account, which forwards the balance to the party.
+// The rent stays with the contract and goes to the payer when the contract closes.
+pub fn close_native_account<'info>(
+    account: &AccountInfo<'info>,
+    trust_pay: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    amount: u64,
+    token_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let close_ctx = CpiContext::new_with_signer(
+        token_program.clone(),
+        CloseAccount {
+            account: account.clone(),
+            destination: trust_pay.clone(),
+            authority: trust_pay.clone(),
+        },
+        signer_seeds,
+    );
+    close_account(close_ctx)?;
+
+    move_lamports(trust_pay, to, amount)
+}
+
+// Accounts needed to pay native SOL out of a vault that stays open
+pub struct NativePayout<'a, 'info> {
+    pub vault: &'a AccountInfo<'info>,
+    pub mint: &'a InterfaceAccount<'info, Mint>,
+    pub trust_pay: &'a AccountInfo<'info>,
+    pub payout_account: &'a AccountInfo<'info>, // Temporary wSOL account, seeds ["native-payout", trust_pay]
+    pub funder: &'a AccountInfo<'info>,         // Fronts the temporary account's rent
+    pub system_program: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+}
+
+// Only a closed wSOL account gives up its lamports, so a payout from a vault that stays open
+// moves through a temporary wSOL account owned by the contract. It is closed into the contract
+// in the same instruction, the party receives the amount and the funder gets the rent back.
+pub fn pay_out_native<'info>(
+    accounts: NativePayout<'_, 'info>,
+    to: &AccountInfo<'info>,
+    amount: u64,
+    payout_bump: u8,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let trust_pay_key = accounts.trust_pay.key();
+    let payout_seeds: &[&[u8]] = &[b"native-payout", trust_pay_key.as_ref(), &[payout_bump]];
+    let payout_signer_seeds = [payout_seeds];
+    let space = TokenAccountState::LEN;
+    let rent = Rent::get()?.minimum_balance(space);
+
+    let create_ctx = CpiContext::new_with_signer(
+        accounts.system_program.clone(),
+        CreateAccount {
+            from: accounts.funder.clone(),
+            to: accounts.payout_account.clone(),
+        },
+        &payout_signer_seeds,
+    );
+    create_account(create_ctx, rent, space as u64, accounts.token_program.key)?;
+
+    let initialize_ctx = CpiContext::new(
+        accounts.token_program.clone(),
+        InitializeAccount3 {
+            account: accounts.payout_account.clone(),
+            mint: accounts.mint.to_account_info(),
+            authority: accounts.trust_pay.clone(),
+        },
+    );
+    initialize_account3(initialize_ctx)?;
+
+    // The native mint has no transfer hook
+    let transfer_accounts = TransferChecked {
+        from: accounts.vault.clone(),
+        mint: accounts.mint.to_account_info(),
+        to: accounts.payout_account.clone(),
+        authority: accounts.trust_pay.clone(),
+    };
+    transfer_checked_with_hook(
+        accounts.token_program,
+        transfer_accounts,
+        &[],
+        amount,
+        accounts.mint.decimals,
+        signer_seeds,
+    )?;
+
+    close_native_account(
+        accounts.payout_account,
+        accounts.trust_pay,
+        to,
+        amount,
+        accounts.token_program,
+        signer_seeds,
+    )?;
+    move_lamports(accounts.trust_pay, accounts.funder, rent)
+}
+
+// Move lamports out of an account owned by this program
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    let from_lamports = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(TrustPayError::CalculationError)?;
+    let to_lamports = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(TrustPayError::CalculationError)?;
+
+    **from.try_borrow_mut_lamports()? = from_lamports;
+    **to.try_borrow_mut_lamports()? = to_lamports;
+    Ok(())
+}
+
+// Reject Token-2022 mints carrying an extension the escrow does not support
+pub fn require_supported_mint(mint: &InterfaceAccount<Mint>) -> Result<()> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner != spl_token_2022::ID {
+        return Ok(());
+    }
 
-    transfer_checked(cpi_context, *amount, mint.decimals)
+    let data = mint_info.try_borrow_data()?;
+    let state = StateWithExtensions::<MintState>::unpack(&data)?;
+    let unsupported = state
+        .get_extension_types()?
+        .iter()
+        .any(|extension| UNSUPPORTED_MINT_EXTENSIONS.contains(extension));
+    require!(!unsupported, TrustPayError::UnsupportedMintExtension);
+    Ok(())
 }
@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::error::TrustPayError;
+use crate::state::{Dispute, DisputePanel, EvidenceEntry, EvidenceLog, GlobalState, TrustPay};
+
+#[derive(Accounts)]
+pub struct SubmitEvidence<'info> {
+    #[account(mut)]
+    pub submitter: Signer<'info>,
+
+    #[account(
+        seeds = [b"trust-pay", trust_pay.payer.key().as_ref(), trust_pay.seed.to_le_bytes().as_ref()],
+        bump = trust_pay.bump,
+        constraint = trust_pay.has_active_disputes() @ TrustPayError::ContractNotDisputed
+    )]
+    pub trust_pay: Account<'info, TrustPay>,
+
+    #[account(
+        has_one = trust_pay @ TrustPayError::DisputeMismatch,
+        seeds = [b"dispute", dispute.dispute_id.to_le_bytes().as_ref()],
+        bump = dispute.bump
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    #[account(
+        init_if_needed,
+        payer = submitter,
+        space = ANCHOR_DISCRIMINATOR + EvidenceLog::INIT_SPACE,
+        seeds = [b"evidence", dispute.key().as_ref()],
+        bump
+    )]
+    pub evidence_log: Box<Account<'info, EvidenceLog>>,
+
+    #[account(
+        seeds = [b"global-state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    // Required when a panel member submits
+    #[account(
+        has_one = trust_pay,
+        seeds = [b"panel", dispute.key().as_ref()],
+        bump = panel.bump
+    )]
+    pub panel: Option<Account<'info, DisputePanel>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn submit_evidence(
+    ctx: Context<SubmitEvidence>,
+    milestone_index: u8,
+    content_hash: [u8; 32],
+    uri: String,
+    evidence_type: u8,
+) -> Result<()> {
+    let trust_pay = &ctx.accounts.trust_pay;
+    let submitter_key = ctx.accounts.submitter.key();
+
+    // Validate the milestone, or the whole contract, is under this dispute
+    trust_pay.require_open_dispute(milestone_index, ctx.accounts.dispute.dispute_id)?;
+
+    // Both parties and anyone who can rule on the dispute may submit
+    let is_panel_member = ctx
+        .accounts
+        .panel
+        .as_ref()
+        .is_some_and(|panel| panel.members.contains(&submitter_key));
+    require!(
+        submitter_key == trust_pay.payer
+            || submitter_key == trust_pay.recipient
+            || trust_pay.arbitrator == Some(submitter_key)
+            || ctx.accounts.dispute.arbitrator == Some(submitter_key)
+            || submitter_key == ctx.accounts.global_state.authority
+            || is_panel_member,
+        TrustPayError::UnauthorizedEvidenceSubmitter
+    );
+
+    // Validate the entry
+    require!(
+        !uri.is_empty()
+            && uri.len() <= MAX_EVIDENCE_URI_LEN
+            && evidence_type <= EVIDENCE_TYPE_OTHER,
+        TrustPayError::InvalidEvidence
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let trust_pay_key = trust_pay.key();
+    let evidence_log = &mut ctx.accounts.evidence_log;
+    if evidence_log.trust_pay == Pubkey::default() {
+        evidence_log.trust_pay = trust_pay_key;
+        evidence_log.milestone_index = milestone_index;
+        evidence_log.dispute_id = ctx.accounts.dispute.dispute_id;
+        evidence_log.bump = ctx.bumps.evidence_log;
+    }
+
+    evidence_log.append(EvidenceEntry {
+        submitter: submitter_key,
+        content_hash,
+        uri: uri.clone(),
+        evidence_type,
+        submitted_at: now,
+    })?;
+
+    emit!(crate::EvidenceSubmittedEvent {
+        trust_pay: trust_pay_key,
+        milestone_index,
+        dispute_id: evidence_log.dispute_id,
+        submitter: submitter_key,
+        entry_index: (evidence_log.entries.len() - 1) as u8,
+        content_hash,
+        uri,
+        evidence_type,
+        head_hash: evidence_log.head_hash,
+        submitted_at: now,
+    });
+
+    msg!(
+        "Evidence {} submitted on milestone {} by {}",
+        evidence_log.entries.len(),
+        milestone_index,
+        submitter_key
+    );
+
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use anchor_lang::prelude::*;
+
+use crate::error::TrustPayError;
+use crate::state::{ContractReview, Reputation, Review};
+
+#[derive(Accounts)]
+pub struct SubmitReview<'info> {
+    pub reviewer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"review", contract_review.trust_pay.as_ref()],
+        bump = contract_review.bump
+    )]
+    pub contract_review: Account<'info, ContractReview>,
+
+    // Receives the record's rent once both reviews are in
+    #[account(
+        mut,
+        address = contract_review.payer @ TrustPayError::ReviewMismatch
+    )]
+    pub payer: SystemAccount<'info>,
+
+    // Profile of the counterparty being rated
+    #[account(
+        mut,
+        seeds = [b"reputation", reviewee_reputation.wallet.as_ref()],
+        bump = reviewee_reputation.bump
+    )]
+    pub reviewee_reputation: Account<'info, Reputation>,
+}
+
+#[derive(Accounts)]
+pub struct CloseReview<'info> {
+    #[account(
+        mut,
+        close = payer,
+        seeds = [b"review", contract_review.trust_pay.as_ref()],
+        bump = contract_review.bump
+    )]
+    pub contract_review: Account<'info, ContractReview>,
+
+    #[account(
+        mut,
+        address = contract_review.payer @ TrustPayError::ReviewMismatch
+    )]
+    pub payer: SystemAccount<'info>,
+}
+
+pub(crate) fn submit_review(ctx: Context<SubmitReview>, score: u8, comment_hash: [u8; 32]) -> Result<()> {
+    let reviewer_key = ctx.accounts.reviewer.key();
+    let submitted_at = Clock::get()?.unix_timestamp;
+
+    // Each party of a completed contract rates the other once
+    let contract_review = &mut ctx.accounts.contract_review;
+    let reviewee = contract_review.submit(
+        reviewer_key,
+        Review {
+            score,
+            comment_hash,
+            submitted_at,
+        },
+    )?;
+    require_keys_eq!(
+        ctx.accounts.reviewee_reputation.wallet,
+        reviewee,
+        TrustPayError::ReviewMismatch
+    );
+
+    // Roll the score into the reviewee's aggregate rating
+    ctx.accounts
+        .reviewee_reputation
+        .record_review(score, submitted_at)?;
+
+    emit!(crate::ReviewSubmittedEvent {
+        trust_pay: contract_review.trust_pay,
+        reviewer: reviewer_key,
+        reviewee,
+        score,
+        comment_hash,
+        submitted_at,
+    });
+
+    msg!(
+        "Review submitted - Reviewer: {}, Reviewee: {}, Score: {}",
+        reviewer_key,
+        reviewee,
+        score
+    );
+
+    // The second review completes the record, so its rent goes back to the payer
+    if contract_review.can_close(submitted_at) {
+        contract_review.close(ctx.accounts.payer.to_account_info())?;
+        msg!("Review record closed");
+    }
+
+    Ok(())
+}
+
+// Anyone can return the rent of a review record once the review window has passed
+pub(crate) fn close_review(ctx: Context<CloseReview>) -> Result<()> {
+    let contract_review = &ctx.accounts.contract_review;
+    require!(
+        contract_review.can_close(Clock::get()?.unix_timestamp),
+        TrustPayError::ReviewWindowOpen
+    );
+
+    msg!(
+        "Review record for contract {} closed, rent returned to {}",
+        contract_review.trust_pay,
+        contract_review.payer
+    );
+
+    Ok(())
+}
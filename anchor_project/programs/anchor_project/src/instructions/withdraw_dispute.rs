@@ -0,0 +1,271 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::constants::*;
+use crate::error::TrustPayError;
+use crate::fee_math::{settle_bonds, BondOutcome};
+use crate::instructions::shared::{pay_out_native, transfer_checked_with_hook, NativePayout};
+use crate::state::{Dispute, GlobalState, MintStats, TrustPay};
+
+#[derive(Accounts)]
+pub struct WithdrawDispute<'info> {
+    #[account(mut)]
+    pub disputer: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        has_one = payer,
+        has_one = recipient,
+        has_one = mint,
+        seeds = [b"trust-pay", payer.key().as_ref(), trust_pay.seed.to_le_bytes().as_ref()],
+        bump = trust_pay.bump,
+        constraint = trust_pay.has_active_disputes() @ TrustPayError::ContractNotDisputed
+    )]
+    pub trust_pay: Account<'info, TrustPay>,
+
+    #[account(
+        mut,
+        has_one = trust_pay @ TrustPayError::DisputeMismatch,
+        seeds = [b"dispute", dispute.dispute_id.to_le_bytes().as_ref()],
+        bump = dispute.bump
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = trust_pay,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: temporary wSOL account for a native SOL payout, created and closed in this instruction
+    #[account(
+        mut,
+        seeds = [b"native-payout", trust_pay.key().as_ref()],
+        bump
+    )]
+    pub native_payout: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = disputer,
+        associated_token::mint = mint,
+        associated_token::authority = payer,
+        associated_token::token_program = token_program,
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = disputer,
+        associated_token::mint = mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"global-state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"mint-stats", mint.key().as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Box<Account<'info, MintStats>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+// The disputer drops the dispute; bonds are returned and the milestone goes back to where it was.
+// Not paused, like cancel and decline, so bonds can always return to their owners.
+pub(crate) fn withdraw_dispute<'info>(
+    ctx: Context<'_, '_, 'info, 'info, WithdrawDispute<'info>>,
+    milestone_index: u8,
+) -> Result<()> {
+    let trust_pay_seed = ctx.accounts.trust_pay.seed;
+    let trust_pay_bump = ctx.accounts.trust_pay.bump;
+    let trust_pay_key = ctx.accounts.trust_pay.key();
+    let payer_key = ctx.accounts.payer.key();
+    let disputer_key = ctx.accounts.disputer.key();
+    let mint_decimals = ctx.accounts.mint.decimals;
+
+    // Validate the milestone, or the whole contract, is under this dispute
+    let whole_contract = milestone_index == CONTRACT_DISPUTE_INDEX;
+    ctx.accounts
+        .trust_pay
+        .require_open_dispute(milestone_index, ctx.accounts.dispute.dispute_id)?;
+    require!(
+        ctx.accounts.dispute.disputer == disputer_key,
+        TrustPayError::UnauthorizedWithdrawal
+    );
+
+    let bonds = {
+        let milestone = ctx.accounts.trust_pay.dispute_state(milestone_index)?;
+
+        // Once someone has ruled, or a panel is hearing it, the dispute runs its course
+        require!(
+            milestone.ruling.is_none() && milestone.appeal_level == 0 && !milestone.panel_required,
+            TrustPayError::DisputeNotWithdrawable
+        );
+
+        settle_bonds(
+            milestone.dispute_bond,
+            milestone.counter_bond,
+            0,
+            BondOutcome::Withdrawn,
+        )?
+    };
+
+    // Return each bond to the party that posted it
+    let disputer_is_payer = disputer_key == payer_key;
+    let (bond_to_payer, bond_to_recipient) = if disputer_is_payer {
+        (bonds.to_disputer, bonds.to_counterparty)
+    } else {
+        (bonds.to_counterparty, bonds.to_disputer)
+    };
+
+    let seeds = &[
+        b"trust-pay",
+        payer_key.as_ref(),
+        &trust_pay_seed.to_le_bytes()[..],
+        &[trust_pay_bump],
+    ];
+    let signer_seeds = [&seeds[..]];
+
+    let vault_info = ctx.accounts.vault.to_account_info();
+    let mint_info = ctx.accounts.mint.to_account_info();
+    let trust_pay_info = ctx.accounts.trust_pay.to_account_info();
+    let token_program_info = ctx.accounts.token_program.to_account_info();
+    let native = ctx.accounts.vault.is_native();
+
+    for (wallet, destination, bond_amount) in [
+        (
+            ctx.accounts.payer.to_account_info(),
+            ctx.accounts.payer_token_account.to_account_info(),
+            bond_to_payer,
+        ),
+        (
+            ctx.accounts.recipient.to_account_info(),
+            ctx.accounts.recipient_token_account.to_account_info(),
+            bond_to_recipient,
+        ),
+    ] {
+        if bond_amount == 0 {
+            continue;
+        }
+
+        // A native SOL bond goes back as lamports
+        if native {
+            pay_out_native(
+                NativePayout {
+                    vault: &vault_info,
+                    mint: &ctx.accounts.mint,
+                    trust_pay: &trust_pay_info,
+                    payout_account: &ctx.accounts.native_payout.to_account_info(),
+                    funder: &ctx.accounts.disputer.to_account_info(),
+                    system_program: &ctx.accounts.system_program.to_account_info(),
+                    token_program: &token_program_info,
+                },
+                &wallet,
+                bond_amount,
+                ctx.bumps.native_payout,
+                &signer_seeds,
+            )?;
+        } else {
+            let bond_transfer_accounts = TransferChecked {
+                from: vault_info.clone(),
+                to: destination,
+                mint: mint_info.clone(),
+                authority: trust_pay_info.clone(),
+            };
+
+            transfer_checked_with_hook(
+                &token_program_info,
+                bond_transfer_accounts,
+                ctx.remaining_accounts,
+                bond_amount,
+                mint_decimals,
+                &signer_seeds,
+            )?;
+        }
+        ctx.accounts.mint_stats.record_withdrawal(bond_amount)?;
+    }
+
+    // Restore the milestone, or the contract after a contract dispute
+    let restored_status;
+    {
+        let trust_pay = &mut ctx.accounts.trust_pay;
+        let milestone = trust_pay.dispute_state_mut(milestone_index)?;
+        milestone.status = milestone.status_before_dispute;
+        milestone.disputer = None;
+        milestone.dispute_bond = 0;
+        milestone.counter_bond = 0;
+        milestone.disputed_at = None;
+        milestone.arbitrator = None;
+        milestone.settlement_offer = None;
+        restored_status = if whole_contract {
+            trust_pay.contract_status = CONTRACT_STATUS_IN_PROGRESS;
+            CONTRACT_STATUS_IN_PROGRESS
+        } else {
+            milestone.status
+        };
+    }
+
+    let withdrawn_at = Clock::get()?.unix_timestamp;
+    {
+        let dispute = &mut ctx.accounts.dispute;
+        dispute.status = DISPUTE_STATUS_WITHDRAWN;
+        dispute.resolved_by = Some(disputer_key);
+        dispute.updated_at = withdrawn_at;
+        dispute.closed_at = Some(withdrawn_at);
+    }
+
+    // Update global state
+    {
+        let global_state = &mut ctx.accounts.global_state;
+        global_state.total_disputes_withdrawn = global_state
+            .total_disputes_withdrawn
+            .checked_add(1)
+            .ok_or(TrustPayError::CalculationError)?;
+    }
+    ctx.accounts.mint_stats.record_dispute_closed()?;
+
+    emit!(crate::DisputeWithdrawnEvent {
+        trust_pay: trust_pay_key,
+        dispute_id: ctx.accounts.dispute.dispute_id,
+        milestone_index,
+        disputer: disputer_key,
+        restored_status,
+        bond_to_payer,
+        bond_to_recipient,
+        contract_status: ctx.accounts.trust_pay.contract_status,
+        withdrawn_at,
+    });
+
+    msg!(
+        "Dispute withdrawn - Milestone {}, Disputer: {}",
+        milestone_index,
+        disputer_key
+    );
+
+    Ok(())
+}
@@ -0,0 +1,179 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::constants::PAUSE_PAYOUTS;
+use crate::error::TrustPayError;
+use crate::instructions::shared::transfer_checked_with_hook;
+use crate::state::{GlobalState, MintStats, Treasury};
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"global-state"],
+        bump = global_state.bump,
+        has_one = authority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        has_one = mint,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump = treasury.bump
+    )]
+    pub treasury: Box<Account<'info, Treasury>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Any wallet the authority chooses to receive the withdrawn fees
+    pub destination: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = mint,
+        associated_token::authority = destination,
+        associated_token::token_program = token_program
+    )]
+    pub destination_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+pub(crate) fn withdraw_fees<'info>(
+    ctx: Context<'_, '_, 'info, 'info, WithdrawFees<'info>>,
+    amount: u64,
+) -> Result<()> {
+    require!(
+        !ctx.accounts
+            .global_state
+            .is_paused(PAUSE_PAYOUTS, Clock::get()?.unix_timestamp),
+        TrustPayError::OperationPaused
+    );
+    require!(amount > 0, TrustPayError::InvalidAmount);
+    require!(
+        amount <= ctx.accounts.treasury.available_fees(),
+        TrustPayError::InsufficientTreasuryFunds
+    );
+
+    let mint_key = ctx.accounts.mint.key();
+    let treasury_bump = ctx.accounts.treasury.bump;
+
+    // Create PDA signer seeds
+    let seeds = &[b"treasury", mint_key.as_ref(), &[treasury_bump]];
+    let signer_seeds = [&seeds[..]];
+
+    let transfer_accounts = TransferChecked {
+        from: ctx.accounts.treasury_token_account.to_account_info(),
+        to: ctx.accounts.destination_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        authority: ctx.accounts.treasury.to_account_info(),
+    };
+
+    transfer_checked_with_hook(
+        &ctx.accounts.token_program.to_account_info(),
+        transfer_accounts,
+        ctx.remaining_accounts,
+        amount,
+        ctx.accounts.mint.decimals,
+        &signer_seeds,
+    )?;
+
+    // Update the treasury ledger
+    let current_time = Clock::get()?.unix_timestamp;
+    let treasury = &mut ctx.accounts.treasury;
+    treasury.total_fees_withdrawn = treasury
+        .total_fees_withdrawn
+        .checked_add(amount)
+        .ok_or(TrustPayError::CalculationError)?;
+    treasury.withdrawal_count = treasury
+        .withdrawal_count
+        .checked_add(1)
+        .ok_or(TrustPayError::CalculationError)?;
+    treasury.last_withdrawal_at = current_time;
+
+    emit!(crate::FeesWithdrawnEvent {
+        treasury: treasury.key(),
+        mint: mint_key,
+        authority: ctx.accounts.authority.key(),
+        destination: ctx.accounts.destination.key(),
+        amount,
+        total_fees_withdrawn: treasury.total_fees_withdrawn,
+        available_fees: treasury.available_fees(),
+        withdrawn_at: current_time,
+    });
+
+    msg!(
+        "Withdrew {} fees from treasury {} to {}, {} remaining",
+        amount,
+        treasury.key(),
+        ctx.accounts.destination.key(),
+        treasury.available_fees()
+    );
+
+    Ok(())
+}
+
+// Set up a freshly created treasury for its mint
+pub fn initialize_treasury_if_needed(treasury: &mut Account<Treasury>, mint: Pubkey, bump: u8) {
+    if treasury.mint == Pubkey::default() {
+        treasury.mint = mint;
+        treasury.total_fees_collected = 0;
+        treasury.total_fees_withdrawn = 0;
+        treasury.withdrawal_count = 0;
+        treasury.last_collection_at = 0;
+        treasury.last_withdrawal_at = 0;
+        treasury.bump = bump;
+
+        msg!("Treasury initialized for mint: {}", mint);
+    }
+}
+
+// Record fees that have just been transferred into the treasury vault
+pub fn record_fee_collection(
+    treasury: &mut Account<Treasury>,
+    mint_stats: &mut MintStats,
+    trust_pay: Pubkey,
+    fee_amount: u64,
+) -> Result<()> {
+    if fee_amount == 0 {
+        return Ok(());
+    }
+
+    let current_time = Clock::get()?.unix_timestamp;
+    treasury.total_fees_collected = treasury
+        .total_fees_collected
+        .checked_add(fee_amount)
+        .ok_or(TrustPayError::CalculationError)?;
+    treasury.last_collection_at = current_time;
+
+    mint_stats.record_fee(fee_amount)?;
+
+    emit!(crate::FeeCollectedEvent {
+        treasury: treasury.key(),
+        mint: treasury.mint,
+        trust_pay,
+        amount: fee_amount,
+        total_fees_collected: treasury.total_fees_collected,
+        collected_at: current_time,
+    });
+
+    Ok(())
+}
@@ -9,6 +9,7 @@ pub mod constants;
 pub use constants::*;
 pub mod events;
 pub use events::*;
+pub mod fee_math;
 
 declare_id!("6fhEuzQ54WFq1fq2BwPp3GLC23NJsswPResuwdoq41Bs");
 
@@ -16,8 +17,137 @@ declare_id!("6fhEuzQ54WFq1fq2BwPp3GLC23NJsswPResuwdoq41Bs");
 pub mod anchor_project {
     use super::*;
 
-    pub fn create_contract(
-        ctx: Context<CreateContract>,
+    pub fn initialize_global_state(ctx: Context<InitializeGlobalState>) -> Result<()> {
+        global_state::initialize_global_state(ctx)
+    }
+
+    pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+        global_state::propose_authority(ctx, new_authority)
+    }
+
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        global_state::accept_authority(ctx)
+    }
+
+    pub fn set_pause(ctx: Context<SetPause>, pause_flags: u8, paused_until: i64) -> Result<()> {
+        global_state::set_pause(ctx, pause_flags, paused_until)
+    }
+
+    pub fn register_arbitrator(
+        ctx: Context<RegisterArbitrator>,
+        arbitrator: Pubkey,
+        specialties: Vec<String>,
+    ) -> Result<()> {
+        manage_arbitrators::register_arbitrator(ctx, arbitrator, specialties)
+    }
+
+    pub fn update_arbitrator(
+        ctx: Context<UpdateArbitrator>,
+        active: bool,
+        specialties: Vec<String>,
+    ) -> Result<()> {
+        manage_arbitrators::update_arbitrator(ctx, active, specialties)
+    }
+
+    pub fn assign_dispute(ctx: Context<AssignDispute>, milestone_index: u8) -> Result<()> {
+        assign_dispute::assign_dispute(ctx, milestone_index)
+    }
+
+    pub fn open_panel<'info>(
+        ctx: Context<'_, '_, 'info, 'info, OpenPanel<'info>>,
+        milestone_index: u8,
+        members: Vec<Pubkey>,
+    ) -> Result<()> {
+        panel_voting::open_panel(ctx, milestone_index, members)
+    }
+
+    pub fn cast_panel_vote(
+        ctx: Context<CastPanelVote>,
+        milestone_index: u8,
+        recipient_share_bps: u16,
+    ) -> Result<()> {
+        panel_voting::cast_panel_vote(ctx, milestone_index, recipient_share_bps)
+    }
+
+    pub fn reassign_panel<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReassignPanel<'info>>,
+        milestone_index: u8,
+        members: Vec<Pubkey>,
+    ) -> Result<()> {
+        panel_voting::reassign_panel(ctx, milestone_index, members)
+    }
+
+    pub fn execute_panel_decision<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecutePanelDecision<'info>>,
+        milestone_index: u8,
+    ) -> Result<()> {
+        panel_voting::execute_panel_decision(ctx, milestone_index)
+    }
+
+    pub fn appeal_ruling<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AppealRuling<'info>>,
+        milestone_index: u8,
+    ) -> Result<()> {
+        appeal_dispute::appeal_ruling(ctx, milestone_index)
+    }
+
+    pub fn finalize_ruling<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FinalizeRuling<'info>>,
+        milestone_index: u8,
+    ) -> Result<()> {
+        appeal_dispute::finalize_ruling(ctx, milestone_index)
+    }
+
+    pub fn submit_evidence(
+        ctx: Context<SubmitEvidence>,
+        milestone_index: u8,
+        content_hash: [u8; 32],
+        uri: String,
+        evidence_type: u8,
+    ) -> Result<()> {
+        submit_evidence::submit_evidence(ctx, milestone_index, content_hash, uri, evidence_type)
+    }
+
+    pub fn respond_to_dispute(
+        ctx: Context<RespondToDispute>,
+        milestone_index: u8,
+        response: String,
+    ) -> Result<()> {
+        respond_to_dispute::respond_to_dispute(ctx, milestone_index, response)
+    }
+
+    pub fn offer_settlement(
+        ctx: Context<OfferSettlement>,
+        milestone_index: u8,
+        recipient_share_bps: u16,
+    ) -> Result<()> {
+        negotiate_settlement::offer_settlement(ctx, milestone_index, recipient_share_bps)
+    }
+
+    pub fn accept_settlement<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AcceptSettlement<'info>>,
+        milestone_index: u8,
+        recipient_share_bps: u16,
+    ) -> Result<()> {
+        negotiate_settlement::accept_settlement(ctx, milestone_index, recipient_share_bps)
+    }
+
+    pub fn finalize_stale_dispute<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FinalizeStaleDispute<'info>>,
+        milestone_index: u8,
+    ) -> Result<()> {
+        finalize_stale_dispute::finalize_stale_dispute(ctx, milestone_index)
+    }
+
+    pub fn withdraw_dispute<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawDispute<'info>>,
+        milestone_index: u8,
+    ) -> Result<()> {
+        withdraw_dispute::withdraw_dispute(ctx, milestone_index)
+    }
+
+    pub fn create_contract<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateContract<'info>>,
         seed: u64,
         creator_role: u8,
         payer_pubkey: Pubkey,
@@ -28,6 +158,7 @@ pub mod anchor_project {
         total_amount: u64,
         milestone_inputs: Vec<create_contract::MilestoneInput>,
         deadline_duration_seconds: u64,
+        arbitrator: Option<Pubkey>,
     ) -> Result<()> {
         create_contract::create_contract(
             ctx,
@@ -41,21 +172,26 @@ pub mod anchor_project {
             total_amount,
             milestone_inputs,
             deadline_duration_seconds,
+            arbitrator,
         )
     }
 
-    pub fn cancel_contract(ctx: Context<CancelContract>) -> Result<()> {
+    pub fn cancel_contract<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CancelContract<'info>>,
+    ) -> Result<()> {
         cancel_contract::cancel_contract(ctx)
     }
 
-    pub fn accept_contract(
-        ctx: Context<AcceptContract>,
+    pub fn accept_contract<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AcceptContract<'info>>,
         deadline_duration_seconds: u64,
     ) -> Result<()> {
         accept_contract::accept_contract(ctx, deadline_duration_seconds)
     }
 
-    pub fn decline_contract(ctx: Context<DeclineContract>) -> Result<()> {
+    pub fn decline_contract<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DeclineContract<'info>>,
+    ) -> Result<()> {
         decline_contract::decline_contract(ctx)
     }
 
@@ -66,27 +202,120 @@ pub mod anchor_project {
         mark_as_complete::mark_milestone_complete(ctx, milestone_index)
     }
 
-    pub fn approve_milestone_payment(
-        ctx: Context<ApproveMilestonePayment>,
+    pub fn approve_milestone_payment<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ApproveMilestonePayment<'info>>,
         milestone_index: u8,
     ) -> Result<()> {
         approve_payment::approve_milestone_payment(ctx, milestone_index)
     }
 
-    pub fn dispute_contract(
-        ctx: Context<DisputeContract>,
+    pub fn submit_review(
+        ctx: Context<SubmitReview>,
+        score: u8,
+        comment_hash: [u8; 32],
+    ) -> Result<()> {
+        submit_review::submit_review(ctx, score, comment_hash)
+    }
+
+    pub fn close_review(ctx: Context<CloseReview>) -> Result<()> {
+        submit_review::close_review(ctx)
+    }
+
+    pub fn dispute_contract<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DisputeContract<'info>>,
         milestone_index: u8,
         dispute_reason: String,
     ) -> Result<()> {
         dispute_contract::dispute_contract(ctx, milestone_index, dispute_reason)
     }
 
-    pub fn resolve_dispute(
-        ctx: Context<ResolveDispute>,
+    pub fn resolve_dispute<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ResolveDispute<'info>>,
         milestone_index: u8,
+        recipient_share_bps: u16,
         resolution: u8,
         resolution_reason: String,
     ) -> Result<()> {
-        resolve_dispute::resolve_dispute(ctx, milestone_index, resolution, resolution_reason)
+        resolve_dispute::resolve_dispute(
+            ctx,
+            milestone_index,
+            recipient_share_bps,
+            resolution,
+            resolution_reason,
+        )
+    }
+
+    pub fn match_dispute_bond<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MatchDisputeBond<'info>>,
+        milestone_index: u8,
+    ) -> Result<()> {
+        match_dispute_bond::match_dispute_bond(ctx, milestone_index)
+    }
+
+    pub fn update_dispute_config(
+        ctx: Context<UpdateDisputeConfig>,
+        dispute_bond_bps: u16,
+        arbitration_fee_bps: u16,
+    ) -> Result<()> {
+        global_state::update_dispute_config(ctx, dispute_bond_bps, arbitration_fee_bps)
+    }
+
+    pub fn update_panel_config(
+        ctx: Context<UpdateDisputeConfig>,
+        panel_threshold: u64,
+        panel_size: u8,
+        panel_quorum: u8,
+        panel_vote_period: i64,
+    ) -> Result<()> {
+        global_state::update_panel_config(
+            ctx,
+            panel_threshold,
+            panel_size,
+            panel_quorum,
+            panel_vote_period,
+        )
+    }
+
+    pub fn update_appeal_config(
+        ctx: Context<UpdateDisputeConfig>,
+        appeal_window: i64,
+        appeal_bond_bps: u16,
+    ) -> Result<()> {
+        global_state::update_appeal_config(ctx, appeal_window, appeal_bond_bps)
+    }
+
+    pub fn update_timeout_config(
+        ctx: Context<UpdateDisputeConfig>,
+        max_resolution_time: i64,
+        default_recipient_share_bps: u16,
+    ) -> Result<()> {
+        global_state::update_timeout_config(ctx, max_resolution_time, default_recipient_share_bps)
+    }
+
+    pub fn withdraw_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawFees<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        withdraw_fees::withdraw_fees(ctx, amount)
+    }
+
+    pub fn set_fee_caps(ctx: Context<SetFeeCaps>, min_fee: u64, max_fee: u64) -> Result<()> {
+        set_fee_caps::set_fee_caps(ctx, min_fee, max_fee)
+    }
+
+    pub fn configure_mint(
+        ctx: Context<ConfigureMint>,
+        enabled: bool,
+        min_contract_amount: u64,
+        max_contract_amount: u64,
+        fee_override: Option<u16>,
+    ) -> Result<()> {
+        configure_mint::configure_mint(
+            ctx,
+            enabled,
+            min_contract_amount,
+            max_contract_amount,
+            fee_override,
+        )
     }
 }
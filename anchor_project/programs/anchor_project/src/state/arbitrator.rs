@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::error::TrustPayError;
+
+#[account]
+#[derive(InitSpace)]
+pub struct Arbitrator {
+    pub arbitrator: Pubkey,
+    pub active: bool,
+    #[max_len(5, 32)]
+    pub specialties: Vec<String>,
+    pub cases_assigned: u64,
+    pub cases_resolved: u64,
+    pub total_resolution_time: i64, // Seconds from dispute to resolution, summed over cases
+    pub average_resolution_time: i64,
+    pub registered_at: i64,
+    pub bump: u8,
+}
+
+impl Arbitrator {
+    pub fn record_resolution(&mut self, resolution_time: i64) -> Result<()> {
+        self.cases_resolved = self
+            .cases_resolved
+            .checked_add(1)
+            .ok_or(TrustPayError::CalculationError)?;
+        self.total_resolution_time = self
+            .total_resolution_time
+            .checked_add(resolution_time.max(0))
+            .ok_or(TrustPayError::CalculationError)?;
+        self.average_resolution_time = self.total_resolution_time / self.cases_resolved as i64;
+        Ok(())
+    }
+}
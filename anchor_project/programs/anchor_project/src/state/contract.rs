@@ -1,4 +1,5 @@
 use crate::constants::*;
+use crate::error::TrustPayError;
 use anchor_lang::prelude::*;
 
 #[account]
@@ -20,8 +21,14 @@ pub struct TrustPay {
     pub fee_percentage: u16,
     pub fee_destination: Pubkey,
     pub fee: u64,
+    pub fee_deposited: u64,
+    pub fee_charged: u64,
+    pub amount_released: u64, // Paid out to the recipient
+    pub amount_refunded: u64, // Returned to the payer before closing
     #[max_len(10)]
     pub milestones: Vec<Milestone>,
+    pub arbitrator: Option<Pubkey>, // Contract-specific arbitrator, replaces the protocol resolver
+    pub contract_dispute: Milestone, // Dispute over the whole contract, DISPUTED while open
     pub bump: u8,
 }
 
@@ -30,13 +37,70 @@ pub struct Milestone {
     #[max_len(200)]
     pub description: String,
     pub amount: u64,
+    pub fee: u64,
     pub status: u8,
     pub completed_at: Option<i64>,
     pub approved_at: Option<i64>,
-    #[max_len(300)]
-    pub dispute_reason: Option<String>,
-    #[max_len(6)]
-    pub dispute_id: Option<String>,
+    pub dispute_id: Option<u64>, // Dispute PDA of the current or last dispute
+    pub disputer: Option<Pubkey>,
+    pub dispute_bond: u64, // Posted by the disputer
+    pub counter_bond: u64, // Matched by the counterparty
+    pub disputed_at: Option<i64>,
+    pub escalated_at: Option<i64>, // Last appeal or panel seating, restarts the dispute timeout
+    pub arbitrator: Option<Pubkey>, // Assigned from the arbitrator registry
+    pub panel_required: bool,      // High-value dispute decided by an arbitrator panel
+    pub ruling: Option<u16>,       // Recipient share waiting out the appeal window
+    pub ruling_resolution: u8,     // RESOLUTION_* the pending ruling settles with
+    pub ruled_by: Option<Pubkey>,
+    pub ruled_at: Option<i64>,
+    pub appeal_deadline: Option<i64>,
+    pub appeal_level: u8, // 0 = first instance, 1 = on appeal
+    pub appellant: Option<Pubkey>,
+    pub appeal_bond: u64,
+    pub appealed_ruling: Option<u16>,
+    pub appealed_resolution: u8,
+    pub settlement_offer: Option<u16>, // Recipient share offered by one of the parties
+    pub settlement_offered_by: u8,     // ROLE_PAYER or ROLE_RECIPIENT
+    pub status_before_dispute: u8,     // Restored if the dispute is withdrawn
+}
+
+impl Milestone {
+    pub fn new(description: String, amount: u64, fee: u64) -> Self {
+        Self {
+            description,
+            amount,
+            fee,
+            status: MILESTONE_STATUS_PENDING,
+            completed_at: None,
+            approved_at: None,
+            dispute_id: None,
+            disputer: None,
+            dispute_bond: 0,
+            counter_bond: 0,
+            disputed_at: None,
+            escalated_at: None,
+            arbitrator: None,
+            panel_required: false,
+            ruling: None,
+            ruling_resolution: RESOLUTION_SPLIT,
+            ruled_by: None,
+            ruled_at: None,
+            appeal_deadline: None,
+            appeal_level: 0,
+            appellant: None,
+            appeal_bond: 0,
+            appealed_ruling: None,
+            appealed_resolution: RESOLUTION_SPLIT,
+            settlement_offer: None,
+            settlement_offered_by: ROLE_PAYER,
+            status_before_dispute: MILESTONE_STATUS_PENDING,
+        }
+    }
+
+    // Not yet paid out or refunded, and not under dispute
+    pub fn is_unsettled(&self) -> bool {
+        self.status == MILESTONE_STATUS_PENDING || self.status == MILESTONE_STATUS_COMPLETED_BY_SP
+    }
 }
 
 impl TrustPay {
@@ -46,6 +110,12 @@ impl TrustPay {
             .all(|m| m.status == MILESTONE_STATUS_APPROVED_BY_PAYER)
     }
 
+    pub fn is_all_milestones_settled(&self) -> bool {
+        self.milestones.iter().all(|m| {
+            m.status == MILESTONE_STATUS_APPROVED_BY_PAYER || m.status == MILESTONE_STATUS_RESOLVED
+        })
+    }
+
     pub fn get_total_approved_amount(&self) -> u64 {
         self.milestones
             .iter()
@@ -59,27 +129,113 @@ impl TrustPay {
         self.total_contract_amount.saturating_sub(approved_amount)
     }
 
-    pub fn has_active_disputes(&self) -> bool {
-        self.milestones
+    // What funding the contract added to its mint's TVL; a pending contract has not been funded
+    pub fn get_tracked_deposit(&self) -> Result<u64> {
+        if self.contract_status == CONTRACT_STATUS_PENDING {
+            return Ok(0);
+        }
+        let deposit = self
+            .total_contract_amount
+            .checked_add(self.fee_deposited)
+            .ok_or(TrustPayError::CalculationError)?;
+        Ok(deposit)
+    }
+
+    // Value of the milestones not yet paid out, refunded or under dispute
+    pub fn get_unsettled_amount(&self) -> Result<u64> {
+        let total = self
+            .milestones
             .iter()
-            .any(|m| m.status == MILESTONE_STATUS_DISPUTED)
+            .filter(|m| m.is_unsettled())
+            .try_fold(0u64, |total, m| total.checked_add(m.amount))
+            .ok_or(TrustPayError::CalculationError)?;
+        Ok(total)
+    }
+
+    pub fn has_active_disputes(&self) -> bool {
+        self.contract_dispute.status == MILESTONE_STATUS_DISPUTED
+            || self
+                .milestones
+                .iter()
+                .any(|m| m.status == MILESTONE_STATUS_DISPUTED)
+    }
+
+    // Dispute state of a milestone, or of the whole contract for CONTRACT_DISPUTE_INDEX
+    pub fn dispute_state(&self, milestone_index: u8) -> Result<&Milestone> {
+        if milestone_index == CONTRACT_DISPUTE_INDEX {
+            return Ok(&self.contract_dispute);
+        }
+        let milestone = self
+            .milestones
+            .get(milestone_index as usize)
+            .ok_or(TrustPayError::InvalidMilestoneIndex)?;
+        Ok(milestone)
+    }
+
+    pub fn dispute_state_mut(&mut self, milestone_index: u8) -> Result<&mut Milestone> {
+        if milestone_index == CONTRACT_DISPUTE_INDEX {
+            return Ok(&mut self.contract_dispute);
+        }
+        let milestone = self
+            .milestones
+            .get_mut(milestone_index as usize)
+            .ok_or(TrustPayError::InvalidMilestoneIndex)?;
+        Ok(milestone)
+    }
+
+    // Check `dispute_id` is the open dispute on a milestone, or on the whole contract
+    pub fn require_open_dispute(&self, milestone_index: u8, dispute_id: u64) -> Result<()> {
+        let milestone = self.dispute_state(milestone_index)?;
+        require!(
+            milestone.status == MILESTONE_STATUS_DISPUTED,
+            TrustPayError::MilestoneNotDisputed
+        );
+        require!(
+            milestone.dispute_id == Some(dispute_id),
+            TrustPayError::DisputeMismatch
+        );
+        Ok(())
     }
 }
 
 #[account]
 #[derive(InitSpace)]
 pub struct GlobalState {
+    // Fields up to bump keep the original account layout; new fields are appended after it
     pub authority: Pubkey,
     pub total_trust_pay_created: u64,
     pub total_trust_pay_closed: u64,
     pub total_confirmations: u64,
     pub fee_percentage: u16,
     pub fee_destination: Pubkey,
-    pub total_fees_collected: u64,
-    pub total_disputes: u64,
-    pub total_volume: u64,
-    pub token_decimals: u8,
-    pub high_watermark_volume: u64,
+    pub reserved_fees: [u8; 8], // Unused; fees are tracked per mint in MintStats
+    pub total_disputes: u64,    // Also numbers the Dispute PDAs, so it never decreases
+    pub reserved_volume: [u8; 17], // Unused; volume is per mint in MintStats, decimals in MintConfig
     pub last_volume_update: i64,
     pub bump: u8,
+    pub pending_authority: Option<Pubkey>, // Proposed authority awaiting acceptance
+    pub total_disputes_withdrawn: u64,
+    pub dispute_bond_bps: u16,    // Bond required to open a dispute
+    pub arbitration_fee_bps: u16, // Share of the bond pool paid to the resolver
+    pub pause_flags: u8,          // PAUSE_* bitmask
+    pub paused_until: i64,        // Flags lapse at this time (0 = until unpaused)
+    pub panel_threshold: u64,     // Disputed amount that requires a panel (0 = disabled)
+    pub panel_size: u8,
+    pub panel_quorum: u8,
+    pub panel_vote_period: i64, // Seconds a panel has to reach quorum
+    pub appeal_window: i64,     // Seconds a ruling can be appealed (0 = rulings are final)
+    pub appeal_bond_bps: u16,
+    pub max_resolution_time: i64, // Seconds before an unresolved dispute can be finalized (0 = never)
+    pub default_recipient_share_bps: u16, // Outcome applied to a dispute that times out
+}
+
+impl GlobalState {
+    // High-value disputes go to a panel unless the contract names its own arbitrator
+    pub fn requires_panel(&self, trust_pay: &TrustPay, amount: u64) -> bool {
+        trust_pay.arbitrator.is_none() && self.panel_threshold > 0 && amount >= self.panel_threshold
+    }
+
+    pub fn is_paused(&self, flag: u8, now: i64) -> bool {
+        self.pause_flags & flag != 0 && (self.paused_until == 0 || now < self.paused_until)
+    }
 }
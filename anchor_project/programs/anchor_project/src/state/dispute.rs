@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct Dispute {
+    pub dispute_id: u64, // GlobalState.total_disputes when the dispute was opened
+    pub trust_pay: Pubkey,
+    pub milestone_index: u8, // CONTRACT_DISPUTE_INDEX for a dispute over the whole contract
+    pub payer: Pubkey,
+    pub recipient: Pubkey,
+    pub disputer: Pubkey,
+    #[max_len(500)]
+    pub reason: String,
+    #[max_len(200)]
+    pub response: Option<String>, // Counter-statement of the non-disputing party
+    pub amount: u64,
+    pub status: u8,
+    pub arbitrator: Option<Pubkey>,
+    pub outcome: Option<u16>, // Recipient share the dispute was closed with
+    pub resolved_by: Option<Pubkey>,
+    pub opened_at: i64,
+    pub updated_at: i64,
+    pub closed_at: Option<i64>,
+    pub bump: u8,
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct DisputePanel {
+    pub trust_pay: Pubkey,
+    pub milestone_index: u8,
+    #[max_len(5)]
+    pub members: Vec<Pubkey>,
+    #[max_len(5)]
+    pub votes: Vec<Option<u16>>, // Recipient share cast by each member, in member order
+    pub quorum: u8,
+    pub round: u8, // Incremented each time the panel is reassigned
+    pub opened_at: i64,
+    pub voting_deadline: i64,
+    pub status: u8,
+    pub decision: Option<u16>,
+    pub bump: u8,
+}
+
+impl DisputePanel {
+    fn count(&self, recipient_share_bps: u16) -> usize {
+        self.votes
+            .iter()
+            .filter(|v| **v == Some(recipient_share_bps))
+            .count()
+    }
+
+    // The recipient share backed by a quorum of members, if any
+    pub fn tally(&self) -> Option<u16> {
+        self.votes
+            .iter()
+            .flatten()
+            .copied()
+            .find(|share| self.count(*share) >= self.quorum as usize)
+    }
+
+    // Voting has timed out, or the outstanding votes can no longer form a quorum
+    pub fn is_deadlocked(&self, now: i64) -> bool {
+        if self.status != PANEL_STATUS_VOTING {
+            return false;
+        }
+        let outstanding = self.votes.iter().filter(|v| v.is_none()).count();
+        let reachable = outstanding >= self.quorum as usize
+            || self
+                .votes
+                .iter()
+                .flatten()
+                .any(|share| self.count(*share) + outstanding >= self.quorum as usize);
+        now > self.voting_deadline || !reachable
+    }
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+use crate::constants::*;
+use crate::error::TrustPayError;
+
+#[account]
+#[derive(InitSpace)]
+pub struct EvidenceLog {
+    pub trust_pay: Pubkey,
+    pub milestone_index: u8,
+    pub dispute_id: u64,
+    #[max_len(20)]
+    pub entries: Vec<EvidenceEntry>,
+    pub head_hash: [u8; 32], // Hash chain over every entry, in submission order
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct EvidenceEntry {
+    pub submitter: Pubkey,
+    pub content_hash: [u8; 32], // Hash of the off-chain material
+    #[max_len(200)]
+    pub uri: String,
+    pub evidence_type: u8,
+    pub submitted_at: i64,
+}
+
+impl EvidenceLog {
+    // Entries are append-only; each one is folded into the head hash
+    pub fn append(&mut self, entry: EvidenceEntry) -> Result<()> {
+        require!(
+            self.entries.len() < MAX_EVIDENCE_ENTRIES,
+            TrustPayError::EvidenceLogFull
+        );
+        self.head_hash = hashv(&[&self.head_hash, &entry.try_to_vec()?]).to_bytes();
+        self.entries.push(entry);
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::DEFAULT_FEE_PERCENTAGE;
+
+// Admin settings for a mint; contracts can only be created on enabled mints
+#[account]
+#[derive(InitSpace)]
+pub struct MintConfig {
+    pub mint: Pubkey,
+    pub decimals: u8,
+    pub enabled: bool,
+    pub min_contract_amount: u64,
+    pub max_contract_amount: u64,  // 0 = no maximum
+    pub fee_override: Option<u16>, // Fee in basis points replacing the default
+    pub min_fee: u64,
+    pub max_fee: u64,     // 0 = no maximum
+    pub treasury: Pubkey, // Treasury PDA collecting this mint's fees
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl MintConfig {
+    pub fn allows_amount(&self, amount: u64) -> bool {
+        amount >= self.min_contract_amount
+            && (self.max_contract_amount == 0 || amount <= self.max_contract_amount)
+    }
+
+    pub fn fee_percentage(&self) -> u16 {
+        self.fee_override.unwrap_or(DEFAULT_FEE_PERCENTAGE)
+    }
+}
@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+
+use crate::error::TrustPayError;
+
+// Protocol statistics for a single mint, so amounts are never summed across mints
+#[account]
+#[derive(InitSpace)]
+pub struct MintStats {
+    pub mint: Pubkey,
+    pub contracts_created: u64,
+    pub contracts_closed: u64, // Completed, cancelled or declined
+    pub open_escrows: u64,     // Funded contracts not yet closed
+    pub total_volume: u64,     // Amount released to recipients
+    pub total_fees_collected: u64,
+    pub disputes_opened: u64,
+    pub disputes_closed: u64,    // Settled, resolved, timed out or withdrawn
+    pub total_value_locked: u64, // Currently held in this mint's contract vaults
+    pub high_watermark_tvl: u64,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl MintStats {
+    fn touch(&mut self) -> Result<()> {
+        self.updated_at = Clock::get()?.unix_timestamp;
+        Ok(())
+    }
+
+    pub fn record_contract_created(&mut self) -> Result<()> {
+        self.contracts_created = self
+            .contracts_created
+            .checked_add(1)
+            .ok_or(TrustPayError::CalculationError)?;
+        self.touch()
+    }
+
+    pub fn record_contract_closed(&mut self) -> Result<()> {
+        self.contracts_closed = self
+            .contracts_closed
+            .checked_add(1)
+            .ok_or(TrustPayError::CalculationError)?;
+        self.touch()
+    }
+
+    // The contract's deposit has landed in its vault
+    pub fn record_escrow_opened(&mut self, deposit: u64) -> Result<()> {
+        self.open_escrows = self
+            .open_escrows
+            .checked_add(1)
+            .ok_or(TrustPayError::CalculationError)?;
+        self.record_deposit(deposit)
+    }
+
+    pub fn record_escrow_closed(&mut self) -> Result<()> {
+        self.open_escrows = self.open_escrows.saturating_sub(1);
+        self.record_contract_closed()
+    }
+
+    pub fn record_deposit(&mut self, amount: u64) -> Result<()> {
+        self.total_value_locked = self
+            .total_value_locked
+            .checked_add(amount)
+            .ok_or(TrustPayError::CalculationError)?;
+        self.high_watermark_tvl = self.high_watermark_tvl.max(self.total_value_locked);
+        self.touch()
+    }
+
+    // Saturating, since tokens sent straight to a vault are paid out without being deposited here
+    pub fn record_withdrawal(&mut self, amount: u64) -> Result<()> {
+        self.total_value_locked = self.total_value_locked.saturating_sub(amount);
+        self.touch()
+    }
+
+    pub fn record_volume(&mut self, amount: u64) -> Result<()> {
+        self.total_volume = self
+            .total_volume
+            .checked_add(amount)
+            .ok_or(TrustPayError::CalculationError)?;
+        self.touch()
+    }
+
+    pub fn record_fee(&mut self, amount: u64) -> Result<()> {
+        self.total_fees_collected = self
+            .total_fees_collected
+            .checked_add(amount)
+            .ok_or(TrustPayError::CalculationError)?;
+        self.touch()
+    }
+
+    pub fn record_dispute_opened(&mut self) -> Result<()> {
+        self.disputes_opened = self
+            .disputes_opened
+            .checked_add(1)
+            .ok_or(TrustPayError::CalculationError)?;
+        self.touch()
+    }
+
+    pub fn record_dispute_closed(&mut self) -> Result<()> {
+        self.disputes_closed = self
+            .disputes_closed
+            .checked_add(1)
+            .ok_or(TrustPayError::CalculationError)?;
+        self.touch()
+    }
+}
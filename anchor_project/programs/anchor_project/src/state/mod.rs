@@ -1,2 +1,29 @@
 pub mod contract;
 pub use contract::*;
+
+pub mod treasury;
+pub use treasury::*;
+
+pub mod arbitrator;
+pub use arbitrator::*;
+
+pub mod dispute_panel;
+pub use dispute_panel::*;
+
+pub mod evidence_log;
+pub use evidence_log::*;
+
+pub mod dispute;
+pub use dispute::*;
+
+pub mod reputation;
+pub use reputation::*;
+
+pub mod review;
+pub use review::*;
+
+pub mod mint_config;
+pub use mint_config::*;
+
+pub mod mint_stats;
+pub use mint_stats::*;
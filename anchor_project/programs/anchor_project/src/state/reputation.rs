@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+
+use crate::error::TrustPayError;
+
+// Track record of a wallet across every contract, maintained by the program only
+#[account]
+#[derive(InitSpace)]
+pub struct Reputation {
+    pub wallet: Pubkey,
+    pub contracts_completed: u64,
+    pub volume: u64, // Amount released to recipients on completed contracts
+    pub disputes_opened: u64,
+    pub disputes_lost: u64, // Adjudicated entirely against this wallet
+    pub cancellations: u64,
+    pub declines: u64,
+    pub approvals: u64,
+    pub total_approval_latency: i64, // Seconds from completion to approval, summed over approvals
+    pub average_approval_latency: i64,
+    pub reviews_received: u64,
+    pub total_review_score: u64,
+    pub average_review_score: u64, // Scaled by 100, e.g. 450 for 4.5
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl Reputation {
+    // Fill in a profile created by init_if_needed; existing profiles are left alone
+    pub fn open_if_new(&mut self, wallet: Pubkey, bump: u8, now: i64) {
+        if self.wallet == Pubkey::default() {
+            self.wallet = wallet;
+            self.created_at = now;
+            self.updated_at = now;
+            self.bump = bump;
+        }
+    }
+
+    pub fn record_completion(&mut self, volume: u64, now: i64) -> Result<()> {
+        self.contracts_completed = self
+            .contracts_completed
+            .checked_add(1)
+            .ok_or(TrustPayError::CalculationError)?;
+        self.volume = self
+            .volume
+            .checked_add(volume)
+            .ok_or(TrustPayError::CalculationError)?;
+        self.updated_at = now;
+        Ok(())
+    }
+
+    pub fn record_approval(&mut self, latency: i64, now: i64) -> Result<()> {
+        self.approvals = self
+            .approvals
+            .checked_add(1)
+            .ok_or(TrustPayError::CalculationError)?;
+        self.total_approval_latency = self
+            .total_approval_latency
+            .checked_add(latency.max(0))
+            .ok_or(TrustPayError::CalculationError)?;
+        self.average_approval_latency = self.total_approval_latency / self.approvals as i64;
+        self.updated_at = now;
+        Ok(())
+    }
+
+    pub fn record_dispute_opened(&mut self, now: i64) -> Result<()> {
+        self.disputes_opened = self
+            .disputes_opened
+            .checked_add(1)
+            .ok_or(TrustPayError::CalculationError)?;
+        self.updated_at = now;
+        Ok(())
+    }
+
+    pub fn record_dispute_lost(&mut self, now: i64) -> Result<()> {
+        self.disputes_lost = self
+            .disputes_lost
+            .checked_add(1)
+            .ok_or(TrustPayError::CalculationError)?;
+        self.updated_at = now;
+        Ok(())
+    }
+
+    pub fn record_cancellation(&mut self, now: i64) -> Result<()> {
+        self.cancellations = self
+            .cancellations
+            .checked_add(1)
+            .ok_or(TrustPayError::CalculationError)?;
+        self.updated_at = now;
+        Ok(())
+    }
+
+    pub fn record_decline(&mut self, now: i64) -> Result<()> {
+        self.declines = self
+            .declines
+            .checked_add(1)
+            .ok_or(TrustPayError::CalculationError)?;
+        self.updated_at = now;
+        Ok(())
+    }
+
+    pub fn record_review(&mut self, score: u8, now: i64) -> Result<()> {
+        self.reviews_received = self
+            .reviews_received
+            .checked_add(1)
+            .ok_or(TrustPayError::CalculationError)?;
+        self.total_review_score = self
+            .total_review_score
+            .checked_add(score as u64)
+            .ok_or(TrustPayError::CalculationError)?;
+        self.average_review_score = self
+            .total_review_score
+            .checked_mul(100)
+            .ok_or(TrustPayError::CalculationError)?
+            / self.reviews_received;
+        self.updated_at = now;
+        Ok(())
+    }
+}
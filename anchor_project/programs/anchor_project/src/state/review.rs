@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::error::TrustPayError;
+
+// Ratings the two parties of a contract give each other, opened once the contract completes
+#[account]
+#[derive(InitSpace)]
+pub struct ContractReview {
+    pub trust_pay: Pubkey,
+    pub payer: Pubkey,
+    pub recipient: Pubkey,
+    pub completed_at: Option<i64>, // Set on completion; reviews are rejected until then
+    pub payer_review: Option<Review>, // Left by the payer about the recipient
+    pub recipient_review: Option<Review>, // Left by the recipient about the payer
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct Review {
+    pub score: u8,
+    pub comment_hash: [u8; 32], // Hash of the off-chain comment
+    pub submitted_at: i64,
+}
+
+impl ContractReview {
+    // Store the reviewer's one-time rating and return the wallet being rated
+    pub fn submit(&mut self, reviewer: Pubkey, review: Review) -> Result<Pubkey> {
+        require!(
+            self.completed_at.is_some(),
+            TrustPayError::ContractNotCompleted
+        );
+        require!(
+            !self.window_ended(review.submitted_at),
+            TrustPayError::ReviewWindowClosed
+        );
+        require!(
+            (MIN_REVIEW_SCORE..=MAX_REVIEW_SCORE).contains(&review.score),
+            TrustPayError::InvalidReviewScore
+        );
+
+        let (slot, reviewee) = if reviewer == self.payer {
+            (&mut self.payer_review, self.recipient)
+        } else if reviewer == self.recipient {
+            (&mut self.recipient_review, self.payer)
+        } else {
+            return Err(TrustPayError::UnauthorizedReviewer.into());
+        };
+        require!(slot.is_none(), TrustPayError::AlreadyReviewed);
+        *slot = Some(review);
+        Ok(reviewee)
+    }
+
+    pub fn window_ended(&self, now: i64) -> bool {
+        self.completed_at
+            .is_some_and(|completed_at| now > completed_at.saturating_add(REVIEW_WINDOW))
+    }
+
+    // Nothing is left to record once both parties reviewed or the window passed
+    pub fn can_close(&self, now: i64) -> bool {
+        (self.payer_review.is_some() && self.recipient_review.is_some()) || self.window_ended(now)
+    }
+}
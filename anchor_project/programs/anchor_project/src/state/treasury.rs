@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct Treasury {
+    pub mint: Pubkey,
+    pub total_fees_collected: u64,
+    pub total_fees_withdrawn: u64,
+    pub withdrawal_count: u64,
+    pub last_collection_at: i64,
+    pub last_withdrawal_at: i64,
+    pub bump: u8,
+}
+
+impl Treasury {
+    pub fn available_fees(&self) -> u64 {
+        self.total_fees_collected
+            .saturating_sub(self.total_fees_withdrawn)
+    }
+}
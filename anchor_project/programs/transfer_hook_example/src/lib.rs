@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use spl_discriminator::SplDiscriminate;
+use spl_tlv_account_resolution::{
+    account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList,
+};
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+
+declare_id!("9Xx9Z1LGzRoQ6JDMbAUcpbucMejkFuHQPmGUWjwVT4HX");
+
+// Minimal transfer hook for the TrustPay tests: counts every transfer of a mint in a counter PDA,
+// which Token-2022 only passes along if the caller forwards the hook's extra accounts
+#[program]
+pub mod transfer_hook_example {
+    use super::*;
+
+    pub fn initialize_extra_account_meta_list(
+        ctx: Context<InitializeExtraAccountMetaList>,
+    ) -> Result<()> {
+        let mut data = ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?;
+        ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &extra_account_metas()?)?;
+        Ok(())
+    }
+
+    #[instruction(discriminator = ExecuteInstruction::SPL_DISCRIMINATOR_SLICE)]
+    pub fn transfer_hook(ctx: Context<TransferHook>, _amount: u64) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.transfers = counter.transfers.checked_add(1).unwrap();
+        Ok(())
+    }
+}
+
+// The counter PDA, derived from the mint (account index 1 of the execute instruction)
+fn extra_account_metas() -> Result<Vec<ExtraAccountMeta>> {
+    Ok(vec![ExtraAccountMeta::new_with_seeds(
+        &[
+            Seed::Literal {
+                bytes: b"counter".to_vec(),
+            },
+            Seed::AccountKey { index: 1 },
+        ],
+        false,
+        true,
+    )?])
+}
+
+#[derive(Accounts)]
+pub struct InitializeExtraAccountMetaList<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: validation account holding the extra account metas, written by the handler
+    #[account(
+        init,
+        payer = payer,
+        space = ExtraAccountMetaList::size_of(1)?,
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Counter::INIT_SPACE,
+        seeds = [b"counter", mint.key().as_ref()],
+        bump
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Account order is fixed by the transfer hook interface
+#[derive(Accounts)]
+pub struct TransferHook<'info> {
+    #[account(token::mint = mint)]
+    pub source_token: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(token::mint = mint)]
+    pub destination_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: owner or delegate of the source account
+    pub owner: UncheckedAccount<'info>,
+
+    /// CHECK: validation account, checked by its seeds
+    #[account(seeds = [b"extra-account-metas", mint.key().as_ref()], bump)]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    #[account(mut, seeds = [b"counter", mint.key().as_ref()], bump)]
+    pub counter: Account<'info, Counter>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Counter {
+    pub transfers: u64,
+}
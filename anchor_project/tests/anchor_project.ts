@@ -1,7 +1,9 @@
 import * as anchor from "@coral-xyz/anchor";
 import { BN, type Program } from "@coral-xyz/anchor";
 import { AnchorProject } from "../target/types/anchor_project";
+import { TransferHookExample } from "../target/types/transfer_hook_example";
 import {
+  NATIVE_MINT,
   TOKEN_2022_PROGRAM_ID,
   TOKEN_PROGRAM_ID,
   getAssociatedTokenAddressSync,
@@ -13,13 +15,21 @@ import {
   makeKeypairs,
 } from "@solana-developers/helpers";
 import {
+  ExtensionType,
+  createInitializeMintInstruction,
+  createInitializeTransferHookInstruction,
+  createInitializeTransferFeeConfigInstruction,
   createMint,
   createAssociatedTokenAccount,
+  createAssociatedTokenAccountIdempotent,
+  createSyncNativeInstruction,
+  getMintLen,
   mintTo,
 } from "@solana/spl-token";
-import { randomBytes } from "node:crypto";
+import { createHash, randomBytes } from "node:crypto";
 
 const TOKEN_PROGRAM = TOKEN_PROGRAM_ID;
+const BPF_LOADER_UPGRADEABLE_PROGRAM_ID = new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111");
 const SECONDS = 1000;
 
 // Role constants
@@ -42,6 +52,21 @@ const MILESTONE_STATUS_PENDING = 0;
 const MILESTONE_STATUS_COMPLETED_BY_SP = 1;
 const MILESTONE_STATUS_APPROVED_BY_PAYER = 2;
 const MILESTONE_STATUS_DISPUTED = 3;
+const MILESTONE_STATUS_RESOLVED = 4;
+
+// Dispute record status constants
+const DISPUTE_STATUS_OPEN = 0;
+const DISPUTE_STATUS_RESOLVED = 3;
+const DISPUTE_STATUS_SETTLED = 4;
+const DISPUTE_STATUS_TIMED_OUT = 5;
+const DISPUTE_STATUS_WITHDRAWN = 6;
+
+// Dispute scope and resolutions
+const CONTRACT_DISPUTE_INDEX = 255;
+const RESOLUTION_SPLIT = 0;
+const RESOLUTION_TERMINATE = 1;
+const RESOLUTION_CONTINUE = 2;
+const DISMISS_VOTE = 65_535;
 
 const getRandomBigNumber = (size = 8) => {
   return new BN(randomBytes(size));
@@ -63,20 +88,59 @@ describe("Trust Pay - Complete Test Suite", () => {
   let client: anchor.web3.Keypair;
   let worker: anchor.web3.Keypair;
   let resolver: anchor.web3.Keypair;
-  let feeDestinationKeypair: anchor.web3.Keypair;
 
-  before("Setup accounts and token mint", async () => {
-    //for this test client is the resolver since they initialize global state
-    // but in production the admin is the resolver
-    [client, worker, resolver, feeDestinationKeypair] = makeKeypairs(4);
+  // Dispute records are numbered by the global dispute counter
+  const disputePda = (disputeId: BN) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("dispute"), disputeId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+
+  const nextDispute = async () =>
+    disputePda((await program.account.globalState.fetch(accounts.globalState)).totalDisputes);
+
+  const disputeOf = async (trustPay: PublicKey, milestoneIndex = 0) => {
+    const contract = await program.account.trustPay.fetch(trustPay);
+    const state = milestoneIndex === CONTRACT_DISPUTE_INDEX
+      ? contract.contractDispute
+      : contract.milestones[milestoneIndex];
+    return disputePda(state.disputeId);
+  };
 
-    resolver = client;
+  // Contracts can only be created on mints the authority has enabled
+  const configureMint = (
+    mint: PublicKey,
+    {
+      tokenProgram = TOKEN_PROGRAM,
+      enabled = true,
+      minAmount = new BN(0),
+      maxAmount = new BN(0),
+      feeOverride = null as number | null,
+      signer = resolver,
+    } = {}
+  ) =>
+    program.methods
+      .configureMint(enabled, minAmount, maxAmount, feeOverride)
+      .accountsPartial({
+        authority: signer.publicKey,
+        globalState: accounts.globalState,
+        mint,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram,
+      })
+      .signers([signer])
+      .rpc();
+
+  before("Setup accounts and token mint", async () => {
+    // The provider wallet is the program upgrade authority, so it bootstraps
+    // the global state and acts as the resolver
+    [client, worker] = makeKeypairs(2);
+    resolver = user;
 
     // Airdrop to users
     const airdropPromises = [
       connection.requestAirdrop(client.publicKey, 2 * LAMPORTS_PER_SOL),
       connection.requestAirdrop(worker.publicKey, 2 * LAMPORTS_PER_SOL),
-      connection.requestAirdrop(resolver.publicKey, 2 * LAMPORTS_PER_SOL),
     ];
 
     const sigs = await Promise.all(airdropPromises);
@@ -132,7 +196,39 @@ describe("Trust Pay - Complete Test Suite", () => {
     accounts.tokenMint = tokenMint;
     accounts.clientTokenAccount = clientTokenAccount;
     accounts.workerTokenAccount = workerTokenAccount;
-    accounts.feeDestination = feeDestinationKeypair.publicKey;
+    [accounts.treasury] = PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury"), tokenMint.toBuffer()],
+      program.programId
+    );
+    accounts.treasuryTokenAccount = getAssociatedTokenAddressSync(
+      tokenMint,
+      accounts.treasury,
+      true,
+      TOKEN_PROGRAM
+    );
+
+    // Bootstrap the global state with the upgrade authority as admin
+    [accounts.globalState] = PublicKey.findProgramAddressSync(
+      [Buffer.from("global-state")],
+      program.programId
+    );
+    [accounts.programData] = PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      BPF_LOADER_UPGRADEABLE_PROGRAM_ID
+    );
+    await program.methods
+      .initializeGlobalState()
+      .accountsPartial({
+        authority: resolver.publicKey,
+        globalState: accounts.globalState,
+        program: program.programId,
+        programData: accounts.programData,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([resolver])
+      .rpc();
+
+    await configureMint(tokenMint);
   });
 
   describe("1. CREATE_CONTRACT Tests", () => {
@@ -190,7 +286,7 @@ describe("Trust Pay - Complete Test Suite", () => {
             termsAndConditions,
             amount,
             milestones,
-            deadlineDuration
+            deadlineDuration, null
           )
           .accountsPartial({
             creator: client.publicKey,
@@ -198,7 +294,7 @@ describe("Trust Pay - Complete Test Suite", () => {
             creatorTokenAccount: accounts.clientTokenAccount,
             trustPay: trustPayPubkey,
             vault: vaultPubkey,
-            feeDestination: accounts.feeDestination,
+            treasury: accounts.treasury,
             globalState: globalStatePubkey,
             systemProgram: anchor.web3.SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM,
@@ -267,7 +363,7 @@ describe("Trust Pay - Complete Test Suite", () => {
             termsAndConditions,
             amount,
             milestones,
-            deadlineDuration
+            deadlineDuration, null
           )
           .accountsPartial({
             creator: client.publicKey,
@@ -275,7 +371,7 @@ describe("Trust Pay - Complete Test Suite", () => {
             creatorTokenAccount: accounts.clientTokenAccount,
             trustPay: trustPayPubkey,
             vault: vaultPubkey,
-            feeDestination: accounts.feeDestination,
+            treasury: accounts.treasury,
             globalState: globalStatePubkey,
             systemProgram: anchor.web3.SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM,
@@ -335,7 +431,7 @@ describe("Trust Pay - Complete Test Suite", () => {
             termsAndConditions,
             amount,
             milestones,
-            deadlineDuration
+            deadlineDuration, null
           )
           .accountsPartial({
             creator: worker.publicKey,
@@ -343,7 +439,7 @@ describe("Trust Pay - Complete Test Suite", () => {
             creatorTokenAccount: accounts.workerTokenAccount,
             trustPay: trustPayPubkey,
             vault: vaultPubkey,
-            feeDestination: accounts.feeDestination,
+            treasury: accounts.treasury,
             globalState: globalStatePubkey,
             systemProgram: anchor.web3.SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM,
@@ -377,12 +473,12 @@ describe("Trust Pay - Complete Test Suite", () => {
             .createContract(
               trustPaySeed, ROLE_PAYER, client.publicKey, accounts.worker,
               CONTRACT_TYPE_MILESTONE, "Test", "Test terms", new BN(0),
-              [{ description: "M1", amount: new BN(0) }], new BN(7 * 24 * 60 * 60)
+              [{ description: "M1", amount: new BN(0) }], new BN(7 * 24 * 60 * 60), null
             )
             .accountsPartial({
               creator: client.publicKey, mint: accounts.tokenMint,
               creatorTokenAccount: accounts.clientTokenAccount, trustPay: trustPayPubkey,
-              vault: vaultPubkey, feeDestination: accounts.feeDestination,
+              vault: vaultPubkey, treasury: accounts.treasury,
               globalState: globalStatePubkey, systemProgram: anchor.web3.SystemProgram.programId,
               tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
             })
@@ -408,12 +504,12 @@ describe("Trust Pay - Complete Test Suite", () => {
             .createContract(
               trustPaySeed, 99, client.publicKey, accounts.worker,
               CONTRACT_TYPE_ONE_TIME, "Test", "Test terms", new BN(1_000_000),
-              [], new BN(7 * 24 * 60 * 60)
+              [], new BN(7 * 24 * 60 * 60), null
             )
             .accountsPartial({
               creator: client.publicKey, mint: accounts.tokenMint,
               creatorTokenAccount: accounts.clientTokenAccount, trustPay: trustPayPubkey,
-              vault: vaultPubkey, feeDestination: accounts.feeDestination,
+              vault: vaultPubkey, treasury: accounts.treasury,
               globalState: globalStatePubkey, systemProgram: anchor.web3.SystemProgram.programId,
               tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
             })
@@ -440,12 +536,12 @@ describe("Trust Pay - Complete Test Suite", () => {
               trustPaySeed, ROLE_PAYER, client.publicKey, accounts.worker,
               CONTRACT_TYPE_MILESTONE, "Test", "Test terms", new BN(5_000_000),
               [{ description: "M1", amount: new BN(2_000_000) }],
-              new BN(7 * 24 * 60 * 60)
+              new BN(7 * 24 * 60 * 60), null
             )
             .accountsPartial({
               creator: client.publicKey, mint: accounts.tokenMint,
               creatorTokenAccount: accounts.clientTokenAccount, trustPay: trustPayPubkey,
-              vault: vaultPubkey, feeDestination: accounts.feeDestination,
+              vault: vaultPubkey, treasury: accounts.treasury,
               globalState: globalStatePubkey, systemProgram: anchor.web3.SystemProgram.programId,
               tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
             })
@@ -471,12 +567,12 @@ describe("Trust Pay - Complete Test Suite", () => {
             .createContract(
               trustPaySeed, ROLE_PAYER, client.publicKey, accounts.worker,
               CONTRACT_TYPE_ONE_TIME, "A".repeat(51), "Test terms",
-              new BN(1_000_000), [], new BN(7 * 24 * 60 * 60)
+              new BN(1_000_000), [], new BN(7 * 24 * 60 * 60), null
             )
             .accountsPartial({
               creator: client.publicKey, mint: accounts.tokenMint,
               creatorTokenAccount: accounts.clientTokenAccount, trustPay: trustPayPubkey,
-              vault: vaultPubkey, feeDestination: accounts.feeDestination,
+              vault: vaultPubkey, treasury: accounts.treasury,
               globalState: globalStatePubkey, systemProgram: anchor.web3.SystemProgram.programId,
               tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
             })
@@ -507,12 +603,12 @@ describe("Trust Pay - Complete Test Suite", () => {
             .createContract(
               trustPaySeed, ROLE_PAYER, client.publicKey, accounts.worker,
               CONTRACT_TYPE_MILESTONE, "Test", "Test terms",
-              new BN(11_000_000), milestones, new BN(7 * 24 * 60 * 60)
+              new BN(11_000_000), milestones, new BN(7 * 24 * 60 * 60), null
             )
             .accountsPartial({
               creator: client.publicKey, mint: accounts.tokenMint,
               creatorTokenAccount: accounts.clientTokenAccount, trustPay: trustPayPubkey,
-              vault: vaultPubkey, feeDestination: accounts.feeDestination,
+              vault: vaultPubkey, treasury: accounts.treasury,
               globalState: globalStatePubkey, systemProgram: anchor.web3.SystemProgram.programId,
               tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
             })
@@ -551,12 +647,12 @@ describe("Trust Pay - Complete Test Suite", () => {
             { description: "M1", amount: new BN(1_500_000) },
             { description: "M2", amount: new BN(1_500_000) }
           ],
-          new BN(7 * 24 * 60 * 60)
+          new BN(7 * 24 * 60 * 60), null
         )
         .accountsPartial({
           creator: worker.publicKey, mint: accounts.tokenMint,
           creatorTokenAccount: accounts.workerTokenAccount, trustPay: trustPayPubkey,
-          vault: vaultPubkey, feeDestination: accounts.feeDestination,
+          vault: vaultPubkey, treasury: accounts.treasury,
           globalState: globalStatePubkey, systemProgram: anchor.web3.SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
         })
@@ -627,12 +723,12 @@ describe("Trust Pay - Complete Test Suite", () => {
           .createContract(
             newSeed, ROLE_RECIPIENT, client.publicKey, accounts.client,
             CONTRACT_TYPE_ONE_TIME, "Test", "Test terms", new BN(1_000_000),
-            [], new BN(7 * 24 * 60 * 60)
+            [], new BN(7 * 24 * 60 * 60), null
           )
           .accountsPartial({
             creator: worker.publicKey, mint: accounts.tokenMint,
             creatorTokenAccount: accounts.workerTokenAccount, trustPay: newTrustPayPubkey,
-            vault: newVaultPubkey, feeDestination: accounts.feeDestination,
+            vault: newVaultPubkey, treasury: accounts.treasury,
             globalState: globalStatePubkey, systemProgram: anchor.web3.SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
           })
@@ -677,12 +773,12 @@ describe("Trust Pay - Complete Test Suite", () => {
         .createContract(
           trustPaySeed, ROLE_RECIPIENT, client.publicKey, accounts.client,
           CONTRACT_TYPE_ONE_TIME, "Decline Test", "Test terms",
-          new BN(1_000_000), [], new BN(7 * 24 * 60 * 60)
+          new BN(1_000_000), [], new BN(7 * 24 * 60 * 60), null
         )
         .accountsPartial({
           creator: worker.publicKey, mint: accounts.tokenMint,
           creatorTokenAccount: accounts.workerTokenAccount, trustPay: trustPayPubkey,
-          vault: vaultPubkey, feeDestination: accounts.feeDestination,
+          vault: vaultPubkey, treasury: accounts.treasury,
           globalState: globalStatePubkey, systemProgram: anchor.web3.SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
         })
@@ -733,12 +829,12 @@ describe("Trust Pay - Complete Test Suite", () => {
           .createContract(
             trustPaySeed, ROLE_PAYER, client.publicKey, accounts.worker,
             CONTRACT_TYPE_ONE_TIME, "Test", "Test terms",
-            new BN(1_000_000), [], new BN(7 * 24 * 60 * 60)
+            new BN(1_000_000), [], new BN(7 * 24 * 60 * 60), null
           )
           .accountsPartial({
             creator: client.publicKey, mint: accounts.tokenMint,
             creatorTokenAccount: accounts.clientTokenAccount, trustPay: newTrustPayPubkey,
-            vault: newVaultPubkey, feeDestination: accounts.feeDestination,
+            vault: newVaultPubkey, treasury: accounts.treasury,
             globalState: globalStatePubkey, systemProgram: anchor.web3.SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
           })
@@ -781,12 +877,12 @@ describe("Trust Pay - Complete Test Suite", () => {
           .createContract(
             trustPaySeed, ROLE_RECIPIENT, client.publicKey, accounts.client,
             CONTRACT_TYPE_ONE_TIME, "Cancel Test", "Test terms",
-            new BN(1_000_000), [], new BN(7 * 24 * 60 * 60)
+            new BN(1_000_000), [], new BN(7 * 24 * 60 * 60), null
           )
           .accountsPartial({
             creator: worker.publicKey, mint: accounts.tokenMint,
             creatorTokenAccount: accounts.workerTokenAccount, trustPay: trustPayPubkey,
-            vault: vaultPubkey, feeDestination: accounts.feeDestination,
+            vault: vaultPubkey, treasury: accounts.treasury,
             globalState: globalStatePubkey, systemProgram: anchor.web3.SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
           })
@@ -829,12 +925,12 @@ describe("Trust Pay - Complete Test Suite", () => {
           .createContract(
             trustPaySeed, ROLE_RECIPIENT, client.publicKey, accounts.client,
             CONTRACT_TYPE_ONE_TIME, "Cancel Test", "Test terms",
-            new BN(1_000_000), [], new BN(7 * 24 * 60 * 60)
+            new BN(1_000_000), [], new BN(7 * 24 * 60 * 60), null
           )
           .accountsPartial({
             creator: worker.publicKey, mint: accounts.tokenMint,
             creatorTokenAccount: accounts.workerTokenAccount, trustPay: trustPayPubkey,
-            vault: vaultPubkey, feeDestination: accounts.feeDestination,
+            vault: vaultPubkey, treasury: accounts.treasury,
             globalState: globalStatePubkey, systemProgram: anchor.web3.SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
           })
@@ -876,12 +972,12 @@ describe("Trust Pay - Complete Test Suite", () => {
           .createContract(
             trustPaySeed, ROLE_RECIPIENT, client.publicKey, accounts.client,
             CONTRACT_TYPE_ONE_TIME, "Test", "Test terms",
-            new BN(1_000_000), [], new BN(7 * 24 * 60 * 60)
+            new BN(1_000_000), [], new BN(7 * 24 * 60 * 60), null
           )
           .accountsPartial({
             creator: worker.publicKey, mint: accounts.tokenMint,
             creatorTokenAccount: accounts.workerTokenAccount, trustPay: trustPayPubkey,
-            vault: vaultPubkey, feeDestination: accounts.feeDestination,
+            vault: vaultPubkey, treasury: accounts.treasury,
             globalState: globalStatePubkey, systemProgram: anchor.web3.SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
           })
@@ -932,12 +1028,12 @@ describe("Trust Pay - Complete Test Suite", () => {
             { description: "M1", amount: new BN(1_000_000) },
             { description: "M2", amount: new BN(2_000_000) }
           ],
-          new BN(7 * 24 * 60 * 60)
+          new BN(7 * 24 * 60 * 60), null
         )
         .accountsPartial({
           creator: client.publicKey, mint: accounts.tokenMint,
           creatorTokenAccount: accounts.clientTokenAccount, trustPay: trustPayPubkey,
-          vault: vaultPubkey, feeDestination: accounts.feeDestination,
+          vault: vaultPubkey, treasury: accounts.treasury,
           globalState: globalStatePubkey, systemProgram: anchor.web3.SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
         })
@@ -1040,12 +1136,12 @@ describe("Trust Pay - Complete Test Suite", () => {
             { description: "M1", amount: new BN(1_000_000) },
             { description: "M2", amount: new BN(1_000_000) }
           ],
-          new BN(7 * 24 * 60 * 60)
+          new BN(7 * 24 * 60 * 60), null
         )
         .accountsPartial({
           creator: client.publicKey, mint: accounts.tokenMint,
           creatorTokenAccount: accounts.clientTokenAccount, trustPay: trustPayPubkey,
-          vault: vaultPubkey, feeDestination: accounts.feeDestination,
+          vault: vaultPubkey, treasury: accounts.treasury,
           globalState: globalStatePubkey, systemProgram: anchor.web3.SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
         })
@@ -1076,10 +1172,8 @@ describe("Trust Pay - Complete Test Suite", () => {
             mint: accounts.tokenMint,
             vault: vaultPubkey,
             recipientTokenAccount: accounts.workerTokenAccount,
-            feeDestination: accounts.feeDestination,
-            feeDestinationTokenAccount: getAssociatedTokenAddressSync(
-              accounts.tokenMint, accounts.feeDestination, true, TOKEN_PROGRAM
-            ),
+            treasury: accounts.treasury,
+            treasuryTokenAccount: accounts.treasuryTokenAccount,
             globalState: globalStatePubkey,
             systemProgram: anchor.web3.SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM,
@@ -1118,10 +1212,8 @@ describe("Trust Pay - Complete Test Suite", () => {
             mint: accounts.tokenMint,
             vault: vaultPubkey,
             recipientTokenAccount: accounts.workerTokenAccount,
-            feeDestination: accounts.feeDestination,
-            feeDestinationTokenAccount: getAssociatedTokenAddressSync(
-              accounts.tokenMint, accounts.feeDestination, true, TOKEN_PROGRAM
-            ),
+            treasury: accounts.treasury,
+            treasuryTokenAccount: accounts.treasuryTokenAccount,
             globalState: globalStatePubkey,
             systemProgram: anchor.web3.SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM,
@@ -1155,12 +1247,12 @@ describe("Trust Pay - Complete Test Suite", () => {
           .createContract(
             seed, ROLE_PAYER, client.publicKey, accounts.worker,
             CONTRACT_TYPE_ONE_TIME, "Error Test", "Test terms",
-            new BN(1_000_000), [], new BN(7 * 24 * 60 * 60)
+            new BN(1_000_000), [], new BN(7 * 24 * 60 * 60), null
           )
           .accountsPartial({
             creator: client.publicKey, mint: accounts.tokenMint,
             creatorTokenAccount: accounts.clientTokenAccount, trustPay: errorTestTrustPay,
-            vault: errorTestVault, feeDestination: accounts.feeDestination,
+            vault: errorTestVault, treasury: accounts.treasury,
             globalState: globalStatePubkey, systemProgram: anchor.web3.SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
           })
@@ -1176,10 +1268,8 @@ describe("Trust Pay - Complete Test Suite", () => {
               payer: client.publicKey, recipient: worker.publicKey,
               trustPay: errorTestTrustPay, mint: accounts.tokenMint,
               vault: errorTestVault, recipientTokenAccount: accounts.workerTokenAccount,
-              feeDestination: accounts.feeDestination,
-              feeDestinationTokenAccount: getAssociatedTokenAddressSync(
-                accounts.tokenMint, accounts.feeDestination, true, TOKEN_PROGRAM
-              ),
+              treasury: accounts.treasury,
+              treasuryTokenAccount: accounts.treasuryTokenAccount,
               globalState: globalStatePubkey,
               systemProgram: anchor.web3.SystemProgram.programId,
               tokenProgram: TOKEN_PROGRAM,
@@ -1201,10 +1291,8 @@ describe("Trust Pay - Complete Test Suite", () => {
               payer: client.publicKey, recipient: worker.publicKey,
               trustPay: errorTestTrustPay, mint: accounts.tokenMint,
               vault: errorTestVault, recipientTokenAccount: accounts.workerTokenAccount,
-              feeDestination: accounts.feeDestination,
-              feeDestinationTokenAccount: getAssociatedTokenAddressSync(
-                accounts.tokenMint, accounts.feeDestination, true, TOKEN_PROGRAM
-              ),
+              treasury: accounts.treasury,
+              treasuryTokenAccount: accounts.treasuryTokenAccount,
               globalState: globalStatePubkey,
               systemProgram: anchor.web3.SystemProgram.programId,
               tokenProgram: TOKEN_PROGRAM,
@@ -1237,12 +1325,12 @@ describe("Trust Pay - Complete Test Suite", () => {
         .createContract(
           seed, ROLE_PAYER, client.publicKey, accounts.worker,
           CONTRACT_TYPE_ONE_TIME, "Dispute Test", "Test terms",
-          new BN(1_000_000), [], new BN(7 * 24 * 60 * 60)
+          new BN(1_000_000), [], new BN(7 * 24 * 60 * 60), null
         )
         .accountsPartial({
           creator: client.publicKey, mint: accounts.tokenMint,
           creatorTokenAccount: accounts.clientTokenAccount, trustPay: trustPayPubkey,
-          vault, feeDestination: accounts.feeDestination,
+          vault, treasury: accounts.treasury,
           globalState: globalStatePubkey, systemProgram: anchor.web3.SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
         })
@@ -1262,6 +1350,9 @@ describe("Trust Pay - Complete Test Suite", () => {
 
     describe("Happy Path", () => {
       it("Payer disputes milestone", async () => {
+        const { totalDisputes } = await program.account.globalState.fetch(globalStatePubkey);
+        const dispute = disputePda(totalDisputes);
+
         await program.methods
           .disputeContract(0, "Work does not meet requirements as specified in the contract")
           .accountsPartial({
@@ -1269,15 +1360,25 @@ describe("Trust Pay - Complete Test Suite", () => {
             trustPay: trustPayPubkey,
             globalState: globalStatePubkey,
             systemProgram: anchor.web3.SystemProgram.programId,
+            dispute,
           })
           .signers([client])
           .rpc();
 
+        // Only the milestone is frozen; the contract stays in progress
         const trustPayAccount = await program.account.trustPay.fetch(trustPayPubkey);
-        assert.equal(trustPayAccount.contractStatus, CONTRACT_STATUS_DISPUTED);
+        assert.equal(trustPayAccount.contractStatus, CONTRACT_STATUS_IN_PROGRESS);
         assert.equal(trustPayAccount.milestones[0].status, MILESTONE_STATUS_DISPUTED);
-        assert.isNotNull(trustPayAccount.milestones[0].disputeReason);
-        assert.isNotNull(trustPayAccount.milestones[0].disputeId);
+        assert.equal(trustPayAccount.milestones[0].disputeId.toString(), totalDisputes.toString());
+
+        // The dispute record is numbered by the global counter
+        const record = await program.account.dispute.fetch(dispute);
+        assert.equal(record.disputeId.toString(), totalDisputes.toString());
+        assert.equal(record.trustPay.toString(), trustPayPubkey.toString());
+        assert.equal(record.disputer.toString(), client.publicKey.toString());
+        assert.equal(record.reason, "Work does not meet requirements as specified in the contract");
+        assert.equal(record.status, DISPUTE_STATUS_OPEN);
+        assert.isNull(record.outcome);
       });
     });
 
@@ -1296,12 +1397,12 @@ describe("Trust Pay - Complete Test Suite", () => {
           .createContract(
             seed, ROLE_PAYER, client.publicKey, accounts.worker,
             CONTRACT_TYPE_ONE_TIME, "Test", "Test terms",
-            new BN(1_000_000), [], new BN(7 * 24 * 60 * 60)
+            new BN(1_000_000), [], new BN(7 * 24 * 60 * 60), null
           )
           .accountsPartial({
             creator: client.publicKey, mint: accounts.tokenMint,
             creatorTokenAccount: accounts.clientTokenAccount, trustPay: errorTrustPay,
-            vault, feeDestination: accounts.feeDestination,
+            vault, treasury: accounts.treasury,
             globalState: globalStatePubkey, systemProgram: anchor.web3.SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
           })
@@ -1328,6 +1429,7 @@ describe("Trust Pay - Complete Test Suite", () => {
               trustPay: errorTrustPay,
               globalState: globalStatePubkey,
               systemProgram: anchor.web3.SystemProgram.programId,
+              dispute: await nextDispute(),
             })
             .signers([client])
             .rpc();
@@ -1350,6 +1452,7 @@ describe("Trust Pay - Complete Test Suite", () => {
               trustPay: errorTrustPay,
               globalState: globalStatePubkey,
               systemProgram: anchor.web3.SystemProgram.programId,
+              dispute: await nextDispute(),
             })
             .signers([unauthorizedKeypair])
             .rpc();
@@ -1380,12 +1483,12 @@ describe("Trust Pay - Complete Test Suite", () => {
         .createContract(
           seed, ROLE_PAYER, client.publicKey, accounts.worker,
           CONTRACT_TYPE_ONE_TIME, "Resolve Test", "Test terms",
-          new BN(2_000_000), [], new BN(7 * 24 * 60 * 60)
+          new BN(2_000_000), [], new BN(7 * 24 * 60 * 60), null
         )
         .accountsPartial({
           creator: client.publicKey, mint: accounts.tokenMint,
           creatorTokenAccount: accounts.clientTokenAccount, trustPay: trustPayPubkey,
-          vault: vaultPubkey, feeDestination: accounts.feeDestination,
+          vault: vaultPubkey, treasury: accounts.treasury,
           globalState: globalStatePubkey, systemProgram: anchor.web3.SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
         })
@@ -1411,6 +1514,7 @@ describe("Trust Pay - Complete Test Suite", () => {
           trustPay: trustPayPubkey,
           globalState: globalStatePubkey,
           systemProgram: anchor.web3.SystemProgram.programId,
+          dispute: await nextDispute(),
         })
         .signers([client])
         .rpc();
@@ -1421,7 +1525,7 @@ describe("Trust Pay - Complete Test Suite", () => {
         const workerBalanceBefore = await connection.getTokenAccountBalance(accounts.workerTokenAccount);
 
         await program.methods
-          .resolveDispute(0, 1, "Evidence shows work was completed as specified")
+          .resolveDispute(0, 10_000, RESOLUTION_SPLIT, "Evidence shows work was completed as specified")
           .accountsPartial({
             resolver: resolver.publicKey,
             payer: client.publicKey,
@@ -1431,14 +1535,14 @@ describe("Trust Pay - Complete Test Suite", () => {
             vault: vaultPubkey,
             payerTokenAccount: accounts.clientTokenAccount,
             recipientTokenAccount: accounts.workerTokenAccount,
-            feeDestination: accounts.feeDestination,
-            feeDestinationTokenAccount: getAssociatedTokenAddressSync(
-              accounts.tokenMint, accounts.feeDestination, true, TOKEN_PROGRAM
-            ),
+            treasury: accounts.treasury,
+            treasuryTokenAccount: accounts.treasuryTokenAccount,
             globalState: globalStatePubkey,
             systemProgram: anchor.web3.SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM,
             associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+            arbitrator: null,
+            dispute: await disputeOf(trustPayPubkey),
           })
           .signers([resolver])
           .rpc();
@@ -1461,7 +1565,7 @@ describe("Trust Pay - Complete Test Suite", () => {
         const payerBalanceBefore = await connection.getTokenAccountBalance(accounts.clientTokenAccount);
 
         await program.methods
-          .resolveDispute(0, 0, "Work does not meet minimum quality standards")
+          .resolveDispute(0, 0, RESOLUTION_SPLIT, "Work does not meet minimum quality standards")
           .accountsPartial({
             resolver: resolver.publicKey,
             payer: client.publicKey,
@@ -1471,14 +1575,14 @@ describe("Trust Pay - Complete Test Suite", () => {
             vault: vaultPubkey,
             payerTokenAccount: accounts.clientTokenAccount,
             recipientTokenAccount: accounts.workerTokenAccount,
-            feeDestination: accounts.feeDestination,
-            feeDestinationTokenAccount: getAssociatedTokenAddressSync(
-              accounts.tokenMint, accounts.feeDestination, true, TOKEN_PROGRAM
-            ),
+            treasury: accounts.treasury,
+            treasuryTokenAccount: accounts.treasuryTokenAccount,
             globalState: globalStatePubkey,
             systemProgram: anchor.web3.SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM,
             associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+            arbitrator: null,
+            dispute: await disputeOf(trustPayPubkey),
           })
           .signers([resolver])
           .rpc();
@@ -1494,7 +1598,7 @@ describe("Trust Pay - Complete Test Suite", () => {
         const workerBalanceBefore = await connection.getTokenAccountBalance(accounts.workerTokenAccount);
 
         await program.methods
-          .resolveDispute(0, 2, "Both parties share responsibility")
+          .resolveDispute(0, 5_000, RESOLUTION_SPLIT, "Both parties share responsibility")
           .accountsPartial({
             resolver: resolver.publicKey,
             payer: client.publicKey,
@@ -1504,14 +1608,14 @@ describe("Trust Pay - Complete Test Suite", () => {
             vault: vaultPubkey,
             payerTokenAccount: accounts.clientTokenAccount,
             recipientTokenAccount: accounts.workerTokenAccount,
-            feeDestination: accounts.feeDestination,
-            feeDestinationTokenAccount: getAssociatedTokenAddressSync(
-              accounts.tokenMint, accounts.feeDestination, true, TOKEN_PROGRAM
-            ),
+            treasury: accounts.treasury,
+            treasuryTokenAccount: accounts.treasuryTokenAccount,
             globalState: globalStatePubkey,
             systemProgram: anchor.web3.SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM,
             associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+            arbitrator: null,
+            dispute: await disputeOf(trustPayPubkey),
           })
           .signers([resolver])
           .rpc();
@@ -1531,22 +1635,12 @@ describe("Trust Pay - Complete Test Suite", () => {
 
     describe("Error Cases", () => {
       it("Fails when non-resolver tries to resolve", async () => {
-
-        //TODO: Remove the unauthorizedKeypair
-        // To properly test we need to use the client but inorder for this test to pass
-        // We will create a new keypair to try and resolve the dispute
-        // In production the client should not be able to resolve dispute, only admin
-        
-        const [unauthorizedKeypair] = makeKeypairs(1);
-        await connection.requestAirdrop(unauthorizedKeypair.publicKey, LAMPORTS_PER_SOL);
-        await new Promise(resolve => setTimeout(resolve, 1000));
-
+        // The client is a party to the contract and must not be able to resolve it
         try {
           await program.methods
-            .resolveDispute(0, 1, "I shouldn't be able to do this")
+            .resolveDispute(0, 10_000, RESOLUTION_SPLIT, "I shouldn't be able to do this")
             .accountsPartial({
-              //resolver: client.publicKey,
-              resolver: unauthorizedKeypair.publicKey, //remove in production test
+              resolver: client.publicKey,
               payer: client.publicKey,
               recipient: worker.publicKey,
               mint: accounts.tokenMint,
@@ -1554,16 +1648,16 @@ describe("Trust Pay - Complete Test Suite", () => {
               vault: vaultPubkey,
               payerTokenAccount: accounts.clientTokenAccount,
               recipientTokenAccount: accounts.workerTokenAccount,
-              feeDestination: accounts.feeDestination,
-              feeDestinationTokenAccount: getAssociatedTokenAddressSync(
-                accounts.tokenMint, accounts.feeDestination, true, TOKEN_PROGRAM
-              ),
+              treasury: accounts.treasury,
+              treasuryTokenAccount: accounts.treasuryTokenAccount,
               globalState: globalStatePubkey,
               systemProgram: anchor.web3.SystemProgram.programId,
               tokenProgram: TOKEN_PROGRAM,
               associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+              arbitrator: null,
+              dispute: await disputeOf(trustPayPubkey),
             })
-            .signers([unauthorizedKeypair]) //replace with client in production test
+            .signers([client])
             .rpc();
           assert.fail("Should have failed");
         } catch (error) {
@@ -1571,10 +1665,10 @@ describe("Trust Pay - Complete Test Suite", () => {
         }
       });
 
-      it("Fails with invalid resolution value", async () => {
+      it("Fails with a recipient share above 10000 bps", async () => {
         try {
           await program.methods
-            .resolveDispute(0, 99, "Invalid resolution")
+            .resolveDispute(0, 10_001, RESOLUTION_SPLIT, "Invalid resolution")
             .accountsPartial({
               resolver: resolver.publicKey,
               payer: client.publicKey,
@@ -1584,14 +1678,14 @@ describe("Trust Pay - Complete Test Suite", () => {
               vault: vaultPubkey,
               payerTokenAccount: accounts.clientTokenAccount,
               recipientTokenAccount: accounts.workerTokenAccount,
-              feeDestination: accounts.feeDestination,
-              feeDestinationTokenAccount: getAssociatedTokenAddressSync(
-                accounts.tokenMint, accounts.feeDestination, true, TOKEN_PROGRAM
-              ),
+              treasury: accounts.treasury,
+              treasuryTokenAccount: accounts.treasuryTokenAccount,
               globalState: globalStatePubkey,
               systemProgram: anchor.web3.SystemProgram.programId,
               tokenProgram: TOKEN_PROGRAM,
               associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+              arbitrator: null,
+              dispute: await disputeOf(trustPayPubkey),
             })
             .signers([resolver])
             .rpc();
@@ -1614,12 +1708,12 @@ describe("Trust Pay - Complete Test Suite", () => {
           .createContract(
             newSeed, ROLE_PAYER, client.publicKey, accounts.worker,
             CONTRACT_TYPE_ONE_TIME, "Test", "Test terms",
-            new BN(1_000_000), [], new BN(7 * 24 * 60 * 60)
+            new BN(1_000_000), [], new BN(7 * 24 * 60 * 60), null
           )
           .accountsPartial({
             creator: client.publicKey, mint: accounts.tokenMint,
             creatorTokenAccount: accounts.clientTokenAccount, trustPay: newTrustPayPubkey,
-            vault: newVaultPubkey, feeDestination: accounts.feeDestination,
+            vault: newVaultPubkey, treasury: accounts.treasury,
             globalState: globalStatePubkey, systemProgram: anchor.web3.SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
           })
@@ -1628,7 +1722,7 @@ describe("Trust Pay - Complete Test Suite", () => {
 
         try {
           await program.methods
-            .resolveDispute(0, 1, "Can't resolve non-disputed contract")
+            .resolveDispute(0, 10_000, RESOLUTION_SPLIT, "Can't resolve non-disputed contract")
             .accountsPartial({
               resolver: resolver.publicKey,
               payer: client.publicKey,
@@ -1638,14 +1732,14 @@ describe("Trust Pay - Complete Test Suite", () => {
               vault: newVaultPubkey,
               payerTokenAccount: accounts.clientTokenAccount,
               recipientTokenAccount: accounts.workerTokenAccount,
-              feeDestination: accounts.feeDestination,
-              feeDestinationTokenAccount: getAssociatedTokenAddressSync(
-                accounts.tokenMint, accounts.feeDestination, true, TOKEN_PROGRAM
-              ),
+              treasury: accounts.treasury,
+              treasuryTokenAccount: accounts.treasuryTokenAccount,
               globalState: globalStatePubkey,
               systemProgram: anchor.web3.SystemProgram.programId,
               tokenProgram: TOKEN_PROGRAM,
               associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+              arbitrator: null,
+              dispute: await disputeOf(newTrustPayPubkey),
             })
             .signers([resolver])
             .rpc();
@@ -1673,12 +1767,12 @@ describe("Trust Pay - Complete Test Suite", () => {
           .createContract(
             seed, ROLE_PAYER, client.publicKey, accounts.worker,
             CONTRACT_TYPE_ONE_TIME, "Integration Test", "Complete workflow",
-            new BN(5_000_000), [], new BN(7 * 24 * 60 * 60)
+            new BN(5_000_000), [], new BN(7 * 24 * 60 * 60), null
           )
           .accountsPartial({
             creator: client.publicKey, mint: accounts.tokenMint,
             creatorTokenAccount: accounts.clientTokenAccount, trustPay: trustPayPubkey,
-            vault: vaultPubkey, feeDestination: accounts.feeDestination,
+            vault: vaultPubkey, treasury: accounts.treasury,
             globalState: globalStatePubkey, systemProgram: anchor.web3.SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
           })
@@ -1706,10 +1800,8 @@ describe("Trust Pay - Complete Test Suite", () => {
             mint: accounts.tokenMint,
             vault: vaultPubkey,
             recipientTokenAccount: accounts.workerTokenAccount,
-            feeDestination: accounts.feeDestination,
-            feeDestinationTokenAccount: getAssociatedTokenAddressSync(
-              accounts.tokenMint, accounts.feeDestination, true, TOKEN_PROGRAM
-            ),
+            treasury: accounts.treasury,
+            treasuryTokenAccount: accounts.treasuryTokenAccount,
             globalState: globalStatePubkey,
             systemProgram: anchor.web3.SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM,
@@ -1748,12 +1840,12 @@ describe("Trust Pay - Complete Test Suite", () => {
           .createContract(
             seed, ROLE_PAYER, client.publicKey, accounts.worker,
             CONTRACT_TYPE_MILESTONE, "Multi-Phase Project", "Complex workflow",
-            new BN(5_000_000), milestones, new BN(14 * 24 * 60 * 60)
+            new BN(5_000_000), milestones, new BN(14 * 24 * 60 * 60), null
           )
           .accountsPartial({
             creator: client.publicKey, mint: accounts.tokenMint,
             creatorTokenAccount: accounts.clientTokenAccount, trustPay: trustPayPubkey,
-            vault: vaultPubkey, feeDestination: accounts.feeDestination,
+            vault: vaultPubkey, treasury: accounts.treasury,
             globalState: globalStatePubkey, systemProgram: anchor.web3.SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
           })
@@ -1777,10 +1869,8 @@ describe("Trust Pay - Complete Test Suite", () => {
             payer: client.publicKey, recipient: worker.publicKey,
             trustPay: trustPayPubkey, mint: accounts.tokenMint,
             vault: vaultPubkey, recipientTokenAccount: accounts.workerTokenAccount,
-            feeDestination: accounts.feeDestination,
-            feeDestinationTokenAccount: getAssociatedTokenAddressSync(
-              accounts.tokenMint, accounts.feeDestination, true, TOKEN_PROGRAM
-            ),
+            treasury: accounts.treasury,
+            treasuryTokenAccount: accounts.treasuryTokenAccount,
             globalState: globalStatePubkey,
             systemProgram: anchor.web3.SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM,
@@ -1810,10 +1900,8 @@ describe("Trust Pay - Complete Test Suite", () => {
             payer: client.publicKey, recipient: worker.publicKey,
             trustPay: trustPayPubkey, mint: accounts.tokenMint,
             vault: vaultPubkey, recipientTokenAccount: accounts.workerTokenAccount,
-            feeDestination: accounts.feeDestination,
-            feeDestinationTokenAccount: getAssociatedTokenAddressSync(
-              accounts.tokenMint, accounts.feeDestination, true, TOKEN_PROGRAM
-            ),
+            treasury: accounts.treasury,
+            treasuryTokenAccount: accounts.treasuryTokenAccount,
             globalState: globalStatePubkey,
             systemProgram: anchor.web3.SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM,
@@ -1847,12 +1935,12 @@ describe("Trust Pay - Complete Test Suite", () => {
           .createContract(
             seed, ROLE_PAYER, client.publicKey, accounts.worker,
             CONTRACT_TYPE_ONE_TIME, "Dispute Flow Test", "Testing disputes",
-            new BN(3_000_000), [], new BN(7 * 24 * 60 * 60)
+            new BN(3_000_000), [], new BN(7 * 24 * 60 * 60), null
           )
           .accountsPartial({
             creator: client.publicKey, mint: accounts.tokenMint,
             creatorTokenAccount: accounts.clientTokenAccount, trustPay: trustPayPubkey,
-            vault: vaultPubkey, feeDestination: accounts.feeDestination,
+            vault: vaultPubkey, treasury: accounts.treasury,
             globalState: globalStatePubkey, systemProgram: anchor.web3.SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
           })
@@ -1878,16 +1966,17 @@ describe("Trust Pay - Complete Test Suite", () => {
             trustPay: trustPayPubkey,
             globalState: globalStatePubkey,
             systemProgram: anchor.web3.SystemProgram.programId,
+            dispute: await nextDispute(),
           })
           .signers([client])
           .rpc();
 
         let trustPayAccount = await program.account.trustPay.fetch(trustPayPubkey);
-        assert.equal(trustPayAccount.contractStatus, CONTRACT_STATUS_DISPUTED);
+        assert.equal(trustPayAccount.milestones[0].status, MILESTONE_STATUS_DISPUTED);
 
         // Resolver resolves
         await program.methods
-          .resolveDispute(0, 1, "After review, work meets specifications")
+          .resolveDispute(0, 10_000, RESOLUTION_SPLIT, "After review, work meets specifications")
           .accountsPartial({
             resolver: resolver.publicKey,
             payer: client.publicKey,
@@ -1897,14 +1986,14 @@ describe("Trust Pay - Complete Test Suite", () => {
             vault: vaultPubkey,
             payerTokenAccount: accounts.clientTokenAccount,
             recipientTokenAccount: accounts.workerTokenAccount,
-            feeDestination: accounts.feeDestination,
-            feeDestinationTokenAccount: getAssociatedTokenAddressSync(
-              accounts.tokenMint, accounts.feeDestination, true, TOKEN_PROGRAM
-            ),
+            treasury: accounts.treasury,
+            treasuryTokenAccount: accounts.treasuryTokenAccount,
             globalState: globalStatePubkey,
             systemProgram: anchor.web3.SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM,
             associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+            arbitrator: null,
+            dispute: await disputeOf(trustPayPubkey),
           })
           .signers([resolver])
           .rpc();
@@ -1919,4 +2008,4878 @@ describe("Trust Pay - Complete Test Suite", () => {
       });
     });
   });
-});
\ No newline at end of file
+
+  describe("10. WITHDRAW_FEES Tests", () => {
+    let globalStatePubkey: PublicKey;
+    let feeRecipient: anchor.web3.Keypair;
+
+    before(async () => {
+      [globalStatePubkey] = PublicKey.findProgramAddressSync(
+        [Buffer.from("global-state")],
+        program.programId
+      );
+      [feeRecipient] = makeKeypairs(1);
+    });
+
+    describe("Happy Path", () => {
+      it("Treasury tracks the fees collected from approvals", async () => {
+        const treasuryAccount = await program.account.treasury.fetch(accounts.treasury);
+        const treasuryBalance = await connection.getTokenAccountBalance(
+          accounts.treasuryTokenAccount
+        );
+
+        assert.equal(treasuryAccount.mint.toString(), accounts.tokenMint.toString());
+        assert.isTrue(treasuryAccount.totalFeesCollected.gtn(0));
+        assert.equal(
+          treasuryAccount.totalFeesCollected.sub(treasuryAccount.totalFeesWithdrawn).toString(),
+          treasuryBalance.value.amount
+        );
+      });
+
+      it("Authority withdraws fees from the treasury", async () => {
+        const treasuryBefore = await program.account.treasury.fetch(accounts.treasury);
+        const amount = treasuryBefore.totalFeesCollected.sub(treasuryBefore.totalFeesWithdrawn);
+
+        await program.methods
+          .withdrawFees(amount)
+          .accountsPartial({
+            authority: resolver.publicKey,
+            globalState: globalStatePubkey,
+            mint: accounts.tokenMint,
+            treasury: accounts.treasury,
+            treasuryTokenAccount: accounts.treasuryTokenAccount,
+            destination: feeRecipient.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM,
+            associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          })
+          .signers([resolver])
+          .rpc();
+
+        const treasuryAfter = await program.account.treasury.fetch(accounts.treasury);
+        assert.equal(
+          treasuryAfter.totalFeesWithdrawn.toString(),
+          treasuryBefore.totalFeesWithdrawn.add(amount).toString()
+        );
+        assert.equal(
+          treasuryAfter.withdrawalCount.toNumber(),
+          treasuryBefore.withdrawalCount.toNumber() + 1
+        );
+
+        const destinationBalance = await connection.getTokenAccountBalance(
+          getAssociatedTokenAddressSync(
+            accounts.tokenMint, feeRecipient.publicKey, false, TOKEN_PROGRAM
+          )
+        );
+        assert.equal(destinationBalance.value.amount, amount.toString());
+      });
+    });
+
+    describe("Error Cases", () => {
+      it("Fails when withdrawing more than the available fees", async () => {
+        try {
+          await program.methods
+            .withdrawFees(new BN(1))
+            .accountsPartial({
+              authority: resolver.publicKey,
+              globalState: globalStatePubkey,
+              mint: accounts.tokenMint,
+              treasury: accounts.treasury,
+              treasuryTokenAccount: accounts.treasuryTokenAccount,
+              destination: feeRecipient.publicKey,
+              systemProgram: anchor.web3.SystemProgram.programId,
+              tokenProgram: TOKEN_PROGRAM,
+              associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+            })
+            .signers([resolver])
+            .rpc();
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "InsufficientTreasuryFunds");
+        }
+      });
+
+      it("Fails when non-authority tries to withdraw", async () => {
+        try {
+          await program.methods
+            .withdrawFees(new BN(1))
+            .accountsPartial({
+              authority: worker.publicKey,
+              globalState: globalStatePubkey,
+              mint: accounts.tokenMint,
+              treasury: accounts.treasury,
+              treasuryTokenAccount: accounts.treasuryTokenAccount,
+              destination: worker.publicKey,
+              systemProgram: anchor.web3.SystemProgram.programId,
+              tokenProgram: TOKEN_PROGRAM,
+              associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+            })
+            .signers([worker])
+            .rpc();
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "ConstraintHasOne");
+        }
+      });
+    });
+  });
+
+  describe("11. FEE_CAPS Tests", () => {
+    let globalStatePubkey: PublicKey;
+
+    before(async () => {
+      [globalStatePubkey] = PublicKey.findProgramAddressSync(
+        [Buffer.from("global-state")],
+        program.programId
+      );
+    });
+
+    const setFeeCaps = (minFee: BN, maxFee: BN, signer = resolver) =>
+      program.methods
+        .setFeeCaps(minFee, maxFee)
+        .accountsPartial({
+          authority: signer.publicKey,
+          globalState: globalStatePubkey,
+          mint: accounts.tokenMint,
+        })
+        .signers([signer])
+        .rpc();
+
+    const createMilestoneContract = async (amounts: BN[]) => {
+      const trustPaySeed = getRandomBigNumber();
+      const [trustPayPubkey] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("trust-pay"),
+          client.publicKey.toBuffer(),
+          trustPaySeed.toArrayLike(Buffer, "le", 8)
+        ],
+        program.programId
+      );
+      const vaultPubkey = getAssociatedTokenAddressSync(
+        accounts.tokenMint, trustPayPubkey, true, TOKEN_PROGRAM
+      );
+      const total = amounts.reduce((sum, amount) => sum.add(amount), new BN(0));
+
+      await program.methods
+        .createContract(
+          trustPaySeed, ROLE_PAYER, client.publicKey, accounts.worker,
+          CONTRACT_TYPE_MILESTONE, "Capped Fees", "Contract used to test fee caps",
+          total,
+          amounts.map((amount, i) => ({ description: `Milestone ${i}`, amount })),
+          new BN(7 * 24 * 60 * 60), null
+        )
+        .accountsPartial({
+          creator: client.publicKey, mint: accounts.tokenMint,
+          creatorTokenAccount: accounts.clientTokenAccount, trustPay: trustPayPubkey,
+          vault: vaultPubkey, treasury: accounts.treasury,
+          globalState: globalStatePubkey, systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([client])
+        .rpc();
+
+      return { trustPayPubkey, vaultPubkey, total };
+    };
+
+    after(async () => {
+      await setFeeCaps(new BN(0), new BN(0));
+    });
+
+    describe("Happy Path", () => {
+      it("Applies the minimum fee to small contracts", async () => {
+        await setFeeCaps(new BN(1_000), new BN(50_000));
+
+        const { trustPayPubkey, vaultPubkey, total } = await createMilestoneContract([
+          new BN(3_000), new BN(3_000), new BN(4_000),
+        ]);
+
+        const trustPayAccount = await program.account.trustPay.fetch(trustPayPubkey);
+        const milestoneFees = trustPayAccount.milestones.reduce(
+          (sum, m) => sum.add(m.fee), new BN(0)
+        );
+        assert.equal(trustPayAccount.fee.toString(), "1000");
+        assert.equal(milestoneFees.toString(), trustPayAccount.fee.toString());
+
+        const vaultBalance = await connection.getTokenAccountBalance(vaultPubkey);
+        assert.equal(vaultBalance.value.amount, total.addn(1_000).toString());
+      });
+
+      it("Applies the maximum fee to large contracts", async () => {
+        await setFeeCaps(new BN(1_000), new BN(2_000));
+
+        const { trustPayPubkey } = await createMilestoneContract([
+          new BN(3_000_000_000), new BN(1_000_000_000),
+        ]);
+
+        const trustPayAccount = await program.account.trustPay.fetch(trustPayPubkey);
+        assert.equal(trustPayAccount.fee.toString(), "2000");
+        assert.equal(
+          trustPayAccount.milestones[0].fee.add(trustPayAccount.milestones[1].fee).toString(),
+          "2000"
+        );
+      });
+    });
+
+    describe("Error Cases", () => {
+      it("Fails when the minimum fee exceeds the maximum fee", async () => {
+        try {
+          await setFeeCaps(new BN(5_000), new BN(1_000));
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "InvalidFeeCaps");
+        }
+      });
+
+      it("Fails when non-authority sets fee caps", async () => {
+        try {
+          await setFeeCaps(new BN(0), new BN(1), worker);
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "ConstraintHasOne");
+        }
+      });
+    });
+  });
+
+  describe("12. FEE_RECONCILIATION Tests", () => {
+    it("Refunds the uncharged fee to the payer when the contract closes", async () => {
+      const [globalStatePubkey] = PublicKey.findProgramAddressSync(
+        [Buffer.from("global-state")],
+        program.programId
+      );
+      const trustPaySeed = getRandomBigNumber();
+      const [trustPayPubkey] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("trust-pay"),
+          client.publicKey.toBuffer(),
+          trustPaySeed.toArrayLike(Buffer, "le", 8)
+        ],
+        program.programId
+      );
+      const vaultPubkey = getAssociatedTokenAddressSync(
+        accounts.tokenMint, trustPayPubkey, true, TOKEN_PROGRAM
+      );
+
+      const payerBalanceBefore = await connection.getTokenAccountBalance(accounts.clientTokenAccount);
+
+      await program.methods
+        .createContract(
+          trustPaySeed, ROLE_PAYER, client.publicKey, accounts.worker,
+          CONTRACT_TYPE_MILESTONE, "Reconciled Fees", "Contract used to test fee refunds",
+          new BN(6_000_000),
+          [
+            { description: "Refunded milestone", amount: new BN(2_000_000) },
+            { description: "Paid milestone", amount: new BN(4_000_000) },
+          ],
+          new BN(7 * 24 * 60 * 60), null
+        )
+        .accountsPartial({
+          creator: client.publicKey, mint: accounts.tokenMint,
+          creatorTokenAccount: accounts.clientTokenAccount, trustPay: trustPayPubkey,
+          vault: vaultPubkey, treasury: accounts.treasury,
+          globalState: globalStatePubkey, systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([client])
+        .rpc();
+
+      const created = await program.account.trustPay.fetch(trustPayPubkey);
+      assert.equal(created.feeDeposited.toString(), created.fee.toString());
+      const paidMilestoneFee = created.milestones[1].fee;
+
+      for (const index of [0, 1]) {
+        await program.methods
+          .markMilestoneComplete(index)
+          .accountsPartial({
+            recipient: worker.publicKey,
+            trustPay: trustPayPubkey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([worker])
+          .rpc();
+      }
+
+      await program.methods
+        .disputeContract(0, "Deliverable was never handed over to the client")
+        .accountsPartial({
+          disputer: client.publicKey,
+          trustPay: trustPayPubkey,
+          globalState: globalStatePubkey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          dispute: await nextDispute(),
+        })
+        .signers([client])
+        .rpc();
+
+      await program.methods
+        .resolveDispute(0, 0, RESOLUTION_SPLIT, "Refund the payer for the missing deliverable")
+        .accountsPartial({
+          resolver: resolver.publicKey,
+          payer: client.publicKey,
+          recipient: worker.publicKey,
+          mint: accounts.tokenMint,
+          trustPay: trustPayPubkey,
+          vault: vaultPubkey,
+          payerTokenAccount: accounts.clientTokenAccount,
+          recipientTokenAccount: accounts.workerTokenAccount,
+          treasury: accounts.treasury,
+          treasuryTokenAccount: accounts.treasuryTokenAccount,
+          globalState: globalStatePubkey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          arbitrator: null,
+          dispute: await disputeOf(trustPayPubkey),
+        })
+        .signers([resolver])
+        .rpc();
+
+      const resolved = await program.account.trustPay.fetch(trustPayPubkey);
+      assert.equal(resolved.milestones[0].status, MILESTONE_STATUS_RESOLVED);
+      assert.equal(resolved.feeCharged.toNumber(), 0);
+
+      await program.methods
+        .approveMilestonePayment(1)
+        .accountsPartial({
+          payer: client.publicKey,
+          recipient: worker.publicKey,
+          trustPay: trustPayPubkey,
+          mint: accounts.tokenMint,
+          vault: vaultPubkey,
+          payerTokenAccount: accounts.clientTokenAccount,
+          recipientTokenAccount: accounts.workerTokenAccount,
+          treasury: accounts.treasury,
+          treasuryTokenAccount: accounts.treasuryTokenAccount,
+          globalState: globalStatePubkey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([client])
+        .rpc();
+
+      // The payer only pays for the approved milestone and its share of the fee
+      const payerBalanceAfter = await connection.getTokenAccountBalance(accounts.clientTokenAccount);
+      const spent = new BN(payerBalanceBefore.value.amount).sub(new BN(payerBalanceAfter.value.amount));
+      assert.equal(spent.toString(), new BN(4_000_000).add(paidMilestoneFee).toString());
+    });
+  });
+
+  describe("13. DISPUTE_BOND Tests", () => {
+    const DISPUTE_BOND_BPS = 500;
+    const ARBITRATION_FEE_BPS = 1000;
+    let globalStatePubkey: PublicKey;
+    let trustPayPubkey: PublicKey;
+    let vaultPubkey: PublicKey;
+
+    before("Configure dispute bonds and open a disputed contract", async () => {
+      [globalStatePubkey] = PublicKey.findProgramAddressSync([Buffer.from("global-state")], program.programId);
+
+      await program.methods
+        .updateDisputeConfig(DISPUTE_BOND_BPS, ARBITRATION_FEE_BPS)
+        .accountsPartial({
+          authority: resolver.publicKey,
+          globalState: globalStatePubkey,
+        })
+        .signers([resolver])
+        .rpc();
+
+      // The worker needs tokens to match the bond
+      await mintTo(
+        connection,
+        payer,
+        accounts.tokenMint,
+        accounts.workerTokenAccount,
+        payer.publicKey,
+        1_000_000,
+        undefined,
+        undefined,
+        TOKEN_PROGRAM
+      );
+
+      const seed = getRandomBigNumber();
+      [trustPayPubkey] = PublicKey.findProgramAddressSync(
+        [Buffer.from("trust-pay"), client.publicKey.toBuffer(), seed.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      vaultPubkey = getAssociatedTokenAddressSync(accounts.tokenMint, trustPayPubkey, true, TOKEN_PROGRAM);
+
+      await program.methods
+        .createContract(
+          seed, ROLE_PAYER, client.publicKey, accounts.worker,
+          CONTRACT_TYPE_ONE_TIME, "Bonded Dispute", "Test terms",
+          new BN(2_000_000), [], new BN(7 * 24 * 60 * 60), null
+        )
+        .accountsPartial({
+          creator: client.publicKey, mint: accounts.tokenMint,
+          creatorTokenAccount: accounts.clientTokenAccount, trustPay: trustPayPubkey,
+          vault: vaultPubkey, treasury: accounts.treasury,
+          globalState: globalStatePubkey, systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([client])
+        .rpc();
+
+      await program.methods
+        .markMilestoneComplete(0)
+        .accountsPartial({
+          recipient: worker.publicKey,
+          trustPay: trustPayPubkey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([worker])
+        .rpc();
+    });
+
+    after("Reset dispute config", async () => {
+      await program.methods
+        .updateDisputeConfig(0, 0)
+        .accountsPartial({
+          authority: resolver.publicKey,
+          globalState: globalStatePubkey,
+        })
+        .signers([resolver])
+        .rpc();
+    });
+
+    describe("Happy Path", () => {
+      it("Disputer posts a bond into the vault", async () => {
+        await program.methods
+          .disputeContract(0, "Deliverable is missing the agreed features entirely")
+          .accountsPartial({
+            disputer: client.publicKey,
+            mint: accounts.tokenMint,
+            disputerTokenAccount: accounts.clientTokenAccount,
+            trustPay: trustPayPubkey,
+            vault: vaultPubkey,
+            globalState: globalStatePubkey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM,
+            associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+            dispute: await nextDispute(),
+          })
+          .signers([client])
+          .rpc();
+
+        const trustPayAccount = await program.account.trustPay.fetch(trustPayPubkey);
+        assert.equal(trustPayAccount.milestones[0].disputeBond.toNumber(), 100_000);
+        assert.equal(trustPayAccount.milestones[0].disputer.toBase58(), client.publicKey.toBase58());
+      });
+
+      it("Counterparty matches the bond", async () => {
+        await program.methods
+          .matchDisputeBond(0)
+          .accountsPartial({
+            counterparty: worker.publicKey,
+            mint: accounts.tokenMint,
+            counterpartyTokenAccount: accounts.workerTokenAccount,
+            trustPay: trustPayPubkey,
+            vault: vaultPubkey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM,
+            associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          })
+          .signers([worker])
+          .rpc();
+
+        const trustPayAccount = await program.account.trustPay.fetch(trustPayPubkey);
+        assert.equal(trustPayAccount.milestones[0].counterBond.toNumber(), 100_000);
+      });
+
+      it("Winner takes the bond pool and the resolver takes the arbitration fee", async () => {
+        const workerBalanceBefore = await connection.getTokenAccountBalance(accounts.workerTokenAccount);
+        const resolverTokenAccount = getAssociatedTokenAddressSync(
+          accounts.tokenMint, resolver.publicKey, false, TOKEN_PROGRAM
+        );
+        const resolverBalanceBefore = await connection
+          .getTokenAccountBalance(resolverTokenAccount)
+          .then(balance => balance.value.amount)
+          .catch(() => "0");
+
+        await program.methods
+          .resolveDispute(0, 10_000, RESOLUTION_SPLIT, "Work was delivered as specified")
+          .accountsPartial({
+            resolver: resolver.publicKey,
+            payer: client.publicKey,
+            recipient: worker.publicKey,
+            mint: accounts.tokenMint,
+            trustPay: trustPayPubkey,
+            vault: vaultPubkey,
+            payerTokenAccount: accounts.clientTokenAccount,
+            recipientTokenAccount: accounts.workerTokenAccount,
+            resolverTokenAccount,
+            treasury: accounts.treasury,
+            treasuryTokenAccount: accounts.treasuryTokenAccount,
+            globalState: globalStatePubkey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM,
+            associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+            arbitrator: null,
+            dispute: await disputeOf(trustPayPubkey),
+          })
+          .signers([resolver])
+          .rpc();
+
+        // Bond pool of 200_000 less a 10% arbitration fee goes to the recipient
+        const workerBalanceAfter = await connection.getTokenAccountBalance(accounts.workerTokenAccount);
+        const workerGain = new BN(workerBalanceAfter.value.amount).sub(new BN(workerBalanceBefore.value.amount));
+        assert.equal(workerGain.toNumber(), 2_000_000 + 180_000);
+
+        const resolverBalanceAfter = await connection.getTokenAccountBalance(resolverTokenAccount);
+        const resolverGain = new BN(resolverBalanceAfter.value.amount).sub(new BN(resolverBalanceBefore));
+        assert.equal(resolverGain.toNumber(), 20_000);
+      });
+    });
+
+    describe("Error Cases", () => {
+      it("Fails when the disputer tries to match their own bond", async () => {
+        const seed = getRandomBigNumber();
+        const [errorTrustPay] = PublicKey.findProgramAddressSync(
+          [Buffer.from("trust-pay"), client.publicKey.toBuffer(), seed.toArrayLike(Buffer, "le", 8)],
+          program.programId
+        );
+        const vault = getAssociatedTokenAddressSync(accounts.tokenMint, errorTrustPay, true, TOKEN_PROGRAM);
+
+        await program.methods
+          .createContract(
+            seed, ROLE_PAYER, client.publicKey, accounts.worker,
+            CONTRACT_TYPE_ONE_TIME, "Self Match", "Test terms",
+            new BN(1_000_000), [], new BN(7 * 24 * 60 * 60), null
+          )
+          .accountsPartial({
+            creator: client.publicKey, mint: accounts.tokenMint,
+            creatorTokenAccount: accounts.clientTokenAccount, trustPay: errorTrustPay,
+            vault, treasury: accounts.treasury,
+            globalState: globalStatePubkey, systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          })
+          .signers([client])
+          .rpc();
+
+        await program.methods
+          .markMilestoneComplete(0)
+          .accountsPartial({
+            recipient: worker.publicKey,
+            trustPay: errorTrustPay,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([worker])
+          .rpc();
+
+        await program.methods
+          .disputeContract(0, "Deliverable is missing the agreed features entirely")
+          .accountsPartial({
+            disputer: client.publicKey,
+            mint: accounts.tokenMint,
+            disputerTokenAccount: accounts.clientTokenAccount,
+            trustPay: errorTrustPay,
+            vault,
+            globalState: globalStatePubkey,
+            dispute: await nextDispute(),
+          })
+          .signers([client])
+          .rpc();
+
+        try {
+          await program.methods
+            .matchDisputeBond(0)
+            .accountsPartial({
+              counterparty: client.publicKey,
+              mint: accounts.tokenMint,
+              counterpartyTokenAccount: accounts.clientTokenAccount,
+              trustPay: errorTrustPay,
+              vault,
+            })
+            .signers([client])
+            .rpc();
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "UnauthorizedBondMatch");
+        }
+      });
+
+      it("Fails when a non-authority updates the dispute config", async () => {
+        try {
+          await program.methods
+            .updateDisputeConfig(100, 100)
+            .accountsPartial({
+              authority: worker.publicKey,
+              globalState: globalStatePubkey,
+            })
+            .signers([worker])
+            .rpc();
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "ConstraintHasOne");
+        }
+      });
+
+      it("Fails with basis points above 100%", async () => {
+        try {
+          await program.methods
+            .updateDisputeConfig(10_001, 0)
+            .accountsPartial({
+              authority: resolver.publicKey,
+              globalState: globalStatePubkey,
+            })
+            .signers([resolver])
+            .rpc();
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "InvalidBasisPoints");
+        }
+      });
+    });
+  });
+
+  describe("14. GLOBAL_STATE_ADMIN Tests", () => {
+    let newAuthority: anchor.web3.Keypair;
+
+    before(async () => {
+      [newAuthority] = makeKeypairs(1);
+      const sig = await connection.requestAirdrop(newAuthority.publicKey, LAMPORTS_PER_SOL);
+      await confirmTransaction(connection, sig);
+    });
+
+    describe("Happy Path", () => {
+      it("Upgrade authority is the bootstrapped admin", async () => {
+        const globalState = await program.account.globalState.fetch(accounts.globalState);
+        assert.equal(globalState.authority.toBase58(), resolver.publicKey.toBase58());
+        assert.isNull(globalState.pendingAuthority);
+      });
+
+      it("Authority is transferred only after the new authority accepts", async () => {
+        await program.methods
+          .proposeAuthority(newAuthority.publicKey)
+          .accountsPartial({
+            authority: resolver.publicKey,
+            globalState: accounts.globalState,
+          })
+          .signers([resolver])
+          .rpc();
+
+        let globalState = await program.account.globalState.fetch(accounts.globalState);
+        assert.equal(globalState.authority.toBase58(), resolver.publicKey.toBase58());
+        assert.equal(globalState.pendingAuthority.toBase58(), newAuthority.publicKey.toBase58());
+
+        await program.methods
+          .acceptAuthority()
+          .accountsPartial({
+            pendingAuthority: newAuthority.publicKey,
+            globalState: accounts.globalState,
+          })
+          .signers([newAuthority])
+          .rpc();
+
+        globalState = await program.account.globalState.fetch(accounts.globalState);
+        assert.equal(globalState.authority.toBase58(), newAuthority.publicKey.toBase58());
+        assert.isNull(globalState.pendingAuthority);
+
+        // Hand the authority back for the remaining tests
+        await program.methods
+          .proposeAuthority(resolver.publicKey)
+          .accountsPartial({
+            authority: newAuthority.publicKey,
+            globalState: accounts.globalState,
+          })
+          .signers([newAuthority])
+          .rpc();
+        await program.methods
+          .acceptAuthority()
+          .accountsPartial({
+            pendingAuthority: resolver.publicKey,
+            globalState: accounts.globalState,
+          })
+          .signers([resolver])
+          .rpc();
+      });
+    });
+
+    describe("Error Cases", () => {
+      it("Fails to initialize the global state twice", async () => {
+        try {
+          await program.methods
+            .initializeGlobalState()
+            .accountsPartial({
+              authority: resolver.publicKey,
+              globalState: accounts.globalState,
+              program: program.programId,
+              programData: accounts.programData,
+              systemProgram: anchor.web3.SystemProgram.programId,
+            })
+            .signers([resolver])
+            .rpc();
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "already in use");
+        }
+      });
+
+      it("Fails when non-authority proposes a new authority", async () => {
+        try {
+          await program.methods
+            .proposeAuthority(worker.publicKey)
+            .accountsPartial({
+              authority: worker.publicKey,
+              globalState: accounts.globalState,
+            })
+            .signers([worker])
+            .rpc();
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "ConstraintHasOne");
+        }
+      });
+
+      it("Fails when someone other than the pending authority accepts", async () => {
+        await program.methods
+          .proposeAuthority(newAuthority.publicKey)
+          .accountsPartial({
+            authority: resolver.publicKey,
+            globalState: accounts.globalState,
+          })
+          .signers([resolver])
+          .rpc();
+
+        try {
+          await program.methods
+            .acceptAuthority()
+            .accountsPartial({
+              pendingAuthority: worker.publicKey,
+              globalState: accounts.globalState,
+            })
+            .signers([worker])
+            .rpc();
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "NotPendingAuthority");
+        }
+
+        // Withdraw the proposal by re-proposing the current authority
+        await program.methods
+          .proposeAuthority(resolver.publicKey)
+          .accountsPartial({
+            authority: resolver.publicKey,
+            globalState: accounts.globalState,
+          })
+          .signers([resolver])
+          .rpc();
+        const globalState = await program.account.globalState.fetch(accounts.globalState);
+        assert.equal(globalState.authority.toBase58(), resolver.publicKey.toBase58());
+      });
+    });
+  });
+
+  describe("15. PAUSE Tests", () => {
+    const PAUSE_NEW_CONTRACTS = 1 << 0;
+    const PAUSE_ACCEPTANCES = 1 << 1;
+    const PAUSE_PAYOUTS = 1 << 2;
+    const PAUSE_DISPUTE_RESOLUTION = 1 << 3;
+    const PAUSE_ALL = PAUSE_NEW_CONTRACTS | PAUSE_ACCEPTANCES | PAUSE_PAYOUTS | PAUSE_DISPUTE_RESOLUTION;
+
+    const setPause = (pauseFlags: number, pausedUntil: BN, signer = resolver) =>
+      program.methods
+        .setPause(pauseFlags, pausedUntil)
+        .accountsPartial({
+          authority: signer.publicKey,
+          globalState: accounts.globalState,
+        })
+        .signers([signer])
+        .rpc();
+
+    // Worker proposes a contract that waits for the client to accept
+    const createPendingContract = async () => {
+      const seed = getRandomBigNumber();
+      const [trustPay] = PublicKey.findProgramAddressSync(
+        [Buffer.from("trust-pay"), client.publicKey.toBuffer(), seed.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      const vault = getAssociatedTokenAddressSync(accounts.tokenMint, trustPay, true, TOKEN_PROGRAM);
+
+      await program.methods
+        .createContract(
+          seed, ROLE_RECIPIENT, client.publicKey, accounts.client,
+          CONTRACT_TYPE_ONE_TIME, "Pause Test", "Test terms",
+          new BN(1_000_000), [], new BN(7 * 24 * 60 * 60), null
+        )
+        .accountsPartial({
+          creator: worker.publicKey, mint: accounts.tokenMint,
+          creatorTokenAccount: accounts.workerTokenAccount, trustPay,
+          vault, treasury: accounts.treasury,
+          globalState: accounts.globalState, systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([worker])
+        .rpc();
+
+      return { seed, trustPay, vault };
+    };
+
+    // Client funds a contract and the worker marks it complete
+    const createCompletedContract = async () => {
+      const seed = getRandomBigNumber();
+      const [trustPay] = PublicKey.findProgramAddressSync(
+        [Buffer.from("trust-pay"), client.publicKey.toBuffer(), seed.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      const vault = getAssociatedTokenAddressSync(accounts.tokenMint, trustPay, true, TOKEN_PROGRAM);
+
+      await program.methods
+        .createContract(
+          seed, ROLE_PAYER, client.publicKey, accounts.worker,
+          CONTRACT_TYPE_ONE_TIME, "Pause Test", "Test terms",
+          new BN(1_000_000), [], new BN(7 * 24 * 60 * 60), null
+        )
+        .accountsPartial({
+          creator: client.publicKey, mint: accounts.tokenMint,
+          creatorTokenAccount: accounts.clientTokenAccount, trustPay,
+          vault, treasury: accounts.treasury,
+          globalState: accounts.globalState, systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([client])
+        .rpc();
+
+      await program.methods
+        .markMilestoneComplete(0)
+        .accountsPartial({ recipient: worker.publicKey, trustPay })
+        .signers([worker])
+        .rpc();
+
+      return { trustPay, vault };
+    };
+
+    const openDispute = async (target: { trustPay: PublicKey; vault: PublicKey }) =>
+      program.methods
+        .disputeContract(0, "Delivery was two weeks late")
+        .accountsPartial({
+          disputer: client.publicKey,
+          mint: accounts.tokenMint,
+          disputerTokenAccount: accounts.clientTokenAccount,
+          trustPay: target.trustPay,
+          vault: target.vault,
+          globalState: accounts.globalState,
+          dispute: await nextDispute(),
+        })
+        .signers([client])
+        .rpc();
+
+    let cancelTarget: { trustPay: PublicKey; vault: PublicKey };
+    let declineTarget: { trustPay: PublicKey; vault: PublicKey };
+    let undisputedTarget: { trustPay: PublicKey; vault: PublicKey };
+    let disputedTarget: { trustPay: PublicKey; vault: PublicKey };
+
+    before("Open pending contracts and pause everything", async () => {
+      cancelTarget = await createPendingContract();
+      declineTarget = await createPendingContract();
+      undisputedTarget = await createCompletedContract();
+      disputedTarget = await createCompletedContract();
+      await openDispute(disputedTarget);
+      await setPause(PAUSE_ALL, new BN(0));
+    });
+
+    after("Unpause", async () => {
+      await setPause(0, new BN(0));
+    });
+
+    describe("Happy Path", () => {
+      it("Creator can still cancel a pending contract", async () => {
+        await program.methods
+          .cancelContract()
+          .accountsPartial({
+            canceller: worker.publicKey, payer: client.publicKey,
+            recipient: worker.publicKey, mint: accounts.tokenMint,
+            trustPay: cancelTarget.trustPay, vault: cancelTarget.vault,
+            cancellerTokenAccount: accounts.workerTokenAccount,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM,
+            associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          })
+          .signers([worker])
+          .rpc();
+
+        try {
+          await program.account.trustPay.fetch(cancelTarget.trustPay);
+          assert.fail("Account should be closed");
+        } catch (error) {
+          assert.include(error.toString(), "Account does not exist");
+        }
+      });
+
+      it("Payer can still decline a pending contract", async () => {
+        await program.methods
+          .declineContract()
+          .accountsPartial({
+            payer: client.publicKey,
+            recipient: worker.publicKey,
+            mint: accounts.tokenMint,
+            trustPay: declineTarget.trustPay,
+            vault: declineTarget.vault,
+            recipientTokenAccount: accounts.workerTokenAccount,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM,
+            associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          })
+          .signers([client])
+          .rpc();
+
+        try {
+          await program.account.trustPay.fetch(declineTarget.trustPay);
+          assert.fail("Account should be closed");
+        } catch (error) {
+          assert.include(error.toString(), "Account does not exist");
+        }
+      });
+
+      it("Pause lapses at the auto-unpause time", async () => {
+        const now = await connection.getBlockTime(await connection.getSlot());
+        await setPause(PAUSE_NEW_CONTRACTS, new BN(now + 2));
+
+        try {
+          await createPendingContract();
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "OperationPaused");
+        }
+
+        await new Promise(resolve => setTimeout(resolve, 4 * SECONDS));
+        const { trustPay } = await createPendingContract();
+        const trustPayAccount = await program.account.trustPay.fetch(trustPay);
+        assert.equal(trustPayAccount.contractStatus, CONTRACT_STATUS_PENDING);
+
+        await setPause(PAUSE_ALL, new BN(0));
+      });
+    });
+
+    describe("Error Cases", () => {
+      it("Fails to create a contract while new contracts are paused", async () => {
+        try {
+          await createPendingContract();
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "OperationPaused");
+        }
+      });
+
+      it("Fails to accept a contract while acceptances are paused", async () => {
+        await setPause(PAUSE_ALL & ~PAUSE_NEW_CONTRACTS, new BN(0));
+        const { trustPay, vault } = await createPendingContract();
+        await setPause(PAUSE_ALL, new BN(0));
+
+        try {
+          await program.methods
+            .acceptContract(new BN(7 * 24 * 60 * 60))
+            .accountsPartial({
+              payer: client.publicKey,
+              mint: accounts.tokenMint,
+              payerTokenAccount: accounts.clientTokenAccount,
+              trustPay,
+              vault,
+              globalState: accounts.globalState,
+              systemProgram: anchor.web3.SystemProgram.programId,
+              tokenProgram: TOKEN_PROGRAM,
+              associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+            })
+            .signers([client])
+            .rpc();
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "OperationPaused");
+        }
+      });
+
+      it("Fails to withdraw fees while payouts are paused", async () => {
+        try {
+          await program.methods
+            .withdrawFees(new BN(1))
+            .accountsPartial({
+              authority: resolver.publicKey,
+              globalState: accounts.globalState,
+              mint: accounts.tokenMint,
+              treasury: accounts.treasury,
+              treasuryTokenAccount: accounts.treasuryTokenAccount,
+              destination: resolver.publicKey,
+              systemProgram: anchor.web3.SystemProgram.programId,
+              tokenProgram: TOKEN_PROGRAM,
+              associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+            })
+            .signers([resolver])
+            .rpc();
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "OperationPaused");
+        }
+      });
+
+      it("Fails to open a dispute or match its bond while new deposits are paused", async () => {
+        await setPause(PAUSE_NEW_CONTRACTS, new BN(0));
+
+        try {
+          await openDispute(undisputedTarget);
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "OperationPaused");
+        }
+
+        try {
+          await program.methods
+            .matchDisputeBond(0)
+            .accountsPartial({
+              counterparty: worker.publicKey,
+              mint: accounts.tokenMint,
+              counterpartyTokenAccount: accounts.workerTokenAccount,
+              trustPay: disputedTarget.trustPay,
+              vault: disputedTarget.vault,
+              globalState: accounts.globalState,
+            })
+            .signers([worker])
+            .rpc();
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "OperationPaused");
+        }
+
+        await setPause(PAUSE_ALL, new BN(0));
+      });
+
+      it("Fails to offer a settlement while dispute resolution is paused", async () => {
+        await setPause(PAUSE_DISPUTE_RESOLUTION, new BN(0));
+
+        try {
+          await program.methods
+            .offerSettlement(0, 5_000)
+            .accountsPartial({
+              party: worker.publicKey,
+              globalState: accounts.globalState,
+              trustPay: disputedTarget.trustPay,
+            })
+            .signers([worker])
+            .rpc();
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "OperationPaused");
+        }
+
+        await setPause(PAUSE_ALL, new BN(0));
+      });
+
+      it("Fails when non-authority sets pause flags", async () => {
+        try {
+          await setPause(0, new BN(0), worker);
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "ConstraintHasOne");
+        }
+      });
+
+      it("Fails with unknown flags or an auto-unpause time in the past", async () => {
+        for (const [flags, until] of [[1 << 7, 0], [PAUSE_PAYOUTS, 1]]) {
+          try {
+            await setPause(flags, new BN(until));
+            assert.fail("Should have failed");
+          } catch (error) {
+            assert.include(error.toString(), "InvalidPauseConfig");
+          }
+        }
+      });
+    });
+  });
+
+  describe("16. ARBITRATOR_REGISTRY Tests", () => {
+    let arbitrator: anchor.web3.Keypair;
+    let outsider: anchor.web3.Keypair;
+    let arbitratorPda: PublicKey;
+    let outsiderPda: PublicKey;
+
+    const arbitratorPdaFor = (key: PublicKey) =>
+      PublicKey.findProgramAddressSync([Buffer.from("arbitrator"), key.toBuffer()], program.programId)[0];
+
+    // Client pays for a one-time contract, worker delivers and client disputes
+    const openDispute = async () => {
+      const seed = getRandomBigNumber();
+      const [trustPay] = PublicKey.findProgramAddressSync(
+        [Buffer.from("trust-pay"), client.publicKey.toBuffer(), seed.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      const vault = getAssociatedTokenAddressSync(accounts.tokenMint, trustPay, true, TOKEN_PROGRAM);
+
+      await program.methods
+        .createContract(
+          seed, ROLE_PAYER, client.publicKey, accounts.worker,
+          CONTRACT_TYPE_ONE_TIME, "Arbitrated", "Test terms",
+          new BN(1_000_000), [], new BN(7 * 24 * 60 * 60), null
+        )
+        .accountsPartial({
+          creator: client.publicKey, mint: accounts.tokenMint,
+          creatorTokenAccount: accounts.clientTokenAccount, trustPay,
+          vault, treasury: accounts.treasury,
+          globalState: accounts.globalState, systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([client])
+        .rpc();
+
+      await program.methods
+        .markMilestoneComplete(0)
+        .accountsPartial({ recipient: worker.publicKey, trustPay })
+        .signers([worker])
+        .rpc();
+
+      await program.methods
+        .disputeContract(0, "Work does not match the agreed specification")
+        .accountsPartial({
+          disputer: client.publicKey,
+          mint: accounts.tokenMint,
+          disputerTokenAccount: accounts.clientTokenAccount,
+          trustPay,
+          vault,
+          globalState: accounts.globalState,
+          dispute: await nextDispute(),
+        })
+        .signers([client])
+        .rpc();
+
+      return { trustPay, vault };
+    };
+
+    const assign = async (trustPay: PublicKey, arbitratorAccount: PublicKey) =>
+      program.methods
+        .assignDispute(0)
+        .accountsPartial({
+          authority: resolver.publicKey,
+          globalState: accounts.globalState,
+          trustPay,
+          arbitratorAccount,
+          dispute: await disputeOf(trustPay),
+        })
+        .signers([resolver])
+        .rpc();
+
+    const resolveAs = async (signer: anchor.web3.Keypair, trustPay: PublicKey, vault: PublicKey) =>
+      program.methods
+        .resolveDispute(0, 10_000, RESOLUTION_SPLIT, "Work meets the specification")
+        .accountsPartial({
+          resolver: signer.publicKey,
+          payer: client.publicKey,
+          recipient: worker.publicKey,
+          mint: accounts.tokenMint,
+          trustPay,
+          vault,
+          payerTokenAccount: accounts.clientTokenAccount,
+          recipientTokenAccount: accounts.workerTokenAccount,
+          resolverTokenAccount: getAssociatedTokenAddressSync(
+            accounts.tokenMint, signer.publicKey, false, TOKEN_PROGRAM
+          ),
+          treasury: accounts.treasury,
+          treasuryTokenAccount: accounts.treasuryTokenAccount,
+          globalState: accounts.globalState,
+          arbitrator: arbitratorPdaFor(signer.publicKey),
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          dispute: await disputeOf(trustPay),
+        })
+        .signers([signer])
+        .rpc();
+
+    before("Register arbitrators", async () => {
+      [arbitrator, outsider] = makeKeypairs(2);
+      const sigs = await Promise.all([
+        connection.requestAirdrop(arbitrator.publicKey, LAMPORTS_PER_SOL),
+        connection.requestAirdrop(outsider.publicKey, LAMPORTS_PER_SOL),
+      ]);
+      await Promise.all(sigs.map(sig => confirmTransaction(connection, sig)));
+      arbitratorPda = arbitratorPdaFor(arbitrator.publicKey);
+      outsiderPda = arbitratorPdaFor(outsider.publicKey);
+
+      for (const key of [arbitrator.publicKey, outsider.publicKey]) {
+        await program.methods
+          .registerArbitrator(key, ["software", "design"])
+          .accountsPartial({
+            authority: resolver.publicKey,
+            globalState: accounts.globalState,
+            arbitratorAccount: arbitratorPdaFor(key),
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([resolver])
+          .rpc();
+      }
+    });
+
+    describe("Happy Path", () => {
+      it("Registers an active arbitrator with specialties", async () => {
+        const account = await program.account.arbitrator.fetch(arbitratorPda);
+        assert.isTrue(account.active);
+        assert.deepEqual(account.specialties, ["software", "design"]);
+        assert.equal(account.casesResolved.toNumber(), 0);
+      });
+
+      it("Assigned arbitrator resolves the dispute and their stats update", async () => {
+        const { trustPay, vault } = await openDispute();
+        await assign(trustPay, arbitratorPda);
+
+        const disputed = await program.account.trustPay.fetch(trustPay);
+        assert.equal(disputed.milestones[0].arbitrator.toBase58(), arbitrator.publicKey.toBase58());
+
+        await resolveAs(arbitrator, trustPay, vault);
+
+        const account = await program.account.arbitrator.fetch(arbitratorPda);
+        assert.equal(account.casesAssigned.toNumber(), 1);
+        assert.equal(account.casesResolved.toNumber(), 1);
+        assert.isTrue(account.averageResolutionTime.gten(0));
+      });
+    });
+
+    describe("Error Cases", () => {
+      it("Fails when an unassigned arbitrator resolves", async () => {
+        const { trustPay, vault } = await openDispute();
+        await assign(trustPay, arbitratorPda);
+
+        try {
+          await resolveAs(outsider, trustPay, vault);
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "UnauthorizedResolver");
+        }
+      });
+
+      it("Fails to assign a dispute to an inactive arbitrator", async () => {
+        await program.methods
+          .updateArbitrator(false, ["software"])
+          .accountsPartial({
+            authority: resolver.publicKey,
+            globalState: accounts.globalState,
+            arbitratorAccount: outsiderPda,
+          })
+          .signers([resolver])
+          .rpc();
+
+        const { trustPay } = await openDispute();
+        try {
+          await assign(trustPay, outsiderPda);
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "ArbitratorInactive");
+        }
+      });
+
+      it("Fails to assign a dispute to one of its parties", async () => {
+        const workerPda = arbitratorPdaFor(worker.publicKey);
+        await program.methods
+          .registerArbitrator(worker.publicKey, [])
+          .accountsPartial({
+            authority: resolver.publicKey,
+            globalState: accounts.globalState,
+            arbitratorAccount: workerPda,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([resolver])
+          .rpc();
+
+        const { trustPay } = await openDispute();
+        try {
+          await assign(trustPay, workerPda);
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "InvalidPanelMembers");
+        }
+      });
+
+      it("Fails when non-authority registers an arbitrator", async () => {
+        const [candidate] = makeKeypairs(1);
+        try {
+          await program.methods
+            .registerArbitrator(candidate.publicKey, [])
+            .accountsPartial({
+              authority: worker.publicKey,
+              globalState: accounts.globalState,
+              arbitratorAccount: arbitratorPdaFor(candidate.publicKey),
+              systemProgram: anchor.web3.SystemProgram.programId,
+            })
+            .signers([worker])
+            .rpc();
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "ConstraintHasOne");
+        }
+      });
+
+      it("Fails with too many specialties", async () => {
+        try {
+          await program.methods
+            .updateArbitrator(true, ["a", "b", "c", "d", "e", "f"])
+            .accountsPartial({
+              authority: resolver.publicKey,
+              globalState: accounts.globalState,
+              arbitratorAccount: arbitratorPda,
+            })
+            .signers([resolver])
+            .rpc();
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "InvalidSpecialties");
+        }
+      });
+    });
+  });
+
+  describe("17. CONTRACT_ARBITRATOR Tests", () => {
+    let mediator: anchor.web3.Keypair;
+    let trustPayPubkey: PublicKey;
+    let vaultPubkey: PublicKey;
+
+    const resolveAs = async (signer: anchor.web3.Keypair) =>
+      program.methods
+        .resolveDispute(0, 0, RESOLUTION_SPLIT, "Deliverable was not provided")
+        .accountsPartial({
+          resolver: signer.publicKey,
+          payer: client.publicKey,
+          recipient: worker.publicKey,
+          mint: accounts.tokenMint,
+          trustPay: trustPayPubkey,
+          vault: vaultPubkey,
+          payerTokenAccount: accounts.clientTokenAccount,
+          recipientTokenAccount: accounts.workerTokenAccount,
+          resolverTokenAccount: getAssociatedTokenAddressSync(
+            accounts.tokenMint, signer.publicKey, false, TOKEN_PROGRAM
+          ),
+          treasury: accounts.treasury,
+          treasuryTokenAccount: accounts.treasuryTokenAccount,
+          globalState: accounts.globalState,
+          arbitrator: null,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          dispute: await disputeOf(trustPayPubkey),
+        })
+        .signers([signer])
+        .rpc();
+
+    before("Create a disputed contract with its own mediator", async () => {
+      [mediator] = makeKeypairs(1);
+      const sig = await connection.requestAirdrop(mediator.publicKey, LAMPORTS_PER_SOL);
+      await confirmTransaction(connection, sig);
+
+      const seed = getRandomBigNumber();
+      [trustPayPubkey] = PublicKey.findProgramAddressSync(
+        [Buffer.from("trust-pay"), client.publicKey.toBuffer(), seed.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      vaultPubkey = getAssociatedTokenAddressSync(accounts.tokenMint, trustPayPubkey, true, TOKEN_PROGRAM);
+
+      await program.methods
+        .createContract(
+          seed, ROLE_PAYER, client.publicKey, accounts.worker,
+          CONTRACT_TYPE_ONE_TIME, "Mediated", "Test terms",
+          new BN(1_000_000), [], new BN(7 * 24 * 60 * 60), mediator.publicKey
+        )
+        .accountsPartial({
+          creator: client.publicKey, mint: accounts.tokenMint,
+          creatorTokenAccount: accounts.clientTokenAccount, trustPay: trustPayPubkey,
+          vault: vaultPubkey, treasury: accounts.treasury,
+          globalState: accounts.globalState, systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([client])
+        .rpc();
+
+      await program.methods
+        .markMilestoneComplete(0)
+        .accountsPartial({ recipient: worker.publicKey, trustPay: trustPayPubkey })
+        .signers([worker])
+        .rpc();
+
+      await program.methods
+        .disputeContract(0, "Deliverable was never handed over")
+        .accountsPartial({
+          disputer: client.publicKey,
+          mint: accounts.tokenMint,
+          disputerTokenAccount: accounts.clientTokenAccount,
+          trustPay: trustPayPubkey,
+          vault: vaultPubkey,
+          globalState: accounts.globalState,
+          dispute: await nextDispute(),
+        })
+        .signers([client])
+        .rpc();
+    });
+
+    describe("Error Cases", () => {
+      it("Fails when the protocol resolver resolves a mediated contract", async () => {
+        try {
+          await resolveAs(resolver);
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "UnauthorizedResolver");
+        }
+      });
+
+      it("Fails to assign a registry arbitrator to a mediated contract", async () => {
+        const [arbitratorPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("arbitrator"), mediator.publicKey.toBuffer()],
+          program.programId
+        );
+        await program.methods
+          .registerArbitrator(mediator.publicKey, [])
+          .accountsPartial({
+            authority: resolver.publicKey,
+            globalState: accounts.globalState,
+            arbitratorAccount: arbitratorPda,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([resolver])
+          .rpc();
+
+        try {
+          await program.methods
+            .assignDispute(0)
+            .accountsPartial({
+              authority: resolver.publicKey,
+              globalState: accounts.globalState,
+              trustPay: trustPayPubkey,
+              arbitratorAccount: arbitratorPda,
+              dispute: await disputeOf(trustPayPubkey),
+            })
+            .signers([resolver])
+            .rpc();
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "ContractArbitratorSet");
+        }
+      });
+
+      it("Fails when a party names themselves as arbitrator", async () => {
+        const seed = getRandomBigNumber();
+        const [trustPay] = PublicKey.findProgramAddressSync(
+          [Buffer.from("trust-pay"), client.publicKey.toBuffer(), seed.toArrayLike(Buffer, "le", 8)],
+          program.programId
+        );
+        try {
+          await program.methods
+            .createContract(
+              seed, ROLE_PAYER, client.publicKey, accounts.worker,
+              CONTRACT_TYPE_ONE_TIME, "Self Mediated", "Test terms",
+              new BN(1_000_000), [], new BN(7 * 24 * 60 * 60), worker.publicKey
+            )
+            .accountsPartial({
+              creator: client.publicKey, mint: accounts.tokenMint,
+              creatorTokenAccount: accounts.clientTokenAccount, trustPay,
+              vault: getAssociatedTokenAddressSync(accounts.tokenMint, trustPay, true, TOKEN_PROGRAM),
+              treasury: accounts.treasury,
+              globalState: accounts.globalState, systemProgram: anchor.web3.SystemProgram.programId,
+              tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+            })
+            .signers([client])
+            .rpc();
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "InvalidArbitrator");
+        }
+      });
+    });
+
+    describe("Happy Path", () => {
+      it("Contract arbitrator resolves the dispute", async () => {
+        const created = await program.account.trustPay.fetch(trustPayPubkey);
+        assert.equal(created.arbitrator.toBase58(), mediator.publicKey.toBase58());
+
+        const payerBalanceBefore = await connection.getTokenAccountBalance(accounts.clientTokenAccount);
+        await resolveAs(mediator);
+        const payerBalanceAfter = await connection.getTokenAccountBalance(accounts.clientTokenAccount);
+
+        // Full refund of the milestone and its fee
+        assert.isTrue(
+          new BN(payerBalanceAfter.value.amount).gt(new BN(payerBalanceBefore.value.amount))
+        );
+      });
+    });
+  });
+  describe("18. PANEL_VOTING Tests", () => {
+    const PANEL_THRESHOLD = new BN(5_000_000);
+    let members: anchor.web3.Keypair[];
+    let trustPayPubkey: PublicKey;
+    let vaultPubkey: PublicKey;
+    let panelPubkey: PublicKey;
+
+    const arbitratorPdaFor = (key: PublicKey) =>
+      PublicKey.findProgramAddressSync([Buffer.from("arbitrator"), key.toBuffer()], program.programId)[0];
+
+    const panelPdaFor = (dispute: PublicKey) =>
+      PublicKey.findProgramAddressSync([Buffer.from("panel"), dispute.toBuffer()], program.programId)[0];
+
+    const memberAccounts = (keys: PublicKey[]) =>
+      keys.map(key => ({ pubkey: arbitratorPdaFor(key), isWritable: true, isSigner: false }));
+
+    const setPanelConfig = (threshold: BN) =>
+      program.methods
+        .updatePanelConfig(threshold, 3, 2, new BN(3 * 24 * 60 * 60))
+        .accountsPartial({ authority: resolver.publicKey, globalState: accounts.globalState })
+        .signers([resolver])
+        .rpc();
+
+    // Client pays for a high-value one-time contract, worker delivers and client disputes
+    const openDispute = async (amount: BN) => {
+      const seed = getRandomBigNumber();
+      const [trustPay] = PublicKey.findProgramAddressSync(
+        [Buffer.from("trust-pay"), client.publicKey.toBuffer(), seed.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      const vault = getAssociatedTokenAddressSync(accounts.tokenMint, trustPay, true, TOKEN_PROGRAM);
+
+      await program.methods
+        .createContract(
+          seed, ROLE_PAYER, client.publicKey, accounts.worker,
+          CONTRACT_TYPE_ONE_TIME, "High Value", "Test terms",
+          amount, [], new BN(7 * 24 * 60 * 60), null
+        )
+        .accountsPartial({
+          creator: client.publicKey, mint: accounts.tokenMint,
+          creatorTokenAccount: accounts.clientTokenAccount, trustPay,
+          vault, treasury: accounts.treasury,
+          globalState: accounts.globalState, systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([client])
+        .rpc();
+
+      await program.methods
+        .markMilestoneComplete(0)
+        .accountsPartial({ recipient: worker.publicKey, trustPay })
+        .signers([worker])
+        .rpc();
+
+      await program.methods
+        .disputeContract(0, "Work does not match the agreed specification")
+        .accountsPartial({
+          disputer: client.publicKey,
+          mint: accounts.tokenMint,
+          disputerTokenAccount: accounts.clientTokenAccount,
+          trustPay,
+          vault,
+          globalState: accounts.globalState,
+          dispute: await nextDispute(),
+        })
+        .signers([client])
+        .rpc();
+
+      return { trustPay, vault };
+    };
+
+    const openPanel = async (trustPay: PublicKey, keys: PublicKey[], milestoneIndex = 0) => {
+      const dispute = await disputeOf(trustPay, milestoneIndex);
+      await program.methods
+        .openPanel(milestoneIndex, keys)
+        .accountsPartial({
+          authority: resolver.publicKey,
+          globalState: accounts.globalState,
+          trustPay,
+          dispute,
+          panel: panelPdaFor(dispute),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .remainingAccounts(memberAccounts(keys))
+        .signers([resolver])
+        .rpc();
+    };
+
+    const vote = async (
+      signer: anchor.web3.Keypair,
+      trustPay: PublicKey,
+      recipientShareBps: number,
+      milestoneIndex = 0
+    ) => {
+      const dispute = await disputeOf(trustPay, milestoneIndex);
+      await program.methods
+        .castPanelVote(milestoneIndex, recipientShareBps)
+        .accountsPartial({ arbitrator: signer.publicKey, trustPay, dispute, panel: panelPdaFor(dispute) })
+        .signers([signer])
+        .rpc();
+    };
+
+    before("Register panel arbitrators and enable panels", async () => {
+      members = makeKeypairs(4);
+      const sigs = await Promise.all(
+        members.map(member => connection.requestAirdrop(member.publicKey, LAMPORTS_PER_SOL))
+      );
+      await Promise.all(sigs.map(sig => confirmTransaction(connection, sig)));
+
+      for (const member of members) {
+        await program.methods
+          .registerArbitrator(member.publicKey, ["finance"])
+          .accountsPartial({
+            authority: resolver.publicKey,
+            globalState: accounts.globalState,
+            arbitratorAccount: arbitratorPdaFor(member.publicKey),
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([resolver])
+          .rpc();
+      }
+
+      await setPanelConfig(PANEL_THRESHOLD);
+
+      ({ trustPay: trustPayPubkey, vault: vaultPubkey } = await openDispute(PANEL_THRESHOLD));
+      panelPubkey = panelPdaFor(await disputeOf(trustPayPubkey));
+    });
+
+    after("Disable panels", async () => {
+      await setPanelConfig(new BN(0));
+    });
+
+    describe("Error Cases", () => {
+      it("Fails when a single resolver resolves a panel dispute", async () => {
+        try {
+          await program.methods
+            .resolveDispute(0, 10_000, RESOLUTION_SPLIT, "Work meets the specification")
+            .accountsPartial({
+              resolver: resolver.publicKey,
+              payer: client.publicKey,
+              recipient: worker.publicKey,
+              mint: accounts.tokenMint,
+              trustPay: trustPayPubkey,
+              vault: vaultPubkey,
+              payerTokenAccount: accounts.clientTokenAccount,
+              recipientTokenAccount: accounts.workerTokenAccount,
+              resolverTokenAccount: getAssociatedTokenAddressSync(
+                accounts.tokenMint, resolver.publicKey, false, TOKEN_PROGRAM
+              ),
+              treasury: accounts.treasury,
+              treasuryTokenAccount: accounts.treasuryTokenAccount,
+              globalState: accounts.globalState,
+              arbitrator: null,
+              systemProgram: anchor.web3.SystemProgram.programId,
+              tokenProgram: TOKEN_PROGRAM,
+              associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+              dispute: await disputeOf(trustPayPubkey),
+            })
+            .signers([resolver])
+            .rpc();
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "PanelRequired");
+        }
+      });
+
+      it("Fails to open a panel with a repeated member", async () => {
+        const keys = [members[0].publicKey, members[0].publicKey, members[1].publicKey];
+        try {
+          await openPanel(trustPayPubkey, keys);
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "InvalidPanelMembers");
+        }
+      });
+
+      it("Fails to open a panel below the threshold", async () => {
+        const { trustPay } = await openDispute(new BN(1_000_000));
+        try {
+          await openPanel(trustPay, members.slice(0, 3).map(m => m.publicKey));
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "PanelNotRequired");
+        }
+      });
+
+      it("Fails with a quorum that is not a majority", async () => {
+        try {
+          await program.methods
+            .updatePanelConfig(PANEL_THRESHOLD, 4, 2, new BN(60))
+            .accountsPartial({ authority: resolver.publicKey, globalState: accounts.globalState })
+            .signers([resolver])
+            .rpc();
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "InvalidPanelConfig");
+        }
+      });
+    });
+
+    describe("Happy Path", () => {
+      it("Opens a panel of three registered arbitrators", async () => {
+        await openPanel(trustPayPubkey, members.slice(0, 3).map(m => m.publicKey));
+
+        const panel = await program.account.disputePanel.fetch(panelPubkey);
+        assert.equal(panel.members.length, 3);
+        assert.equal(panel.quorum, 2);
+        assert.equal(panel.round, 0);
+
+        const arbitrator = await program.account.arbitrator.fetch(arbitratorPdaFor(members[0].publicKey));
+        assert.equal(arbitrator.casesAssigned.toNumber(), 1);
+      });
+
+      it("Rejects votes from outsiders and repeat voters", async () => {
+        try {
+          await vote(members[3], trustPayPubkey, 10_000);
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "NotPanelMember");
+        }
+
+        await vote(members[0], trustPayPubkey, 10_000);
+        try {
+          await vote(members[0], trustPayPubkey, 0);
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "AlreadyVoted");
+        }
+      });
+
+      it("Cannot reassign a panel that can still reach quorum", async () => {
+        try {
+          await program.methods
+            .reassignPanel(0, members.slice(1, 4).map(m => m.publicKey))
+            .accountsPartial({
+              authority: resolver.publicKey,
+              globalState: accounts.globalState,
+              trustPay: trustPayPubkey,
+              dispute: await disputeOf(trustPayPubkey),
+              panel: panelPubkey,
+            })
+            .remainingAccounts(memberAccounts(members.slice(1, 4).map(m => m.publicKey)))
+            .signers([resolver])
+            .rpc();
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "PanelNotDeadlocked");
+        }
+      });
+
+      it("Reassigns a deadlocked panel", async () => {
+        // Full, none and half to the recipient leave no share with a quorum
+        await vote(members[1], trustPayPubkey, 0);
+        await vote(members[2], trustPayPubkey, 5_000);
+
+        const keys = members.slice(1, 4).map(m => m.publicKey);
+        await program.methods
+          .reassignPanel(0, keys)
+          .accountsPartial({
+            authority: resolver.publicKey,
+            globalState: accounts.globalState,
+            trustPay: trustPayPubkey,
+            dispute: await disputeOf(trustPayPubkey),
+            panel: panelPubkey,
+          })
+          .remainingAccounts(memberAccounts(keys))
+          .signers([resolver])
+          .rpc();
+
+        const panel = await program.account.disputePanel.fetch(panelPubkey);
+        assert.equal(panel.round, 1);
+        assert.isTrue(panel.votes.every(v => v === null));
+      });
+
+      it("Executes once a quorum agrees", async () => {
+        await vote(members[1], trustPayPubkey, 10_000);
+        await vote(members[3], trustPayPubkey, 10_000);
+
+        const panel = await program.account.disputePanel.fetch(panelPubkey);
+        assert.equal(panel.decision, 10_000);
+
+        const workerBalanceBefore = await connection.getTokenAccountBalance(accounts.workerTokenAccount);
+        await program.methods
+          .executePanelDecision(0)
+          .accountsPartial({
+            executor: worker.publicKey,
+            payer: client.publicKey,
+            recipient: worker.publicKey,
+            mint: accounts.tokenMint,
+            trustPay: trustPayPubkey,
+            vault: vaultPubkey,
+            payerTokenAccount: accounts.clientTokenAccount,
+            recipientTokenAccount: accounts.workerTokenAccount,
+            treasury: accounts.treasury,
+            treasuryTokenAccount: accounts.treasuryTokenAccount,
+            globalState: accounts.globalState,
+            panel: panelPubkey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM,
+            associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+            dispute: await disputeOf(trustPayPubkey),
+          })
+          .remainingAccounts(memberAccounts(members.slice(1, 4).map(m => m.publicKey)))
+          .signers([worker])
+          .rpc();
+        const workerBalanceAfter = await connection.getTokenAccountBalance(accounts.workerTokenAccount);
+
+        assert.equal(
+          new BN(workerBalanceAfter.value.amount).sub(new BN(workerBalanceBefore.value.amount)).toString(),
+          PANEL_THRESHOLD.toString()
+        );
+
+        const executed = await program.account.disputePanel.fetch(panelPubkey);
+        assert.equal(executed.status, 2);
+
+        // Only the members whose votes formed the decision are credited with the case
+        const resolved = await Promise.all(
+          members.map(async m => (await program.account.arbitrator.fetch(arbitratorPdaFor(m.publicKey))).casesResolved.toNumber())
+        );
+        assert.deepEqual(resolved, [0, 1, 0, 1]);
+      });
+
+      it("Seats a new panel when a dismissed contract dispute is raised again", async () => {
+        const seed = getRandomBigNumber();
+        const [trustPay] = PublicKey.findProgramAddressSync(
+          [Buffer.from("trust-pay"), client.publicKey.toBuffer(), seed.toArrayLike(Buffer, "le", 8)],
+          program.programId
+        );
+        const vault = getAssociatedTokenAddressSync(accounts.tokenMint, trustPay, true, TOKEN_PROGRAM);
+        await program.methods
+          .createContract(
+            seed, ROLE_PAYER, client.publicKey, accounts.worker,
+            CONTRACT_TYPE_ONE_TIME, "Twice Disputed", "Test terms",
+            PANEL_THRESHOLD, [], new BN(7 * 24 * 60 * 60), null
+          )
+          .accountsPartial({
+            creator: client.publicKey, mint: accounts.tokenMint,
+            creatorTokenAccount: accounts.clientTokenAccount, trustPay,
+            vault, treasury: accounts.treasury,
+            globalState: accounts.globalState, systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          })
+          .signers([client])
+          .rpc();
+
+        const keys = members.slice(0, 3).map(m => m.publicKey);
+        const panels: PublicKey[] = [];
+        for (let round = 0; round < 2; round++) {
+          const dispute = await nextDispute();
+          await program.methods
+            .disputeContract(CONTRACT_DISPUTE_INDEX, "Client has not provided access to the servers")
+            .accountsPartial({
+              disputer: worker.publicKey,
+              mint: accounts.tokenMint,
+              disputerTokenAccount: accounts.workerTokenAccount,
+              trustPay,
+              vault,
+              globalState: accounts.globalState,
+              dispute,
+            })
+            .signers([worker])
+            .rpc();
+
+          await openPanel(trustPay, keys, CONTRACT_DISPUTE_INDEX);
+          await vote(members[0], trustPay, DISMISS_VOTE, CONTRACT_DISPUTE_INDEX);
+          await vote(members[1], trustPay, DISMISS_VOTE, CONTRACT_DISPUTE_INDEX);
+
+          await program.methods
+            .executePanelDecision(CONTRACT_DISPUTE_INDEX)
+            .accountsPartial({
+              executor: worker.publicKey,
+              payer: client.publicKey,
+              recipient: worker.publicKey,
+              mint: accounts.tokenMint,
+              trustPay,
+              vault,
+              payerTokenAccount: accounts.clientTokenAccount,
+              recipientTokenAccount: accounts.workerTokenAccount,
+              treasury: accounts.treasury,
+              treasuryTokenAccount: accounts.treasuryTokenAccount,
+              globalState: accounts.globalState,
+              panel: panelPdaFor(dispute),
+              systemProgram: anchor.web3.SystemProgram.programId,
+              tokenProgram: TOKEN_PROGRAM,
+              associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+              dispute,
+            })
+            .remainingAccounts(memberAccounts(keys))
+            .signers([worker])
+            .rpc();
+          panels.push(panelPdaFor(dispute));
+        }
+
+        assert.notEqual(panels[0].toBase58(), panels[1].toBase58());
+        const contract = await program.account.trustPay.fetch(trustPay);
+        assert.equal(contract.contractStatus, CONTRACT_STATUS_IN_PROGRESS);
+        const panel = await program.account.disputePanel.fetch(panels[1]);
+        assert.equal(panel.decision, DISMISS_VOTE);
+      });
+    });
+  });
+  describe("19. DISPUTE_APPEAL Tests", () => {
+    const APPEAL_WINDOW = 5;
+    const APPEAL_BOND_BPS = 500;
+    let arbitrator: anchor.web3.Keypair;
+    let arbitratorPda: PublicKey;
+
+    // Client pays for a one-time contract, worker delivers and client disputes
+    const openDispute = async () => {
+      const seed = getRandomBigNumber();
+      const [trustPay] = PublicKey.findProgramAddressSync(
+        [Buffer.from("trust-pay"), client.publicKey.toBuffer(), seed.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      const vault = getAssociatedTokenAddressSync(accounts.tokenMint, trustPay, true, TOKEN_PROGRAM);
+
+      await program.methods
+        .createContract(
+          seed, ROLE_PAYER, client.publicKey, accounts.worker,
+          CONTRACT_TYPE_ONE_TIME, "Appealable", "Test terms",
+          new BN(1_000_000), [], new BN(7 * 24 * 60 * 60), null
+        )
+        .accountsPartial({
+          creator: client.publicKey, mint: accounts.tokenMint,
+          creatorTokenAccount: accounts.clientTokenAccount, trustPay,
+          vault, treasury: accounts.treasury,
+          globalState: accounts.globalState, systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([client])
+        .rpc();
+
+      await program.methods
+        .markMilestoneComplete(0)
+        .accountsPartial({ recipient: worker.publicKey, trustPay })
+        .signers([worker])
+        .rpc();
+
+      await program.methods
+        .disputeContract(0, "Work does not match the agreed specification")
+        .accountsPartial({
+          disputer: client.publicKey,
+          mint: accounts.tokenMint,
+          disputerTokenAccount: accounts.clientTokenAccount,
+          trustPay,
+          vault,
+          globalState: accounts.globalState,
+          dispute: await nextDispute(),
+        })
+        .signers([client])
+        .rpc();
+
+      return { trustPay, vault };
+    };
+
+    const resolveAs = async (
+      signer: anchor.web3.Keypair,
+      trustPay: PublicKey,
+      vault: PublicKey,
+      recipientShareBps: number,
+      arbitratorAccount: PublicKey | null
+    ) =>
+      program.methods
+        .resolveDispute(0, recipientShareBps, RESOLUTION_SPLIT, "Ruling on the delivered work")
+        .accountsPartial({
+          resolver: signer.publicKey,
+          payer: client.publicKey,
+          recipient: worker.publicKey,
+          mint: accounts.tokenMint,
+          trustPay,
+          vault,
+          payerTokenAccount: accounts.clientTokenAccount,
+          recipientTokenAccount: accounts.workerTokenAccount,
+          resolverTokenAccount: getAssociatedTokenAddressSync(
+            accounts.tokenMint, signer.publicKey, false, TOKEN_PROGRAM
+          ),
+          treasury: accounts.treasury,
+          treasuryTokenAccount: accounts.treasuryTokenAccount,
+          globalState: accounts.globalState,
+          arbitrator: arbitratorAccount,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          dispute: await disputeOf(trustPay),
+        })
+        .signers([signer])
+        .rpc();
+
+    const appeal = async (signer: anchor.web3.Keypair, signerTokenAccount: PublicKey, trustPay: PublicKey, vault: PublicKey) =>
+      program.methods
+        .appealRuling(0)
+        .accountsPartial({
+          appellant: signer.publicKey,
+          mint: accounts.tokenMint,
+          appellantTokenAccount: signerTokenAccount,
+          trustPay,
+          vault,
+          globalState: accounts.globalState,
+          dispute: await disputeOf(trustPay),
+        })
+        .signers([signer])
+        .rpc();
+
+    const finalize = async (
+      trustPay: PublicKey,
+      vault: PublicKey,
+      resolverKey: PublicKey,
+      arbitratorAccount: PublicKey | null = null
+    ) =>
+      program.methods
+        .finalizeRuling(0)
+        .accountsPartial({
+          caller: worker.publicKey,
+          payer: client.publicKey,
+          recipient: worker.publicKey,
+          mint: accounts.tokenMint,
+          trustPay,
+          vault,
+          payerTokenAccount: accounts.clientTokenAccount,
+          recipientTokenAccount: accounts.workerTokenAccount,
+          resolverTokenAccount: getAssociatedTokenAddressSync(
+            accounts.tokenMint, resolverKey, false, TOKEN_PROGRAM
+          ),
+          treasury: accounts.treasury,
+          treasuryTokenAccount: accounts.treasuryTokenAccount,
+          globalState: accounts.globalState,
+          arbitrator: arbitratorAccount,
+          panel: null,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          dispute: await disputeOf(trustPay),
+        })
+        .signers([worker])
+        .rpc();
+
+    const assign = async (trustPay: PublicKey) =>
+      program.methods
+        .assignDispute(0)
+        .accountsPartial({
+          authority: resolver.publicKey,
+          globalState: accounts.globalState,
+          trustPay,
+          arbitratorAccount: arbitratorPda,
+          dispute: await disputeOf(trustPay),
+        })
+        .signers([resolver])
+        .rpc();
+
+    const casesResolved = async () =>
+      (await program.account.arbitrator.fetch(arbitratorPda)).casesResolved.toNumber();
+
+    before("Register an arbitrator and open an appeal window", async () => {
+      [arbitrator] = makeKeypairs(1);
+      const sig = await connection.requestAirdrop(arbitrator.publicKey, LAMPORTS_PER_SOL);
+      await confirmTransaction(connection, sig);
+      [arbitratorPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("arbitrator"), arbitrator.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .registerArbitrator(arbitrator.publicKey, ["software"])
+        .accountsPartial({
+          authority: resolver.publicKey,
+          globalState: accounts.globalState,
+          arbitratorAccount: arbitratorPda,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([resolver])
+        .rpc();
+
+      await program.methods
+        .updateAppealConfig(new BN(APPEAL_WINDOW), APPEAL_BOND_BPS)
+        .accountsPartial({ authority: resolver.publicKey, globalState: accounts.globalState })
+        .signers([resolver])
+        .rpc();
+    });
+
+    after("Close the appeal window", async () => {
+      await program.methods
+        .updateAppealConfig(new BN(0), 0)
+        .accountsPartial({ authority: resolver.publicKey, globalState: accounts.globalState })
+        .signers([resolver])
+        .rpc();
+    });
+
+    describe("Happy Path", () => {
+      it("Records a ruling and settles it after the appeal window", async () => {
+        const { trustPay, vault } = await openDispute();
+        const workerBalanceBefore = await connection.getTokenAccountBalance(accounts.workerTokenAccount);
+
+        await resolveAs(resolver, trustPay, vault, 10_000, null);
+
+        const ruled = await program.account.trustPay.fetch(trustPay);
+        assert.equal(ruled.milestones[0].ruling, 10_000);
+        assert.equal(ruled.contractStatus, 3); // Still DISPUTED
+        const workerBalanceRuled = await connection.getTokenAccountBalance(accounts.workerTokenAccount);
+        assert.equal(workerBalanceRuled.value.amount, workerBalanceBefore.value.amount);
+
+        try {
+          await finalize(trustPay, vault, resolver.publicKey);
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "AppealWindowOpen");
+        }
+
+        await new Promise(resolve => setTimeout(resolve, (APPEAL_WINDOW + 2) * 1000));
+        await finalize(trustPay, vault, resolver.publicKey);
+
+        const workerBalanceAfter = await connection.getTokenAccountBalance(accounts.workerTokenAccount);
+        assert.equal(
+          new BN(workerBalanceAfter.value.amount).sub(new BN(workerBalanceBefore.value.amount)).toString(),
+          "1000000"
+        );
+      });
+
+      it("Credits the arbitrator once its ruling settles", async () => {
+        const { trustPay, vault } = await openDispute();
+        await assign(trustPay);
+        const resolvedBefore = await casesResolved();
+
+        await resolveAs(arbitrator, trustPay, vault, 10_000, arbitratorPda);
+        assert.equal(await casesResolved(), resolvedBefore);
+
+        await new Promise(resolve => setTimeout(resolve, (APPEAL_WINDOW + 2) * 1000));
+        try {
+          await finalize(trustPay, vault, arbitrator.publicKey);
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "ArbitratorMismatch");
+        }
+        await finalize(trustPay, vault, arbitrator.publicKey, arbitratorPda);
+
+        assert.equal(await casesResolved(), resolvedBefore + 1);
+      });
+
+      it("Losing party appeals an arbitrator's ruling to the authority", async () => {
+        const { trustPay, vault } = await openDispute();
+        await assign(trustPay);
+        const resolvedBefore = await casesResolved();
+
+        // Arbitrator rules for the worker, so only the client can appeal
+        await resolveAs(arbitrator, trustPay, vault, 10_000, arbitratorPda);
+        try {
+          await appeal(worker, accounts.workerTokenAccount, trustPay, vault);
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "UnauthorizedAppeal");
+        }
+
+        await appeal(client, accounts.clientTokenAccount, trustPay, vault);
+        const clientBalanceBefore = await connection.getTokenAccountBalance(accounts.clientTokenAccount);
+
+        const appealed = await program.account.trustPay.fetch(trustPay);
+        assert.equal(appealed.milestones[0].appealLevel, 1);
+        assert.equal(appealed.milestones[0].appealBond.toNumber(), 50_000);
+        assert.isNull(appealed.milestones[0].ruling);
+
+        // The appeal is heard by the authority alone and settles immediately
+        try {
+          await resolveAs(arbitrator, trustPay, vault, 0, arbitratorPda);
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "UnauthorizedResolver");
+        }
+        await resolveAs(resolver, trustPay, vault, 0, null);
+
+        // Overturned: the client gets the milestone and the appeal bond back
+        const clientBalanceAfter = await connection.getTokenAccountBalance(accounts.clientTokenAccount);
+        assert.isTrue(
+          new BN(clientBalanceAfter.value.amount)
+            .sub(new BN(clientBalanceBefore.value.amount))
+            .gte(new BN(1_050_000))
+        );
+
+        // The overturned ruling never counts as resolved by the arbitrator
+        assert.equal(await casesResolved(), resolvedBefore);
+      });
+    });
+
+    describe("Error Cases", () => {
+      it("Fails to appeal the authority's ruling when there is no higher tier", async () => {
+        const { trustPay, vault } = await openDispute();
+        await resolveAs(resolver, trustPay, vault, 10_000, null);
+
+        try {
+          await appeal(client, accounts.clientTokenAccount, trustPay, vault);
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "AppealUnavailable");
+        }
+      });
+
+      it("Fails to rule twice during the appeal window", async () => {
+        const { trustPay, vault } = await openDispute();
+        await resolveAs(resolver, trustPay, vault, 0, null);
+
+        try {
+          await resolveAs(resolver, trustPay, vault, 10_000, null);
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "RulingPending");
+        }
+      });
+
+      it("Fails to finalize without a ruling", async () => {
+        const { trustPay, vault } = await openDispute();
+        try {
+          await finalize(trustPay, vault, resolver.publicKey);
+          assert.fail("Should have failed");
+        } catch (error) {
+          assert.include(error.toString(), "NoPendingRuling");
+        }
+      });
+    });
+  });
+  describe("20. CUSTOM_SPLIT Tests", () => {
+    let trustPayPubkey: PublicKey;
+    let vaultPubkey: PublicKey;
+
+    const resolveWithShare = async (recipientShareBps: number) =>
+      program.methods
+        .resolveDispute(0, recipientShareBps, RESOLUTION_SPLIT, "Recipient delivered most of the work")
+        .accountsPartial({
+          resolver: resolver.publicKey,
+          payer: client.publicKey,
+          recipient: worker.publicKey,
+          mint: accounts.tokenMint,
+          trustPay: trustPayPubkey,
+          vault: vaultPubkey,
+          payerTokenAccount: accounts.clientTokenAccount,
+          recipientTokenAccount: accounts.workerTokenAccount,
+          treasury: accounts.treasury,
+          treasuryTokenAccount: accounts.treasuryTokenAccount,
+          globalState: accounts.globalState,
+          arbitrator: null,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          dispute: await disputeOf(trustPayPubkey),
+        })
+        .signers([resolver])
+        .rpc();
+
+    before("Create and dispute a contract", async () => {
+      const seed = getRandomBigNumber();
+      [trustPayPubkey] = PublicKey.findProgramAddressSync(
+        [Buffer.from("trust-pay"), client.publicKey.toBuffer(), seed.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      vaultPubkey = getAssociatedTokenAddressSync(accounts.tokenMint, trustPayPubkey, true, TOKEN_PROGRAM);
+
+      await program.methods
+        .createContract(
+          seed, ROLE_PAYER, client.publicKey, accounts.worker,
+          CONTRACT_TYPE_ONE_TIME, "Partial Delivery", "Test terms",
+          new BN(1_000_000), [], new BN(7 * 24 * 60 * 60), null
+        )
+        .accountsPartial({
+          creator: client.publicKey, mint: accounts.tokenMint,
+          creatorTokenAccount: accounts.clientTokenAccount, trustPay: trustPayPubkey,
+          vault: vaultPubkey, treasury: accounts.treasury,
+          globalState: accounts.globalState, systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([client])
+        .rpc();
+
+      await program.methods
+        .markMilestoneComplete(0)
+        .accountsPartial({ recipient: worker.publicKey, trustPay: trustPayPubkey })
+        .signers([worker])
+        .rpc();
+
+      await program.methods
+        .disputeContract(0, "Only part of the work was delivered")
+        .accountsPartial({
+          disputer: client.publicKey,
+          mint: accounts.tokenMint,
+          disputerTokenAccount: accounts.clientTokenAccount,
+          trustPay: trustPayPubkey,
+          vault: vaultPubkey,
+          globalState: accounts.globalState,
+          dispute: await nextDispute(),
+        })
+        .signers([client])
+        .rpc();
+    });
+
+    describe("Happy Path", () => {
+      it("Splits 70/30 and charges the fee on the recipient's portion only", async () => {
+        const contract = await program.account.trustPay.fetch(trustPayPubkey);
+        const milestoneFee = contract.milestones[0].fee;
+        const expectedFee = milestoneFee.muln(7_000).divn(10_000);
+
+        const payerBefore = await connection.getTokenAccountBalance(accounts.clientTokenAccount);
+        const workerBefore = await connection.getTokenAccountBalance(accounts.workerTokenAccount);
+        const treasuryBefore = await connection.getTokenAccountBalance(accounts.treasuryTokenAccount);
+        const dispute = await disputeOf(trustPayPubkey);
+
+        await resolveWithShare(7_000);
+
+        const payerAfter = await connection.getTokenAccountBalance(accounts.clientTokenAccount);
+        const workerAfter = await connection.getTokenAccountBalance(accounts.workerTokenAccount);
+        const treasuryAfter = await connection.getTokenAccountBalance(accounts.treasuryTokenAccount);
+
+        assert.equal(
+          new BN(workerAfter.value.amount).sub(new BN(workerBefore.value.amount)).toString(),
+          "700000"
+        );
+        assert.equal(
+          new BN(treasuryAfter.value.amount).sub(new BN(treasuryBefore.value.amount)).toString(),
+          expectedFee.toString()
+        );
+        // The payer's 30% plus the fee that was never charged
+        assert.equal(
+          new BN(payerAfter.value.amount).sub(new BN(payerBefore.value.amount)).toString(),
+          new BN(300_000).add(milestoneFee).sub(expectedFee).toString()
+        );
+
+        // The dispute record outlives the closed contract
+        const record = await program.account.dispute.fetch(dispute);
+        assert.equal(record.status, DISPUTE_STATUS_RESOLVED);
+        assert.equal(record.outcome, 7_000);
+        assert.equal(record.resolvedBy.toString(), resolver.publicKey.toString());
+        assert.isNotNull(record.closedAt);
+      });
+    });
+  });
+  describe("21. DISPUTE_EVIDENCE Tests", () => {
+    const EVIDENCE_TYPE_DOCUMENT = 0;
+    const EVIDENCE_TYPE_IMAGE = 1;
+    let trustPayPubkey: PublicKey;
+    let vaultPubkey: PublicKey;
+    let evidenceLogPubkey: PublicKey;
+    let outsider: anchor.web3.Keypair;
+
+    const contentHash = (label: string) =>
+      Array.from(createHash("sha256").update(label).digest());
+
+    const submit = async (
+      signer: anchor.web3.Keypair,
+      uri: string,
+      evidenceType: number,
+      label = uri
+    ) =>
+      program.methods
+        .submitEvidence(0, contentHash(label), uri, evidenceType)
+        .accountsPartial({
+          submitter: signer.publicKey,
+          trustPay: trustPayPubkey,
+          evidenceLog: evidenceLogPubkey,
+          globalState: accounts.globalState,
+          panel: null,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          dispute: await disputeOf(trustPayPubkey),
+        })
+        .signers([signer])
+        .rpc();
+
+    before("Create and dispute a contract", async () => {
+      [outsider] = makeKeypairs(1);
+      const sig = await connection.requestAirdrop(outsider.publicKey, LAMPORTS_PER_SOL);
+      await confirmTransaction(connection, sig);
+
+      const seed = getRandomBigNumber();
+      [trustPayPubkey] = PublicKey.findProgramAddressSync(
+        [Buffer.from("trust-pay"), client.publicKey.toBuffer(), seed.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      vaultPubkey = getAssociatedTokenAddressSync(accounts.tokenMint, trustPayPubkey, true, TOKEN_PROGRAM);
+
+      await program.methods
+        .createContract(
+          seed, ROLE_PAYER, client.publicKey, accounts.worker,
+          CONTRACT_TYPE_ONE_TIME, "Evidence", "Test terms",
+          new BN(1_000_000), [], new BN(7 * 24 * 60 * 60), null
+        )
+        .accountsPartial({
+          creator: client.publicKey, mint: accounts.tokenMint,
+          creatorTokenAccount: accounts.clientTokenAccount, trustPay: trustPayPubkey,
+          vault: vaultPubkey, treasury: accounts.treasury,
+          globalState: accounts.globalState, systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([client])
+        .rpc();
+
+      await program.methods
+        .markMilestoneComplete(0)
+        .accountsPartial({ recipient: worker.publicKey, trustPay: trustPayPubkey })
+        .signers([worker])
+        .rpc();
+
+      await program.methods
+        .disputeContract(0, "Delivered files are missing the agreed assets")
+        .accountsPartial({
+          disputer: client.publicKey,
+          mint: accounts.tokenMint,
+          disputerTokenAccount: accounts.clientTokenAccount,
+          trustPay: trustPayPubkey,
+          vault: vaultPubkey,
+          globalState: accounts.globalState,
+          dispute: await nextDispute(),
+        })
+        .signers([client])
+        .rpc();
+
+      // One evidence log per dispute record
+      [evidenceLogPubkey] = PublicKey.findProgramAddressSync(
+        [Buffer.from("evidence"), (await disputeOf(trustPayPubkey)).toBuffer()],
+        program.programId
+      );
+    });
+
+    describe("Happy Path", () => {
+      it("Both parties and the resolver append to the evidence log", async () => {
+        await submit(client, "ipfs://spec-document", EVIDENCE_TYPE_DOCUMENT);
+        const first = await program.account.evidenceLog.fetch(evidenceLogPubkey);
+
+        await submit(worker, "ipfs://delivery-screenshot", EVIDENCE_TYPE_IMAGE);
+        await submit(resolver, "https://example.com/hearing-notes", EVIDENCE_TYPE_DOCUMENT);
+
+        const log = await program.account.evidenceLog.fetch(evidenceLogPubkey);
+        assert.equal(log.trustPay.toString(), trustPayPubkey.toString());
+        assert.equal(log.milestoneIndex, 0);
+        assert.equal(log.entries.length, 3);
+        assert.equal(log.entries[0].submitter.toString(), client.publicKey.toString());
+        assert.equal(log.entries[1].submitter.toString(), worker.publicKey.toString());
+        assert.equal(log.entries[1].uri, "ipfs://delivery-screenshot");
+        assert.equal(log.entries[1].evidenceType, EVIDENCE_TYPE_IMAGE);
+        assert.deepEqual(log.entries[1].contentHash, contentHash("ipfs://delivery-screenshot"));
+        assert.isTrue(log.entries[2].submittedAt.gt(new BN(0)));
+        // Every entry moves the head of the hash chain
+        assert.notDeepEqual(log.headHash, first.headHash);
+      });
+    });
+
+    describe("Error Cases", () => {
+      it("Fails when an outsider submits evidence", async () => {
+        try {
+          await submit(outsider, "ipfs://unrelated", EVIDENCE_TYPE_DOCUMENT);
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.include(err.toString(), "UnauthorizedEvidenceSubmitter");
+        }
+      });
+
+      it("Fails with an empty URI or unknown evidence type", async () => {
+        try {
+          await submit(client, "", EVIDENCE_TYPE_DOCUMENT, "empty");
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.include(err.toString(), "InvalidEvidence");
+        }
+
+        try {
+          await submit(client, "ipfs://unknown-type", 9);
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.include(err.toString(), "InvalidEvidence");
+        }
+      });
+
+      it("Fails once the dispute is resolved, leaving the log in place", async () => {
+        await program.methods
+          .resolveDispute(0, 0, RESOLUTION_SPLIT, "Assets were not delivered")
+          .accountsPartial({
+            resolver: resolver.publicKey,
+            payer: client.publicKey,
+            recipient: worker.publicKey,
+            mint: accounts.tokenMint,
+            trustPay: trustPayPubkey,
+            vault: vaultPubkey,
+            payerTokenAccount: accounts.clientTokenAccount,
+            recipientTokenAccount: accounts.workerTokenAccount,
+            treasury: accounts.treasury,
+            treasuryTokenAccount: accounts.treasuryTokenAccount,
+            globalState: accounts.globalState,
+            arbitrator: null,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM,
+            associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+            dispute: await disputeOf(trustPayPubkey),
+          })
+          .signers([resolver])
+          .rpc();
+
+        try {
+          await submit(worker, "ipfs://late-submission", EVIDENCE_TYPE_DOCUMENT);
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.notInclude(err.toString(), "Should have failed");
+        }
+
+        const log = await program.account.evidenceLog.fetch(evidenceLogPubkey);
+        assert.equal(log.entries.length, 3);
+      });
+    });
+  });
+  describe("22. DISPUTE_SETTLEMENT Tests", () => {
+    let trustPayPubkey: PublicKey;
+    let vaultPubkey: PublicKey;
+
+    const offer = (signer: anchor.web3.Keypair, recipientShareBps: number) =>
+      program.methods
+        .offerSettlement(0, recipientShareBps)
+        .accountsPartial({ party: signer.publicKey, trustPay: trustPayPubkey })
+        .signers([signer])
+        .rpc();
+
+    const accept = async (signer: anchor.web3.Keypair, recipientShareBps: number) =>
+      program.methods
+        .acceptSettlement(0, recipientShareBps)
+        .accountsPartial({
+          party: signer.publicKey,
+          payer: client.publicKey,
+          recipient: worker.publicKey,
+          mint: accounts.tokenMint,
+          trustPay: trustPayPubkey,
+          vault: vaultPubkey,
+          payerTokenAccount: accounts.clientTokenAccount,
+          recipientTokenAccount: accounts.workerTokenAccount,
+          treasury: accounts.treasury,
+          treasuryTokenAccount: accounts.treasuryTokenAccount,
+          globalState: accounts.globalState,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          dispute: await disputeOf(trustPayPubkey),
+        })
+        .signers([signer])
+        .rpc();
+
+    const respond = async (signer: anchor.web3.Keypair, response: string) =>
+      program.methods
+        .respondToDispute(0, response)
+        .accountsPartial({ responder: signer.publicKey, trustPay: trustPayPubkey, dispute: await disputeOf(trustPayPubkey) })
+        .signers([signer])
+        .rpc();
+
+    before("Create and dispute a contract", async () => {
+      const seed = getRandomBigNumber();
+      [trustPayPubkey] = PublicKey.findProgramAddressSync(
+        [Buffer.from("trust-pay"), client.publicKey.toBuffer(), seed.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      vaultPubkey = getAssociatedTokenAddressSync(accounts.tokenMint, trustPayPubkey, true, TOKEN_PROGRAM);
+
+      await program.methods
+        .createContract(
+          seed, ROLE_PAYER, client.publicKey, accounts.worker,
+          CONTRACT_TYPE_ONE_TIME, "Negotiable", "Test terms",
+          new BN(1_000_000), [], new BN(7 * 24 * 60 * 60), null
+        )
+        .accountsPartial({
+          creator: client.publicKey, mint: accounts.tokenMint,
+          creatorTokenAccount: accounts.clientTokenAccount, trustPay: trustPayPubkey,
+          vault: vaultPubkey, treasury: accounts.treasury,
+          globalState: accounts.globalState, systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([client])
+        .rpc();
+
+      await program.methods
+        .markMilestoneComplete(0)
+        .accountsPartial({ recipient: worker.publicKey, trustPay: trustPayPubkey })
+        .signers([worker])
+        .rpc();
+
+      await program.methods
+        .disputeContract(0, "Delivery was two weeks late")
+        .accountsPartial({
+          disputer: client.publicKey,
+          mint: accounts.tokenMint,
+          disputerTokenAccount: accounts.clientTokenAccount,
+          trustPay: trustPayPubkey,
+          vault: vaultPubkey,
+          globalState: accounts.globalState,
+          dispute: await nextDispute(),
+        })
+        .signers([client])
+        .rpc();
+    });
+
+    describe("Happy Path", () => {
+      it("Records the counterparty's response", async () => {
+        await respond(worker, "The client changed the scope twice during the project");
+
+        const record = await program.account.dispute.fetch(await disputeOf(trustPayPubkey));
+        assert.equal(record.response, "The client changed the scope twice during the project");
+      });
+
+      it("Settles on an accepted counter-offer without a resolver", async () => {
+        await offer(client, 5_000);
+        // The worker counters; the new offer replaces the client's
+        await offer(worker, 8_000);
+
+        const contract = await program.account.trustPay.fetch(trustPayPubkey);
+        assert.equal(contract.milestones[0].settlementOffer, 8_000);
+        assert.equal(contract.milestones[0].settlementOfferedBy, ROLE_RECIPIENT);
+        const expectedFee = contract.milestones[0].fee.muln(8_000).divn(10_000);
+
+        const workerBefore = await connection.getTokenAccountBalance(accounts.workerTokenAccount);
+        const treasuryBefore = await connection.getTokenAccountBalance(accounts.treasuryTokenAccount);
+        const dispute = await disputeOf(trustPayPubkey);
+
+        await accept(client, 8_000);
+
+        const workerAfter = await connection.getTokenAccountBalance(accounts.workerTokenAccount);
+        const treasuryAfter = await connection.getTokenAccountBalance(accounts.treasuryTokenAccount);
+        assert.equal(
+          new BN(workerAfter.value.amount).sub(new BN(workerBefore.value.amount)).toString(),
+          "800000"
+        );
+        assert.equal(
+          new BN(treasuryAfter.value.amount).sub(new BN(treasuryBefore.value.amount)).toString(),
+          expectedFee.toString()
+        );
+
+        // The only milestone is settled, so the contract is closed
+        const closed = await connection.getAccountInfo(trustPayPubkey);
+        assert.isNull(closed);
+
+        const record = await program.account.dispute.fetch(dispute);
+        assert.equal(record.status, DISPUTE_STATUS_SETTLED);
+        assert.equal(record.outcome, 8_000);
+      });
+    });
+
+    describe("Error Cases", () => {
+      let disputedPubkey: PublicKey;
+
+      before("Dispute a second contract", async () => {
+        const seed = getRandomBigNumber();
+        [disputedPubkey] = PublicKey.findProgramAddressSync(
+          [Buffer.from("trust-pay"), client.publicKey.toBuffer(), seed.toArrayLike(Buffer, "le", 8)],
+          program.programId
+        );
+        const vault = getAssociatedTokenAddressSync(accounts.tokenMint, disputedPubkey, true, TOKEN_PROGRAM);
+
+        await program.methods
+          .createContract(
+            seed, ROLE_PAYER, client.publicKey, accounts.worker,
+            CONTRACT_TYPE_ONE_TIME, "Negotiable", "Test terms",
+            new BN(1_000_000), [], new BN(7 * 24 * 60 * 60), null
+          )
+          .accountsPartial({
+            creator: client.publicKey, mint: accounts.tokenMint,
+            creatorTokenAccount: accounts.clientTokenAccount, trustPay: disputedPubkey,
+            vault, treasury: accounts.treasury,
+            globalState: accounts.globalState, systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          })
+          .signers([client])
+          .rpc();
+
+        await program.methods
+          .markMilestoneComplete(0)
+          .accountsPartial({ recipient: worker.publicKey, trustPay: disputedPubkey })
+          .signers([worker])
+          .rpc();
+
+        await program.methods
+          .disputeContract(0, "Delivery was two weeks late")
+          .accountsPartial({
+            disputer: client.publicKey,
+            mint: accounts.tokenMint,
+            disputerTokenAccount: accounts.clientTokenAccount,
+            trustPay: disputedPubkey,
+            vault,
+            globalState: accounts.globalState,
+            dispute: await nextDispute(),
+          })
+          .signers([client])
+          .rpc();
+
+        trustPayPubkey = disputedPubkey;
+        vaultPubkey = vault;
+      });
+
+      it("Fails when the disputer responds to their own dispute", async () => {
+        try {
+          await respond(client, "Responding to my own dispute");
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.include(err.toString(), "UnauthorizedResponder");
+        }
+      });
+
+      it("Fails to accept without an offer, or one's own offer", async () => {
+        try {
+          await accept(worker, 5_000);
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.include(err.toString(), "NoSettlementOffer");
+        }
+
+        await offer(client, 3_000);
+        try {
+          await accept(client, 3_000);
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.include(err.toString(), "UnauthorizedSettlement");
+        }
+      });
+
+      it("Fails to accept an offer that has changed", async () => {
+        await offer(client, 2_000);
+        try {
+          await accept(worker, 3_000);
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.include(err.toString(), "SettlementOfferChanged");
+        }
+
+        const contract = await program.account.trustPay.fetch(disputedPubkey);
+        assert.equal(contract.milestones[0].status, MILESTONE_STATUS_DISPUTED);
+      });
+    });
+  });
+  describe("23. DISPUTE_TIMEOUT Tests", () => {
+    const MAX_RESOLUTION_TIME = 3;
+    const DEFAULT_RECIPIENT_SHARE_BPS = 5_000;
+
+    // Client pays for a one-time contract, worker delivers and client disputes
+    const openDispute = async () => {
+      const seed = getRandomBigNumber();
+      const [trustPay] = PublicKey.findProgramAddressSync(
+        [Buffer.from("trust-pay"), client.publicKey.toBuffer(), seed.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      const vault = getAssociatedTokenAddressSync(accounts.tokenMint, trustPay, true, TOKEN_PROGRAM);
+
+      await program.methods
+        .createContract(
+          seed, ROLE_PAYER, client.publicKey, accounts.worker,
+          CONTRACT_TYPE_ONE_TIME, "Abandoned Dispute", "Test terms",
+          new BN(1_000_000), [], new BN(7 * 24 * 60 * 60), null
+        )
+        .accountsPartial({
+          creator: client.publicKey, mint: accounts.tokenMint,
+          creatorTokenAccount: accounts.clientTokenAccount, trustPay,
+          vault, treasury: accounts.treasury,
+          globalState: accounts.globalState, systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([client])
+        .rpc();
+
+      await program.methods
+        .markMilestoneComplete(0)
+        .accountsPartial({ recipient: worker.publicKey, trustPay })
+        .signers([worker])
+        .rpc();
+
+      await program.methods
+        .disputeContract(0, "Work was never reviewed by the resolver")
+        .accountsPartial({
+          disputer: client.publicKey,
+          mint: accounts.tokenMint,
+          disputerTokenAccount: accounts.clientTokenAccount,
+          trustPay,
+          vault,
+          globalState: accounts.globalState,
+          dispute: await nextDispute(),
+        })
+        .signers([client])
+        .rpc();
+
+      return { trustPay, vault };
+    };
+
+    const finalizeStale = async (trustPay: PublicKey, vault: PublicKey) =>
+      program.methods
+        .finalizeStaleDispute(0)
+        .accountsPartial({
+          caller: worker.publicKey,
+          payer: client.publicKey,
+          recipient: worker.publicKey,
+          mint: accounts.tokenMint,
+          trustPay,
+          vault,
+          payerTokenAccount: accounts.clientTokenAccount,
+          recipientTokenAccount: accounts.workerTokenAccount,
+          treasury: accounts.treasury,
+          treasuryTokenAccount: accounts.treasuryTokenAccount,
+          globalState: accounts.globalState,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          dispute: await disputeOf(trustPay),
+        })
+        .signers([worker])
+        .rpc();
+
+    before("Set a maximum resolution time", async () => {
+      await program.methods
+        .updateTimeoutConfig(new BN(MAX_RESOLUTION_TIME), DEFAULT_RECIPIENT_SHARE_BPS)
+        .accountsPartial({ authority: resolver.publicKey, globalState: accounts.globalState })
+        .signers([resolver])
+        .rpc();
+
+      const globalState = await program.account.globalState.fetch(accounts.globalState);
+      assert.equal(globalState.maxResolutionTime.toNumber(), MAX_RESOLUTION_TIME);
+      assert.equal(globalState.defaultRecipientShareBps, DEFAULT_RECIPIENT_SHARE_BPS);
+    });
+
+    after("Disable dispute timeouts", async () => {
+      await program.methods
+        .updateTimeoutConfig(new BN(0), 0)
+        .accountsPartial({ authority: resolver.publicKey, globalState: accounts.globalState })
+        .signers([resolver])
+        .rpc();
+    });
+
+    describe("Happy Path", () => {
+      it("Anyone applies the default outcome once the dispute is stale", async () => {
+        const { trustPay, vault } = await openDispute();
+        await new Promise(resolve => setTimeout(resolve, (MAX_RESOLUTION_TIME + 2) * SECONDS));
+
+        const workerBefore = await connection.getTokenAccountBalance(accounts.workerTokenAccount);
+        const dispute = await disputeOf(trustPay);
+        await finalizeStale(trustPay, vault);
+
+        const workerAfter = await connection.getTokenAccountBalance(accounts.workerTokenAccount);
+        assert.equal(
+          new BN(workerAfter.value.amount).sub(new BN(workerBefore.value.amount)).toString(),
+          "500000"
+        );
+        // The only milestone is settled, so the contract is closed
+        assert.isNull(await connection.getAccountInfo(trustPay));
+
+        const record = await program.account.dispute.fetch(dispute);
+        assert.equal(record.status, DISPUTE_STATUS_TIMED_OUT);
+        assert.equal(record.outcome, DEFAULT_RECIPIENT_SHARE_BPS);
+      });
+    });
+
+    describe("Error Cases", () => {
+      it("Fails before the maximum resolution time has passed", async () => {
+        const { trustPay, vault } = await openDispute();
+        try {
+          await finalizeStale(trustPay, vault);
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.include(err.toString(), "DisputeNotStale");
+        }
+      });
+
+      it("Fails right after an appeal, even once the original dispute is stale", async () => {
+        const APPEAL_WINDOW = 10;
+        const [arbitrator] = makeKeypairs(1);
+        const sig = await connection.requestAirdrop(arbitrator.publicKey, LAMPORTS_PER_SOL);
+        await confirmTransaction(connection, sig);
+        const [arbitratorPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("arbitrator"), arbitrator.publicKey.toBuffer()],
+          program.programId
+        );
+        await program.methods
+          .registerArbitrator(arbitrator.publicKey, ["software"])
+          .accountsPartial({
+            authority: resolver.publicKey,
+            globalState: accounts.globalState,
+            arbitratorAccount: arbitratorPda,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([resolver])
+          .rpc();
+        await program.methods
+          .updateAppealConfig(new BN(APPEAL_WINDOW), 0)
+          .accountsPartial({ authority: resolver.publicKey, globalState: accounts.globalState })
+          .signers([resolver])
+          .rpc();
+
+        try {
+          const { trustPay, vault } = await openDispute();
+          await program.methods
+            .assignDispute(0)
+            .accountsPartial({
+              authority: resolver.publicKey,
+              globalState: accounts.globalState,
+              trustPay,
+              arbitratorAccount: arbitratorPda,
+              dispute: await disputeOf(trustPay),
+            })
+            .signers([resolver])
+            .rpc();
+          await program.methods
+            .resolveDispute(0, 10_000, RESOLUTION_SPLIT, "Work meets the specification")
+            .accountsPartial({
+              resolver: arbitrator.publicKey,
+              payer: client.publicKey,
+              recipient: worker.publicKey,
+              mint: accounts.tokenMint,
+              trustPay,
+              vault,
+              payerTokenAccount: accounts.clientTokenAccount,
+              recipientTokenAccount: accounts.workerTokenAccount,
+              resolverTokenAccount: getAssociatedTokenAddressSync(
+                accounts.tokenMint, arbitrator.publicKey, false, TOKEN_PROGRAM
+              ),
+              treasury: accounts.treasury,
+              treasuryTokenAccount: accounts.treasuryTokenAccount,
+              globalState: accounts.globalState,
+              arbitrator: arbitratorPda,
+              systemProgram: anchor.web3.SystemProgram.programId,
+              tokenProgram: TOKEN_PROGRAM,
+              associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+              dispute: await disputeOf(trustPay),
+            })
+            .signers([arbitrator])
+            .rpc();
+
+          // Appeal late in the window, after the dispute itself would have timed out
+          await new Promise(resolve => setTimeout(resolve, (MAX_RESOLUTION_TIME + 2) * SECONDS));
+          await program.methods
+            .appealRuling(0)
+            .accountsPartial({
+              appellant: client.publicKey,
+              mint: accounts.tokenMint,
+              appellantTokenAccount: accounts.clientTokenAccount,
+              trustPay,
+              vault,
+              globalState: accounts.globalState,
+              dispute: await disputeOf(trustPay),
+            })
+            .signers([client])
+            .rpc();
+
+          const appealed = await program.account.trustPay.fetch(trustPay);
+          assert.isNotNull(appealed.milestones[0].escalatedAt);
+
+          try {
+            await finalizeStale(trustPay, vault);
+            assert.fail("Should have failed");
+          } catch (err) {
+            assert.include(err.toString(), "DisputeNotStale");
+          }
+        } finally {
+          await program.methods
+            .updateAppealConfig(new BN(0), 0)
+            .accountsPartial({ authority: resolver.publicKey, globalState: accounts.globalState })
+            .signers([resolver])
+            .rpc();
+        }
+      });
+
+      it("Fails to set a default outcome above 10000 bps", async () => {
+        try {
+          await program.methods
+            .updateTimeoutConfig(new BN(MAX_RESOLUTION_TIME), 10_001)
+            .accountsPartial({ authority: resolver.publicKey, globalState: accounts.globalState })
+            .signers([resolver])
+            .rpc();
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.include(err.toString(), "InvalidTimeoutConfig");
+        }
+      });
+
+      it("Fails to set the timeout config by non-authority", async () => {
+        try {
+          await program.methods
+            .updateTimeoutConfig(new BN(1), 0)
+            .accountsPartial({ authority: client.publicKey, globalState: accounts.globalState })
+            .signers([client])
+            .rpc();
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.notInclude(err.toString(), "Should have failed");
+        }
+      });
+    });
+  });
+  describe("24. DISPUTE_WITHDRAWAL Tests", () => {
+    let trustPayPubkey: PublicKey;
+    let vaultPubkey: PublicKey;
+    let disputePubkey: PublicKey;
+
+    const withdraw = (signer: anchor.web3.Keypair) =>
+      program.methods
+        .withdrawDispute(0)
+        .accountsPartial({
+          disputer: signer.publicKey,
+          payer: client.publicKey,
+          recipient: worker.publicKey,
+          mint: accounts.tokenMint,
+          trustPay: trustPayPubkey,
+          dispute: disputePubkey,
+          vault: vaultPubkey,
+          payerTokenAccount: accounts.clientTokenAccount,
+          recipientTokenAccount: accounts.workerTokenAccount,
+          globalState: accounts.globalState,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([signer])
+        .rpc();
+
+    before("Create and dispute a contract", async () => {
+      const seed = getRandomBigNumber();
+      [trustPayPubkey] = PublicKey.findProgramAddressSync(
+        [Buffer.from("trust-pay"), client.publicKey.toBuffer(), seed.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      vaultPubkey = getAssociatedTokenAddressSync(accounts.tokenMint, trustPayPubkey, true, TOKEN_PROGRAM);
+
+      await program.methods
+        .createContract(
+          seed, ROLE_PAYER, client.publicKey, accounts.worker,
+          CONTRACT_TYPE_ONE_TIME, "Withdrawable", "Test terms",
+          new BN(1_000_000), [], new BN(7 * 24 * 60 * 60), null
+        )
+        .accountsPartial({
+          creator: client.publicKey, mint: accounts.tokenMint,
+          creatorTokenAccount: accounts.clientTokenAccount, trustPay: trustPayPubkey,
+          vault: vaultPubkey, treasury: accounts.treasury,
+          globalState: accounts.globalState, systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([client])
+        .rpc();
+
+      await program.methods
+        .markMilestoneComplete(0)
+        .accountsPartial({ recipient: worker.publicKey, trustPay: trustPayPubkey })
+        .signers([worker])
+        .rpc();
+
+      disputePubkey = await nextDispute();
+      await program.methods
+        .disputeContract(0, "Delivery was missing the final report")
+        .accountsPartial({
+          disputer: client.publicKey,
+          mint: accounts.tokenMint,
+          disputerTokenAccount: accounts.clientTokenAccount,
+          trustPay: trustPayPubkey,
+          vault: vaultPubkey,
+          globalState: accounts.globalState,
+          dispute: disputePubkey,
+        })
+        .signers([client])
+        .rpc();
+    });
+
+    describe("Error Cases", () => {
+      it("Fails when someone other than the disputer withdraws", async () => {
+        try {
+          await withdraw(worker);
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.include(err.toString(), "UnauthorizedWithdrawal");
+        }
+      });
+    });
+
+    describe("Happy Path", () => {
+      it("Restores the milestone and contract and refunds the bond", async () => {
+        const before = await program.account.trustPay.fetch(trustPayPubkey);
+        const bond = before.milestones[0].disputeBond;
+        const { totalDisputesWithdrawn } = await program.account.globalState.fetch(accounts.globalState);
+        const clientBefore = await connection.getTokenAccountBalance(accounts.clientTokenAccount);
+
+        await withdraw(client);
+
+        const contract = await program.account.trustPay.fetch(trustPayPubkey);
+        assert.equal(contract.contractStatus, CONTRACT_STATUS_IN_PROGRESS);
+        assert.equal(contract.milestones[0].status, MILESTONE_STATUS_COMPLETED_BY_SP);
+        assert.isNull(contract.milestones[0].disputer);
+        assert.equal(contract.milestones[0].disputeBond.toNumber(), 0);
+
+        const clientAfter = await connection.getTokenAccountBalance(accounts.clientTokenAccount);
+        assert.equal(
+          new BN(clientAfter.value.amount).sub(new BN(clientBefore.value.amount)).toString(),
+          bond.toString()
+        );
+
+        const record = await program.account.dispute.fetch(disputePubkey);
+        assert.equal(record.status, DISPUTE_STATUS_WITHDRAWN);
+        assert.equal(record.resolvedBy.toString(), client.publicKey.toString());
+        assert.isNotNull(record.closedAt);
+
+        const globalState = await program.account.globalState.fetch(accounts.globalState);
+        assert.equal(
+          globalState.totalDisputesWithdrawn.toString(),
+          totalDisputesWithdrawn.addn(1).toString()
+        );
+      });
+
+      it("Fails to withdraw a dispute that is already withdrawn", async () => {
+        try {
+          await withdraw(client);
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.include(err.toString(), "ContractNotDisputed");
+        }
+      });
+    });
+  });
+  describe("25. CONTRACT_DISPUTE Tests", () => {
+    // Client pays for a two-milestone contract that has not started
+    const createMilestoneContract = async () => {
+      const seed = getRandomBigNumber();
+      const [trustPay] = PublicKey.findProgramAddressSync(
+        [Buffer.from("trust-pay"), client.publicKey.toBuffer(), seed.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      const vault = getAssociatedTokenAddressSync(accounts.tokenMint, trustPay, true, TOKEN_PROGRAM);
+
+      await program.methods
+        .createContract(
+          seed, ROLE_PAYER, client.publicKey, accounts.worker,
+          CONTRACT_TYPE_MILESTONE, "Blocked project", "Test terms", new BN(2_000_000),
+          [
+            { description: "Research", amount: new BN(1_000_000) },
+            { description: "Build", amount: new BN(1_000_000) },
+          ],
+          new BN(7 * 24 * 60 * 60), null
+        )
+        .accountsPartial({
+          creator: client.publicKey, mint: accounts.tokenMint,
+          creatorTokenAccount: accounts.clientTokenAccount, trustPay,
+          vault, treasury: accounts.treasury,
+          globalState: accounts.globalState, systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([client])
+        .rpc();
+
+      return { trustPay, vault };
+    };
+
+    const dispute = async (trustPay: PublicKey, vault: PublicKey, milestoneIndex: number) => {
+      const disputeRecord = await nextDispute();
+      await program.methods
+        .disputeContract(milestoneIndex, "Client has not provided access to the servers")
+        .accountsPartial({
+          disputer: worker.publicKey,
+          mint: accounts.tokenMint,
+          disputerTokenAccount: accounts.workerTokenAccount,
+          trustPay,
+          vault,
+          globalState: accounts.globalState,
+          dispute: disputeRecord,
+        })
+        .signers([worker])
+        .rpc();
+      return disputeRecord;
+    };
+
+    const resolve = (
+      trustPay: PublicKey,
+      vault: PublicKey,
+      disputeRecord: PublicKey,
+      milestoneIndex: number,
+      recipientShareBps: number,
+      resolution: number
+    ) =>
+      program.methods
+        .resolveDispute(milestoneIndex, recipientShareBps, resolution, "Client blocked the project")
+        .accountsPartial({
+          resolver: resolver.publicKey,
+          payer: client.publicKey,
+          recipient: worker.publicKey,
+          mint: accounts.tokenMint,
+          trustPay,
+          dispute: disputeRecord,
+          vault,
+          payerTokenAccount: accounts.clientTokenAccount,
+          recipientTokenAccount: accounts.workerTokenAccount,
+          treasury: accounts.treasury,
+          treasuryTokenAccount: accounts.treasuryTokenAccount,
+          globalState: accounts.globalState,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          arbitrator: null,
+        })
+        .signers([resolver])
+        .rpc();
+
+    const settlementAccounts = async (trustPay: PublicKey, vault: PublicKey) => ({
+      payer: client.publicKey,
+      recipient: worker.publicKey,
+      mint: accounts.tokenMint,
+      trustPay,
+      vault,
+      payerTokenAccount: accounts.clientTokenAccount,
+      recipientTokenAccount: accounts.workerTokenAccount,
+      treasury: accounts.treasury,
+      treasuryTokenAccount: accounts.treasuryTokenAccount,
+      globalState: accounts.globalState,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM,
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+      dispute: await disputeOf(trustPay, CONTRACT_DISPUTE_INDEX),
+    });
+
+    describe("Happy Path", () => {
+      it("Recipient disputes a pending milestone and the contract is terminated with compensation", async () => {
+        const { trustPay, vault } = await createMilestoneContract();
+        const disputeRecord = await dispute(trustPay, vault, 0);
+
+        const contract = await program.account.trustPay.fetch(trustPay);
+        assert.equal(contract.milestones[0].status, MILESTONE_STATUS_DISPUTED);
+        assert.equal(contract.milestones[0].statusBeforeDispute, MILESTONE_STATUS_PENDING);
+
+        const workerBefore = await connection.getTokenAccountBalance(accounts.workerTokenAccount);
+
+        // 30% of both milestones goes to the worker, the rest back to the client
+        await resolve(trustPay, vault, disputeRecord, 0, 3_000, RESOLUTION_TERMINATE);
+
+        const workerAfter = await connection.getTokenAccountBalance(accounts.workerTokenAccount);
+        assert.equal(
+          new BN(workerAfter.value.amount).sub(new BN(workerBefore.value.amount)).toString(),
+          "600000"
+        );
+
+        const closed = await connection.getAccountInfo(trustPay);
+        assert.isNull(closed);
+
+        const record = await program.account.dispute.fetch(disputeRecord);
+        assert.equal(record.status, DISPUTE_STATUS_RESOLVED);
+        assert.equal(record.outcome, 3_000);
+      });
+
+      it("Disputes the whole contract and dismisses the dispute so work continues", async () => {
+        const { trustPay, vault } = await createMilestoneContract();
+        const disputeRecord = await dispute(trustPay, vault, CONTRACT_DISPUTE_INDEX);
+
+        let contract = await program.account.trustPay.fetch(trustPay);
+        let record = await program.account.dispute.fetch(disputeRecord);
+        assert.equal(contract.contractStatus, CONTRACT_STATUS_DISPUTED);
+        assert.equal(contract.contractDispute.status, MILESTONE_STATUS_DISPUTED);
+        assert.equal(contract.contractDispute.disputeId.toString(), record.disputeId.toString());
+        assert.equal(contract.milestones[0].status, MILESTONE_STATUS_PENDING);
+        assert.equal(record.milestoneIndex, CONTRACT_DISPUTE_INDEX);
+        assert.equal(record.amount.toNumber(), 2_000_000);
+
+        await resolve(trustPay, vault, disputeRecord, CONTRACT_DISPUTE_INDEX, 0, RESOLUTION_CONTINUE);
+
+        contract = await program.account.trustPay.fetch(trustPay);
+        assert.equal(contract.contractStatus, CONTRACT_STATUS_IN_PROGRESS);
+        assert.equal(contract.contractDispute.status, MILESTONE_STATUS_RESOLVED);
+
+        record = await program.account.dispute.fetch(disputeRecord);
+        assert.equal(record.status, DISPUTE_STATUS_RESOLVED);
+        assert.isNull(record.outcome);
+      });
+
+      it("Matches the bond on a contract dispute and settles it by terminating the contract", async () => {
+        await program.methods
+          .updateDisputeConfig(500, 0)
+          .accountsPartial({ authority: resolver.publicKey, globalState: accounts.globalState })
+          .signers([resolver])
+          .rpc();
+
+        try {
+          const { trustPay, vault } = await createMilestoneContract();
+          const disputeRecord = await dispute(trustPay, vault, CONTRACT_DISPUTE_INDEX);
+
+          await program.methods
+            .matchDisputeBond(CONTRACT_DISPUTE_INDEX)
+            .accountsPartial({
+              counterparty: client.publicKey,
+              mint: accounts.tokenMint,
+              counterpartyTokenAccount: accounts.clientTokenAccount,
+              trustPay,
+              vault,
+              systemProgram: anchor.web3.SystemProgram.programId,
+              tokenProgram: TOKEN_PROGRAM,
+              associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+            })
+            .signers([client])
+            .rpc();
+
+          const contract = await program.account.trustPay.fetch(trustPay);
+          assert.equal(contract.contractDispute.disputeBond.toNumber(), 100_000);
+          assert.equal(contract.contractDispute.counterBond.toNumber(), 100_000);
+
+          await program.methods
+            .offerSettlement(CONTRACT_DISPUTE_INDEX, 4_000)
+            .accountsPartial({ party: worker.publicKey, trustPay })
+            .signers([worker])
+            .rpc();
+
+          const workerBefore = await connection.getTokenAccountBalance(accounts.workerTokenAccount);
+          await program.methods
+            .acceptSettlement(CONTRACT_DISPUTE_INDEX, 4_000)
+            .accountsPartial({ party: client.publicKey, ...(await settlementAccounts(trustPay, vault)) })
+            .signers([client])
+            .rpc();
+          const workerAfter = await connection.getTokenAccountBalance(accounts.workerTokenAccount);
+
+          // The worker's bond comes back on top of its share, less the fee on that share
+          const received = new BN(workerAfter.value.amount).sub(new BN(workerBefore.value.amount));
+          assert.isTrue(received.gt(new BN(100_000)));
+          assert.isTrue(received.lte(new BN(900_000)));
+          assert.isNull(await connection.getAccountInfo(trustPay));
+
+          const record = await program.account.dispute.fetch(disputeRecord);
+          assert.equal(record.status, DISPUTE_STATUS_SETTLED);
+          assert.equal(record.outcome, 4_000);
+        } finally {
+          await program.methods
+            .updateDisputeConfig(0, 0)
+            .accountsPartial({ authority: resolver.publicKey, globalState: accounts.globalState })
+            .signers([resolver])
+            .rpc();
+        }
+      });
+
+      it("Terminates a stale contract dispute with the default outcome", async () => {
+        await program.methods
+          .updateTimeoutConfig(new BN(2), 5_000)
+          .accountsPartial({ authority: resolver.publicKey, globalState: accounts.globalState })
+          .signers([resolver])
+          .rpc();
+
+        try {
+          const { trustPay, vault } = await createMilestoneContract();
+          const disputeRecord = await dispute(trustPay, vault, CONTRACT_DISPUTE_INDEX);
+          await new Promise(resolve => setTimeout(resolve, 4 * SECONDS));
+
+          await program.methods
+            .finalizeStaleDispute(CONTRACT_DISPUTE_INDEX)
+            .accountsPartial({ caller: worker.publicKey, ...(await settlementAccounts(trustPay, vault)) })
+            .signers([worker])
+            .rpc();
+
+          assert.isNull(await connection.getAccountInfo(trustPay));
+          const record = await program.account.dispute.fetch(disputeRecord);
+          assert.equal(record.status, DISPUTE_STATUS_TIMED_OUT);
+          assert.equal(record.outcome, 5_000);
+        } finally {
+          await program.methods
+            .updateTimeoutConfig(new BN(0), 0)
+            .accountsPartial({ authority: resolver.publicKey, globalState: accounts.globalState })
+            .signers([resolver])
+            .rpc();
+        }
+      });
+      it("Holds a contract termination for the appeal window before settling it", async () => {
+        await program.methods
+          .updateAppealConfig(new BN(2), 0)
+          .accountsPartial({ authority: resolver.publicKey, globalState: accounts.globalState })
+          .signers([resolver])
+          .rpc();
+
+        try {
+          const { trustPay, vault } = await createMilestoneContract();
+          const disputeRecord = await dispute(trustPay, vault, CONTRACT_DISPUTE_INDEX);
+          await resolve(trustPay, vault, disputeRecord, CONTRACT_DISPUTE_INDEX, 3_000, RESOLUTION_TERMINATE);
+
+          // The ruling is recorded, the contract stays open until the window passes
+          const contract = await program.account.trustPay.fetch(trustPay);
+          assert.equal(contract.contractStatus, CONTRACT_STATUS_DISPUTED);
+          assert.equal(contract.contractDispute.ruling, 3_000);
+          assert.equal(contract.contractDispute.rulingResolution, RESOLUTION_TERMINATE);
+
+          await new Promise(resolve => setTimeout(resolve, 3 * SECONDS));
+          await program.methods
+            .finalizeRuling(CONTRACT_DISPUTE_INDEX)
+            .accountsPartial({
+              caller: worker.publicKey,
+              resolverTokenAccount: null,
+              arbitrator: null,
+              panel: null,
+              ...(await settlementAccounts(trustPay, vault)),
+            })
+            .signers([worker])
+            .rpc();
+
+          assert.isNull(await connection.getAccountInfo(trustPay));
+          const record = await program.account.dispute.fetch(disputeRecord);
+          assert.equal(record.status, DISPUTE_STATUS_RESOLVED);
+          assert.equal(record.outcome, 3_000);
+        } finally {
+          await program.methods
+            .updateAppealConfig(new BN(0), 0)
+            .accountsPartial({ authority: resolver.publicKey, globalState: accounts.globalState })
+            .signers([resolver])
+            .rpc();
+        }
+      });
+    });
+
+    describe("Error Cases", () => {
+      it("Fails to split a contract dispute or continue a milestone dispute", async () => {
+        const contractDispute = await createMilestoneContract();
+        const contractRecord = await dispute(contractDispute.trustPay, contractDispute.vault, CONTRACT_DISPUTE_INDEX);
+        try {
+          await resolve(
+            contractDispute.trustPay, contractDispute.vault, contractRecord,
+            CONTRACT_DISPUTE_INDEX, 5_000, RESOLUTION_SPLIT
+          );
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.include(err.toString(), "InvalidResolution");
+        }
+
+        const milestoneDispute = await createMilestoneContract();
+        const milestoneRecord = await dispute(milestoneDispute.trustPay, milestoneDispute.vault, 1);
+        try {
+          await resolve(milestoneDispute.trustPay, milestoneDispute.vault, milestoneRecord, 1, 5_000, RESOLUTION_CONTINUE);
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.include(err.toString(), "InvalidResolution");
+        }
+      });
+
+      it("Fails to resolve a contract dispute or a termination above the panel threshold directly", async () => {
+        await program.methods
+          .updatePanelConfig(new BN(1_500_000), 3, 2, new BN(3 * 24 * 60 * 60))
+          .accountsPartial({ authority: resolver.publicKey, globalState: accounts.globalState })
+          .signers([resolver])
+          .rpc();
+
+        try {
+          // The whole contract is worth 2,000,000, so its dispute goes to a panel
+          const contractDispute = await createMilestoneContract();
+          const contractRecord = await dispute(contractDispute.trustPay, contractDispute.vault, CONTRACT_DISPUTE_INDEX);
+          const contract = await program.account.trustPay.fetch(contractDispute.trustPay);
+          assert.isTrue(contract.contractDispute.panelRequired);
+          try {
+            await resolve(
+              contractDispute.trustPay, contractDispute.vault, contractRecord,
+              CONTRACT_DISPUTE_INDEX, 3_000, RESOLUTION_TERMINATE
+            );
+            assert.fail("Should have failed");
+          } catch (err) {
+            assert.include(err.toString(), "PanelRequired");
+          }
+
+          // A single milestone is below the threshold, but terminating settles both
+          const milestoneDispute = await createMilestoneContract();
+          const milestoneRecord = await dispute(milestoneDispute.trustPay, milestoneDispute.vault, 0);
+          try {
+            await resolve(milestoneDispute.trustPay, milestoneDispute.vault, milestoneRecord, 0, 3_000, RESOLUTION_TERMINATE);
+            assert.fail("Should have failed");
+          } catch (err) {
+            assert.include(err.toString(), "PanelRequired");
+          }
+
+          // Splitting the milestone alone stays with the resolver
+          await resolve(milestoneDispute.trustPay, milestoneDispute.vault, milestoneRecord, 0, 3_000, RESOLUTION_SPLIT);
+          const record = await program.account.dispute.fetch(milestoneRecord);
+          assert.equal(record.status, DISPUTE_STATUS_RESOLVED);
+        } finally {
+          await program.methods
+            .updatePanelConfig(new BN(0), 3, 2, new BN(3 * 24 * 60 * 60))
+            .accountsPartial({ authority: resolver.publicKey, globalState: accounts.globalState })
+            .signers([resolver])
+            .rpc();
+        }
+      });
+
+      it("Fails when a non-admin resolves a contract dispute", async () => {
+        const { trustPay, vault } = await createMilestoneContract();
+        const disputeRecord = await dispute(trustPay, vault, CONTRACT_DISPUTE_INDEX);
+        try {
+          await program.methods
+            .resolveDispute(CONTRACT_DISPUTE_INDEX, 10_000, RESOLUTION_TERMINATE, "Pay me everything")
+            .accountsPartial({
+              resolver: worker.publicKey,
+              payer: client.publicKey,
+              recipient: worker.publicKey,
+              mint: accounts.tokenMint,
+              trustPay,
+              dispute: disputeRecord,
+              vault,
+              payerTokenAccount: accounts.clientTokenAccount,
+              recipientTokenAccount: accounts.workerTokenAccount,
+              treasury: accounts.treasury,
+              treasuryTokenAccount: accounts.treasuryTokenAccount,
+              globalState: accounts.globalState,
+              systemProgram: anchor.web3.SystemProgram.programId,
+              tokenProgram: TOKEN_PROGRAM,
+              associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+              arbitrator: null,
+            })
+            .signers([worker])
+            .rpc();
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.include(err.toString(), "UnauthorizedResolver");
+        }
+      });
+    });
+  });
+  describe("26. CONCURRENT_DISPUTES Tests", () => {
+    let trustPayPubkey: PublicKey;
+    let vaultPubkey: PublicKey;
+    let firstDispute: PublicKey;
+    let secondDispute: PublicKey;
+
+    const dispute = async (milestoneIndex: number) => {
+      const disputeRecord = await nextDispute();
+      await program.methods
+        .disputeContract(milestoneIndex, "Deliverable does not match the brief")
+        .accountsPartial({
+          disputer: client.publicKey,
+          mint: accounts.tokenMint,
+          disputerTokenAccount: accounts.clientTokenAccount,
+          trustPay: trustPayPubkey,
+          vault: vaultPubkey,
+          globalState: accounts.globalState,
+          dispute: disputeRecord,
+        })
+        .signers([client])
+        .rpc();
+      return disputeRecord;
+    };
+
+    const resolve = (disputeRecord: PublicKey, milestoneIndex: number, recipientShareBps: number, resolution: number) =>
+      program.methods
+        .resolveDispute(milestoneIndex, recipientShareBps, resolution, "Ruling on the deliverable")
+        .accountsPartial({
+          resolver: resolver.publicKey,
+          payer: client.publicKey,
+          recipient: worker.publicKey,
+          mint: accounts.tokenMint,
+          trustPay: trustPayPubkey,
+          dispute: disputeRecord,
+          vault: vaultPubkey,
+          payerTokenAccount: accounts.clientTokenAccount,
+          recipientTokenAccount: accounts.workerTokenAccount,
+          treasury: accounts.treasury,
+          treasuryTokenAccount: accounts.treasuryTokenAccount,
+          globalState: accounts.globalState,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          arbitrator: null,
+        })
+        .signers([resolver])
+        .rpc();
+
+    const markComplete = (milestoneIndex: number) =>
+      program.methods
+        .markMilestoneComplete(milestoneIndex)
+        .accountsPartial({ recipient: worker.publicKey, trustPay: trustPayPubkey })
+        .signers([worker])
+        .rpc();
+
+    before("Create a contract and dispute two of its milestones", async () => {
+      const seed = getRandomBigNumber();
+      [trustPayPubkey] = PublicKey.findProgramAddressSync(
+        [Buffer.from("trust-pay"), client.publicKey.toBuffer(), seed.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      vaultPubkey = getAssociatedTokenAddressSync(accounts.tokenMint, trustPayPubkey, true, TOKEN_PROGRAM);
+
+      await program.methods
+        .createContract(
+          seed, ROLE_PAYER, client.publicKey, accounts.worker,
+          CONTRACT_TYPE_MILESTONE, "Three deliverables", "Test terms", new BN(3_000_000),
+          [
+            { description: "Logo", amount: new BN(1_000_000) },
+            { description: "Website", amount: new BN(1_000_000) },
+            { description: "Brochure", amount: new BN(1_000_000) },
+          ],
+          new BN(7 * 24 * 60 * 60), null
+        )
+        .accountsPartial({
+          creator: client.publicKey, mint: accounts.tokenMint,
+          creatorTokenAccount: accounts.clientTokenAccount, trustPay: trustPayPubkey,
+          vault: vaultPubkey, treasury: accounts.treasury,
+          globalState: accounts.globalState, systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([client])
+        .rpc();
+
+      await markComplete(0);
+      await markComplete(1);
+      firstDispute = await dispute(0);
+      secondDispute = await dispute(1);
+    });
+
+    describe("Happy Path", () => {
+      it("Keeps the contract in progress with two open disputes", async () => {
+        const contract = await program.account.trustPay.fetch(trustPayPubkey);
+        assert.equal(contract.contractStatus, CONTRACT_STATUS_IN_PROGRESS);
+        assert.equal(contract.milestones[0].status, MILESTONE_STATUS_DISPUTED);
+        assert.equal(contract.milestones[1].status, MILESTONE_STATUS_DISPUTED);
+        assert.notEqual(firstDispute.toString(), secondDispute.toString());
+      });
+
+      it("Completes and pays an unaffected milestone during the disputes", async () => {
+        await markComplete(2);
+
+        const workerBefore = await connection.getTokenAccountBalance(accounts.workerTokenAccount);
+        await program.methods
+          .approveMilestonePayment(2)
+          .accountsPartial({
+            payer: client.publicKey,
+            recipient: worker.publicKey,
+            mint: accounts.tokenMint,
+            trustPay: trustPayPubkey,
+            vault: vaultPubkey,
+            payerTokenAccount: accounts.clientTokenAccount,
+            recipientTokenAccount: accounts.workerTokenAccount,
+            treasury: accounts.treasury,
+            treasuryTokenAccount: accounts.treasuryTokenAccount,
+            globalState: accounts.globalState,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM,
+            associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          })
+          .signers([client])
+          .rpc();
+
+        const workerAfter = await connection.getTokenAccountBalance(accounts.workerTokenAccount);
+        assert.equal(
+          new BN(workerAfter.value.amount).sub(new BN(workerBefore.value.amount)).toString(),
+          "1000000"
+        );
+
+        const contract = await program.account.trustPay.fetch(trustPayPubkey);
+        assert.equal(contract.milestones[2].status, MILESTONE_STATUS_APPROVED_BY_PAYER);
+        assert.equal(contract.milestones[0].status, MILESTONE_STATUS_DISPUTED);
+      });
+    });
+
+    describe("Error Cases", () => {
+      it("Fails to dispute the whole contract while milestone disputes are open", async () => {
+        try {
+          await dispute(CONTRACT_DISPUTE_INDEX);
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.include(err.toString(), "MilestoneDisputesOpen");
+        }
+      });
+
+      it("Fails to terminate while another milestone is disputed", async () => {
+        try {
+          await resolve(firstDispute, 0, 5_000, RESOLUTION_TERMINATE);
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.include(err.toString(), "MilestoneDisputesOpen");
+        }
+      });
+    });
+
+    describe("Resolution", () => {
+      it("Resolves each dispute independently and closes the contract after the last", async () => {
+        await resolve(firstDispute, 0, 10_000, RESOLUTION_SPLIT);
+
+        const contract = await program.account.trustPay.fetch(trustPayPubkey);
+        assert.equal(contract.contractStatus, CONTRACT_STATUS_IN_PROGRESS);
+        assert.equal(contract.milestones[0].status, MILESTONE_STATUS_APPROVED_BY_PAYER);
+        assert.equal(contract.milestones[1].status, MILESTONE_STATUS_DISPUTED);
+
+        await resolve(secondDispute, 1, 0, RESOLUTION_SPLIT);
+
+        const closed = await connection.getAccountInfo(trustPayPubkey);
+        assert.isNull(closed);
+      });
+    });
+  });
+  describe("27. REPUTATION Tests", () => {
+    const reputationPda = (wallet: PublicKey) =>
+      PublicKey.findProgramAddressSync([Buffer.from("reputation"), wallet.toBuffer()], program.programId)[0];
+
+    const createOneTime = async (title: string) => {
+      const seed = getRandomBigNumber();
+      const [trustPayPubkey] = PublicKey.findProgramAddressSync(
+        [Buffer.from("trust-pay"), client.publicKey.toBuffer(), seed.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      const vaultPubkey = getAssociatedTokenAddressSync(accounts.tokenMint, trustPayPubkey, true, TOKEN_PROGRAM);
+
+      await program.methods
+        .createContract(
+          seed, ROLE_PAYER, client.publicKey, accounts.worker,
+          CONTRACT_TYPE_ONE_TIME, title, "Test terms", new BN(1_000_000),
+          [], new BN(7 * 24 * 60 * 60), null
+        )
+        .accountsPartial({
+          creator: client.publicKey, mint: accounts.tokenMint,
+          creatorTokenAccount: accounts.clientTokenAccount, trustPay: trustPayPubkey,
+          vault: vaultPubkey, treasury: accounts.treasury,
+          globalState: accounts.globalState, systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([client])
+        .rpc();
+
+      await program.methods
+        .markMilestoneComplete(0)
+        .accountsPartial({ recipient: worker.publicKey, trustPay: trustPayPubkey })
+        .signers([worker])
+        .rpc();
+
+      return { trustPayPubkey, vaultPubkey };
+    };
+
+    const approve = (trustPayPubkey: PublicKey, vaultPubkey: PublicKey, payerReputation?: PublicKey) =>
+      program.methods
+        .approveMilestonePayment(0)
+        .accountsPartial({
+          payer: client.publicKey,
+          recipient: worker.publicKey,
+          mint: accounts.tokenMint,
+          trustPay: trustPayPubkey,
+          vault: vaultPubkey,
+          payerTokenAccount: accounts.clientTokenAccount,
+          recipientTokenAccount: accounts.workerTokenAccount,
+          treasury: accounts.treasury,
+          treasuryTokenAccount: accounts.treasuryTokenAccount,
+          globalState: accounts.globalState,
+          payerReputation: payerReputation ?? reputationPda(client.publicKey),
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([client])
+        .rpc();
+
+    describe("Happy Path", () => {
+      it("Opens a reputation profile for both parties", async () => {
+        const clientReputation = await program.account.reputation.fetch(reputationPda(client.publicKey));
+        const workerReputation = await program.account.reputation.fetch(reputationPda(worker.publicKey));
+        assert.equal(clientReputation.wallet.toString(), client.publicKey.toString());
+        assert.equal(workerReputation.wallet.toString(), worker.publicKey.toString());
+      });
+
+      it("Records approvals, completions and volume when a contract completes", async () => {
+        const { trustPayPubkey, vaultPubkey } = await createOneTime("Reputation contract");
+        const clientBefore = await program.account.reputation.fetch(reputationPda(client.publicKey));
+        const workerBefore = await program.account.reputation.fetch(reputationPda(worker.publicKey));
+
+        await approve(trustPayPubkey, vaultPubkey);
+
+        const clientAfter = await program.account.reputation.fetch(reputationPda(client.publicKey));
+        const workerAfter = await program.account.reputation.fetch(reputationPda(worker.publicKey));
+        assert.equal(clientAfter.approvals.toNumber(), clientBefore.approvals.toNumber() + 1);
+        assert.equal(clientAfter.contractsCompleted.toNumber(), clientBefore.contractsCompleted.toNumber() + 1);
+        assert.equal(workerAfter.contractsCompleted.toNumber(), workerBefore.contractsCompleted.toNumber() + 1);
+        assert.equal(workerAfter.volume.sub(workerBefore.volume).toString(), "1000000");
+        assert.equal(clientAfter.volume.sub(clientBefore.volume).toString(), "1000000");
+      });
+
+      it("Records a dispute opened by the payer and lost when the recipient is paid in full", async () => {
+        const { trustPayPubkey, vaultPubkey } = await createOneTime("Disputed reputation contract");
+        const clientBefore = await program.account.reputation.fetch(reputationPda(client.publicKey));
+        const workerBefore = await program.account.reputation.fetch(reputationPda(worker.publicKey));
+
+        const disputeRecord = await nextDispute();
+        await program.methods
+          .disputeContract(0, "Deliverable does not match the brief")
+          .accountsPartial({
+            disputer: client.publicKey,
+            mint: accounts.tokenMint,
+            disputerTokenAccount: accounts.clientTokenAccount,
+            trustPay: trustPayPubkey,
+            vault: vaultPubkey,
+            globalState: accounts.globalState,
+            dispute: disputeRecord,
+          })
+          .signers([client])
+          .rpc();
+
+        const opened = await program.account.reputation.fetch(reputationPda(client.publicKey));
+        assert.equal(opened.disputesOpened.toNumber(), clientBefore.disputesOpened.toNumber() + 1);
+
+        await program.methods
+          .resolveDispute(0, 10_000, RESOLUTION_SPLIT, "Work delivered as agreed")
+          .accountsPartial({
+            resolver: resolver.publicKey,
+            payer: client.publicKey,
+            recipient: worker.publicKey,
+            mint: accounts.tokenMint,
+            trustPay: trustPayPubkey,
+            dispute: disputeRecord,
+            vault: vaultPubkey,
+            payerTokenAccount: accounts.clientTokenAccount,
+            recipientTokenAccount: accounts.workerTokenAccount,
+            treasury: accounts.treasury,
+            treasuryTokenAccount: accounts.treasuryTokenAccount,
+            globalState: accounts.globalState,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM,
+            associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+            arbitrator: null,
+          })
+          .signers([resolver])
+          .rpc();
+
+        const clientAfter = await program.account.reputation.fetch(reputationPda(client.publicKey));
+        const workerAfter = await program.account.reputation.fetch(reputationPda(worker.publicKey));
+        assert.equal(clientAfter.disputesLost.toNumber(), clientBefore.disputesLost.toNumber() + 1);
+        assert.equal(workerAfter.disputesLost.toNumber(), workerBefore.disputesLost.toNumber());
+        assert.equal(workerAfter.contractsCompleted.toNumber(), workerBefore.contractsCompleted.toNumber() + 1);
+      });
+    });
+
+    describe("Error Cases", () => {
+      it("Fails to approve with another wallet's reputation profile", async () => {
+        const { trustPayPubkey, vaultPubkey } = await createOneTime("Wrong profile contract");
+        try {
+          await approve(trustPayPubkey, vaultPubkey, reputationPda(worker.publicKey));
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.include(err.toString(), "ConstraintSeeds");
+        }
+      });
+
+      it("Fails to create a contract with yourself", async () => {
+        const seed = getRandomBigNumber();
+        const [trustPay] = PublicKey.findProgramAddressSync(
+          [Buffer.from("trust-pay"), client.publicKey.toBuffer(), seed.toArrayLike(Buffer, "le", 8)],
+          program.programId
+        );
+        try {
+          await program.methods
+            .createContract(
+              seed, ROLE_PAYER, client.publicKey, client.publicKey,
+              CONTRACT_TYPE_ONE_TIME, "Self contract", "Test terms", new BN(1_000_000),
+              [], new BN(7 * 24 * 60 * 60), null
+            )
+            .accountsPartial({
+              creator: client.publicKey, mint: accounts.tokenMint,
+              creatorTokenAccount: accounts.clientTokenAccount, trustPay,
+              vault: getAssociatedTokenAddressSync(accounts.tokenMint, trustPay, true, TOKEN_PROGRAM),
+              treasury: accounts.treasury,
+              globalState: accounts.globalState, systemProgram: anchor.web3.SystemProgram.programId,
+              tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+            })
+            .signers([client])
+            .rpc();
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.include(err.toString(), "SelfContract");
+        }
+      });
+    });
+  });
+  describe("28. REVIEWS Tests", () => {
+    let trustPayPubkey: PublicKey;
+    let vaultPubkey: PublicKey;
+    let outsider: anchor.web3.Keypair;
+
+    const reputationPda = (wallet: PublicKey) =>
+      PublicKey.findProgramAddressSync([Buffer.from("reputation"), wallet.toBuffer()], program.programId)[0];
+    const reviewPda = (trustPay: PublicKey) =>
+      PublicKey.findProgramAddressSync([Buffer.from("review"), trustPay.toBuffer()], program.programId)[0];
+    const commentHash = (comment: string) =>
+      Array.from(createHash("sha256").update(comment).digest());
+
+    const review = (
+      reviewer: anchor.web3.Keypair,
+      reviewee: PublicKey,
+      score: number,
+      comment = "Great to work with"
+    ) =>
+      program.methods
+        .submitReview(score, commentHash(comment))
+        .accountsPartial({
+          reviewer: reviewer.publicKey,
+          contractReview: reviewPda(trustPayPubkey),
+          payer: client.publicKey,
+          revieweeReputation: reputationPda(reviewee),
+        })
+        .signers([reviewer])
+        .rpc();
+
+    before("Create a one-time contract and mark it complete", async () => {
+      [outsider] = makeKeypairs(1);
+
+      const seed = getRandomBigNumber();
+      [trustPayPubkey] = PublicKey.findProgramAddressSync(
+        [Buffer.from("trust-pay"), client.publicKey.toBuffer(), seed.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      vaultPubkey = getAssociatedTokenAddressSync(accounts.tokenMint, trustPayPubkey, true, TOKEN_PROGRAM);
+
+      await program.methods
+        .createContract(
+          seed, ROLE_PAYER, client.publicKey, accounts.worker,
+          CONTRACT_TYPE_ONE_TIME, "Reviewed contract", "Test terms", new BN(1_000_000),
+          [], new BN(7 * 24 * 60 * 60), null
+        )
+        .accountsPartial({
+          creator: client.publicKey, mint: accounts.tokenMint,
+          creatorTokenAccount: accounts.clientTokenAccount, trustPay: trustPayPubkey,
+          vault: vaultPubkey, treasury: accounts.treasury,
+          globalState: accounts.globalState, systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([client])
+        .rpc();
+
+      await program.methods
+        .markMilestoneComplete(0)
+        .accountsPartial({ recipient: worker.publicKey, trustPay: trustPayPubkey })
+        .signers([worker])
+        .rpc();
+    });
+
+    describe("Error Cases", () => {
+      it("Fails to review before the contract completes", async () => {
+        try {
+          await review(client, worker.publicKey, 5);
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.include(err.toString(), "ContractNotCompleted");
+        }
+      });
+    });
+
+    describe("Happy Path", () => {
+      it("Opens the review record for both parties when the contract completes", async () => {
+        await program.methods
+          .approveMilestonePayment(0)
+          .accountsPartial({
+            payer: client.publicKey,
+            recipient: worker.publicKey,
+            mint: accounts.tokenMint,
+            trustPay: trustPayPubkey,
+            vault: vaultPubkey,
+            payerTokenAccount: accounts.clientTokenAccount,
+            recipientTokenAccount: accounts.workerTokenAccount,
+            treasury: accounts.treasury,
+            treasuryTokenAccount: accounts.treasuryTokenAccount,
+            globalState: accounts.globalState,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM,
+            associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          })
+          .signers([client])
+          .rpc();
+
+        assert.isNull(await connection.getAccountInfo(trustPayPubkey));
+        const record = await program.account.contractReview.fetch(reviewPda(trustPayPubkey));
+        assert.equal(record.payer.toString(), client.publicKey.toString());
+        assert.equal(record.recipient.toString(), worker.publicKey.toString());
+        assert.isNotNull(record.completedAt);
+        assert.isNull(record.payerReview);
+        assert.isNull(record.recipientReview);
+      });
+
+      it("Lets the payer rate the recipient and rolls the score into their reputation", async () => {
+        const workerBefore = await program.account.reputation.fetch(reputationPda(worker.publicKey));
+
+        await review(client, worker.publicKey, 5);
+
+        const record = await program.account.contractReview.fetch(reviewPda(trustPayPubkey));
+        assert.equal(record.payerReview.score, 5);
+        assert.deepEqual(record.payerReview.commentHash, commentHash("Great to work with"));
+        assert.isNull(record.recipientReview);
+
+        const workerAfter = await program.account.reputation.fetch(reputationPda(worker.publicKey));
+        assert.equal(workerAfter.reviewsReceived.toNumber(), workerBefore.reviewsReceived.toNumber() + 1);
+        assert.equal(workerAfter.totalReviewScore.sub(workerBefore.totalReviewScore).toNumber(), 5);
+        assert.equal(
+          workerAfter.averageReviewScore.toNumber(),
+          Math.floor((workerAfter.totalReviewScore.toNumber() * 100) / workerAfter.reviewsReceived.toNumber())
+        );
+      });
+    });
+
+    describe("Review Restrictions", () => {
+      it("Fails with a score outside 1-5", async () => {
+        try {
+          await review(client, worker.publicKey, 6);
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.include(err.toString(), "InvalidReviewScore");
+        }
+      });
+
+      it("Fails to review the same contract twice", async () => {
+        try {
+          await review(client, worker.publicKey, 1);
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.include(err.toString(), "AlreadyReviewed");
+        }
+      });
+
+      it("Fails to review from a wallet outside the contract", async () => {
+        try {
+          await review(outsider, worker.publicKey, 1);
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.include(err.toString(), "UnauthorizedReviewer");
+        }
+      });
+
+      it("Fails to close the review record while a review is outstanding", async () => {
+        try {
+          await program.methods
+            .closeReview()
+            .accountsPartial({ contractReview: reviewPda(trustPayPubkey), payer: client.publicKey })
+            .rpc();
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.include(err.toString(), "ReviewWindowOpen");
+        }
+      });
+    });
+
+    describe("Closing", () => {
+      it("Closes the review record to the payer once the recipient reviews too", async () => {
+        const clientBefore = await program.account.reputation.fetch(reputationPda(client.publicKey));
+        const rent = await connection.getBalance(reviewPda(trustPayPubkey));
+        const lamportsBefore = await connection.getBalance(client.publicKey);
+
+        await review(worker, client.publicKey, 4, "Clear brief, paid on time");
+
+        const clientAfter = await program.account.reputation.fetch(reputationPda(client.publicKey));
+        assert.equal(clientAfter.totalReviewScore.sub(clientBefore.totalReviewScore).toNumber(), 4);
+        assert.isNull(await connection.getAccountInfo(reviewPda(trustPayPubkey)));
+        assert.equal(await connection.getBalance(client.publicKey), lamportsBefore + rent);
+      });
+    });
+  });
+  describe("29. NATIVE_SOL Tests", () => {
+    const amount = new BN(LAMPORTS_PER_SOL / 10);
+    const wsolAccount = (owner: PublicKey) => getAssociatedTokenAddressSync(NATIVE_MINT, owner, true, TOKEN_PROGRAM);
+    const [nativeTreasury] = PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury"), NATIVE_MINT.toBuffer()],
+      program.programId
+    );
+
+    const contractPdas = (seed: BN) => {
+      const [trustPayPubkey] = PublicKey.findProgramAddressSync(
+        [Buffer.from("trust-pay"), client.publicKey.toBuffer(), seed.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      return { trustPayPubkey, vaultPubkey: wsolAccount(trustPayPubkey) };
+    };
+
+    const nativePayoutPda = (trustPay: PublicKey) =>
+      PublicKey.findProgramAddressSync([Buffer.from("native-payout"), trustPay.toBuffer()], program.programId)[0];
+
+    const create = (seed: BN, creator: anchor.web3.Keypair, role: number, other: PublicKey, total = amount) => {
+      const { trustPayPubkey, vaultPubkey } = contractPdas(seed);
+      return program.methods
+        .createContract(
+          seed, role, client.publicKey, other,
+          CONTRACT_TYPE_ONE_TIME, "Paid in SOL", "Test terms", total,
+          [], new BN(7 * 24 * 60 * 60), null
+        )
+        .accountsPartial({
+          creator: creator.publicKey, mint: NATIVE_MINT,
+          creatorTokenAccount: wsolAccount(creator.publicKey), trustPay: trustPayPubkey,
+          vault: vaultPubkey, treasury: nativeTreasury,
+          treasuryTokenAccount: wsolAccount(nativeTreasury),
+          globalState: accounts.globalState, systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([creator])
+        .rpc();
+    };
+
+    // The recipient completes the milestone and the payer disputes it, wrapping the bond from lamports
+    const disputeNative = async (trustPay: PublicKey, vault: PublicKey) => {
+      await program.methods
+        .markMilestoneComplete(0)
+        .accountsPartial({ recipient: worker.publicKey, trustPay })
+        .signers([worker])
+        .rpc();
+
+      const dispute = await nextDispute();
+      await program.methods
+        .disputeContract(0, "The delivery was incomplete")
+        .accountsPartial({
+          disputer: client.publicKey,
+          mint: NATIVE_MINT,
+          disputerTokenAccount: wsolAccount(client.publicKey),
+          trustPay,
+          vault,
+          globalState: accounts.globalState,
+          dispute,
+        })
+        .signers([client])
+        .rpc();
+      return dispute;
+    };
+
+    before("Enable the native mint", async () => {
+      await configureMint(NATIVE_MINT);
+    });
+
+    describe("Happy Path", () => {
+      it("Wraps the payer's SOL into the vault and pays the recipient in lamports on approval", async () => {
+        const seed = getRandomBigNumber();
+        const { trustPayPubkey, vaultPubkey } = contractPdas(seed);
+        const lamportsBefore = await connection.getBalance(client.publicKey);
+
+        await create(seed, client, ROLE_PAYER, worker.publicKey);
+
+        const contract = await program.account.trustPay.fetch(trustPayPubkey);
+        const deposit = amount.add(contract.fee);
+        const vault = await connection.getTokenAccountBalance(vaultPubkey);
+        assert.equal(vault.value.amount, deposit.toString());
+        // The wSOL account opened for the deposit is closed again, returning its rent
+        assert.isNull(await connection.getAccountInfo(wsolAccount(client.publicKey)));
+        assert.isAtLeast(lamportsBefore - (await connection.getBalance(client.publicKey)), deposit.toNumber());
+
+        await program.methods
+          .markMilestoneComplete(0)
+          .accountsPartial({ recipient: worker.publicKey, trustPay: trustPayPubkey })
+          .signers([worker])
+          .rpc();
+
+        const workerLamportsBefore = await connection.getBalance(worker.publicKey);
+
+        await program.methods
+          .approveMilestonePayment(0)
+          .accountsPartial({
+            payer: client.publicKey,
+            recipient: worker.publicKey,
+            mint: NATIVE_MINT,
+            trustPay: trustPayPubkey,
+            vault: vaultPubkey,
+            nativePayout: nativePayoutPda(trustPayPubkey),
+            payerTokenAccount: wsolAccount(client.publicKey),
+            recipientTokenAccount: wsolAccount(worker.publicKey),
+            treasury: nativeTreasury,
+            treasuryTokenAccount: wsolAccount(nativeTreasury),
+            globalState: accounts.globalState,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM,
+            associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          })
+          .signers([client])
+          .rpc();
+
+        // The recipient is paid in lamports and holds no wrapped SOL
+        const workerLamportsAfter = await connection.getBalance(worker.publicKey);
+        assert.equal(workerLamportsAfter - workerLamportsBefore, amount.toNumber());
+        const workerWsol = await connection.getTokenAccountBalance(wsolAccount(worker.publicKey));
+        assert.equal(workerWsol.value.amount, "0");
+        assert.isNull(await connection.getAccountInfo(nativePayoutPda(trustPayPubkey)));
+        assert.isNull(await connection.getAccountInfo(wsolAccount(client.publicKey)));
+        const treasury = await program.account.treasury.fetch(nativeTreasury);
+        assert.equal(treasury.totalFeesCollected.toString(), contract.fee.toString());
+        assert.isNull(await connection.getAccountInfo(trustPayPubkey));
+        assert.isNull(await connection.getAccountInfo(vaultPubkey));
+      });
+
+      it("Wraps the deposit when the payer accepts a SOL contract created by the recipient", async () => {
+        const seed = getRandomBigNumber();
+        const { trustPayPubkey, vaultPubkey } = contractPdas(seed);
+        await create(seed, worker, ROLE_RECIPIENT, client.publicKey);
+        const lamportsBefore = await connection.getBalance(client.publicKey);
+
+        await program.methods
+          .acceptContract(new BN(7 * 24 * 60 * 60))
+          .accountsPartial({
+            payer: client.publicKey,
+            mint: NATIVE_MINT,
+            payerTokenAccount: wsolAccount(client.publicKey),
+            trustPay: trustPayPubkey,
+            vault: vaultPubkey,
+            globalState: accounts.globalState,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM,
+            associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          })
+          .signers([client])
+          .rpc();
+
+        const contract = await program.account.trustPay.fetch(trustPayPubkey);
+        assert.equal(contract.contractStatus, CONTRACT_STATUS_IN_PROGRESS);
+        const deposit = amount.add(contract.fee);
+        const vault = await connection.getTokenAccountBalance(vaultPubkey);
+        assert.equal(vault.value.amount, deposit.toString());
+
+        // Only the deposit and the transaction fee leave the payer's wallet
+        assert.isNull(await connection.getAccountInfo(wsolAccount(client.publicKey)));
+        const spent = lamportsBefore - (await connection.getBalance(client.publicKey));
+        assert.isBelow(spent - deposit.toNumber(), 1_000_000);
+      });
+
+      it("Refunds a declined SOL contract's vault balance in lamports", async () => {
+        const seed = getRandomBigNumber();
+        const { trustPayPubkey, vaultPubkey } = contractPdas(seed);
+        await create(seed, worker, ROLE_RECIPIENT, client.publicKey);
+
+        // SOL sent straight to the vault goes back to the recipient as lamports
+        const stray = LAMPORTS_PER_SOL / 100;
+        await anchor.web3.sendAndConfirmTransaction(
+          connection,
+          new anchor.web3.Transaction().add(
+            anchor.web3.SystemProgram.transfer({ fromPubkey: client.publicKey, toPubkey: vaultPubkey, lamports: stray }),
+            createSyncNativeInstruction(vaultPubkey, TOKEN_PROGRAM)
+          ),
+          [client]
+        );
+        const workerLamportsBefore = await connection.getBalance(worker.publicKey);
+
+        await program.methods
+          .declineContract()
+          .accountsPartial({
+            payer: client.publicKey,
+            recipient: worker.publicKey,
+            mint: NATIVE_MINT,
+            trustPay: trustPayPubkey,
+            vault: vaultPubkey,
+            recipientTokenAccount: wsolAccount(worker.publicKey),
+            systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM,
+            associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          })
+          .signers([client])
+          .rpc();
+
+        // The recipient also gets the review record's rent back
+        const received = (await connection.getBalance(worker.publicKey)) - workerLamportsBefore;
+        assert.isAtLeast(received, stray);
+        assert.isBelow(received - stray, 10_000_000);
+        assert.isNull(await connection.getAccountInfo(vaultPubkey));
+      });
+
+      it("Pays a settled SOL dispute and its bond out in lamports", async () => {
+        const seed = getRandomBigNumber();
+        const { trustPayPubkey, vaultPubkey } = contractPdas(seed);
+        await create(seed, client, ROLE_PAYER, worker.publicKey);
+        await disputeNative(trustPayPubkey, vaultPubkey);
+        const bond = (await program.account.trustPay.fetch(trustPayPubkey)).milestones[0].disputeBond;
+
+        await program.methods
+          .offerSettlement(0, 5_000)
+          .accountsPartial({ party: worker.publicKey, trustPay: trustPayPubkey })
+          .signers([worker])
+          .rpc();
+
+        const clientLamportsBefore = await connection.getBalance(client.publicKey);
+        const workerLamportsBefore = await connection.getBalance(worker.publicKey);
+
+        await program.methods
+          .acceptSettlement(0, 5_000)
+          .accountsPartial({
+            party: client.publicKey,
+            payer: client.publicKey,
+            recipient: worker.publicKey,
+            mint: NATIVE_MINT,
+            trustPay: trustPayPubkey,
+            dispute: await disputeOf(trustPayPubkey),
+            vault: vaultPubkey,
+            nativePayout: nativePayoutPda(trustPayPubkey),
+            payerTokenAccount: wsolAccount(client.publicKey),
+            recipientTokenAccount: wsolAccount(worker.publicKey),
+            treasury: nativeTreasury,
+            treasuryTokenAccount: wsolAccount(nativeTreasury),
+            globalState: accounts.globalState,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM,
+            associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          })
+          .signers([client])
+          .rpc();
+
+        // The recipient's half arrives as lamports; the payer gets the other half and the bond back
+        const workerLamportsAfter = await connection.getBalance(worker.publicKey);
+        assert.equal(workerLamportsAfter - workerLamportsBefore, amount.divn(2).toNumber());
+        const clientGained = (await connection.getBalance(client.publicKey)) - clientLamportsBefore;
+        assert.isAtLeast(clientGained, amount.divn(2).add(bond).toNumber() - 10_000_000);
+        for (const owner of [client.publicKey, worker.publicKey]) {
+          const wsol = await connection.getTokenAccountBalance(wsolAccount(owner));
+          assert.equal(wsol.value.amount, "0");
+        }
+        assert.isNull(await connection.getAccountInfo(nativePayoutPda(trustPayPubkey)));
+        assert.isNull(await connection.getAccountInfo(trustPayPubkey));
+        assert.isNull(await connection.getAccountInfo(vaultPubkey));
+      });
+
+      it("Returns a withdrawn SOL dispute's bond in lamports", async () => {
+        const seed = getRandomBigNumber();
+        const { trustPayPubkey, vaultPubkey } = contractPdas(seed);
+        await create(seed, client, ROLE_PAYER, worker.publicKey);
+        const dispute = await disputeNative(trustPayPubkey, vaultPubkey);
+        const bond = (await program.account.trustPay.fetch(trustPayPubkey)).milestones[0].disputeBond;
+        const vaultBefore = await connection.getTokenAccountBalance(vaultPubkey);
+
+        // The worker fronts the token accounts so the client's balance only moves by the bond
+        for (const owner of [client.publicKey, worker.publicKey]) {
+          await createAssociatedTokenAccountIdempotent(connection, worker, NATIVE_MINT, owner, undefined, TOKEN_PROGRAM);
+        }
+        const clientLamportsBefore = await connection.getBalance(client.publicKey);
+
+        await program.methods
+          .withdrawDispute(0)
+          .accountsPartial({
+            disputer: client.publicKey,
+            payer: client.publicKey,
+            recipient: worker.publicKey,
+            mint: NATIVE_MINT,
+            trustPay: trustPayPubkey,
+            dispute,
+            vault: vaultPubkey,
+            nativePayout: nativePayoutPda(trustPayPubkey),
+            payerTokenAccount: wsolAccount(client.publicKey),
+            recipientTokenAccount: wsolAccount(worker.publicKey),
+            globalState: accounts.globalState,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM,
+            associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          })
+          .signers([client])
+          .rpc();
+
+        // Only the transaction fee is taken from the returned bond
+        const clientGained = (await connection.getBalance(client.publicKey)) - clientLamportsBefore;
+        assert.isAtLeast(clientGained, bond.toNumber() - 10_000);
+        assert.isAtMost(clientGained, bond.toNumber());
+        const clientWsol = await connection.getTokenAccountBalance(wsolAccount(client.publicKey));
+        assert.equal(clientWsol.value.amount, "0");
+        const vaultAfter = await connection.getTokenAccountBalance(vaultPubkey);
+        assert.equal(new BN(vaultBefore.value.amount).sub(new BN(vaultAfter.value.amount)).toString(), bond.toString());
+        assert.isNull(await connection.getAccountInfo(nativePayoutPda(trustPayPubkey)));
+      });
+    });
+
+    describe("Error Cases", () => {
+      it("Fails to create a SOL contract larger than the payer's balance", async () => {
+        const balance = await connection.getBalance(client.publicKey);
+        try {
+          await create(getRandomBigNumber(), client, ROLE_PAYER, worker.publicKey, new BN(balance));
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.notInclude(err.toString(), "Should have failed");
+        }
+      });
+    });
+  });
+  describe("30. TOKEN_2022_EXTENSIONS Tests", () => {
+    let plainMint: PublicKey;
+    let transferFeeMint: PublicKey;
+
+    const createToken2022Contract = async (mint: PublicKey) => {
+      const seed = getRandomBigNumber();
+      const [trustPayPubkey] = PublicKey.findProgramAddressSync(
+        [Buffer.from("trust-pay"), client.publicKey.toBuffer(), seed.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      const vaultPubkey = getAssociatedTokenAddressSync(mint, trustPayPubkey, true, TOKEN_2022_PROGRAM_ID);
+      const [treasury] = PublicKey.findProgramAddressSync([Buffer.from("treasury"), mint.toBuffer()], program.programId);
+
+      await program.methods
+        .createContract(
+          seed, ROLE_PAYER, client.publicKey, accounts.worker,
+          CONTRACT_TYPE_ONE_TIME, "Token-2022 contract", "Test terms", new BN(1_000_000),
+          [], new BN(7 * 24 * 60 * 60), null
+        )
+        .accountsPartial({
+          creator: client.publicKey, mint,
+          creatorTokenAccount: getAssociatedTokenAddressSync(mint, client.publicKey, false, TOKEN_2022_PROGRAM_ID),
+          trustPay: trustPayPubkey, vault: vaultPubkey, treasury,
+          treasuryTokenAccount: getAssociatedTokenAddressSync(mint, treasury, true, TOKEN_2022_PROGRAM_ID),
+          globalState: accounts.globalState, systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([client])
+        .rpc();
+
+      return { trustPayPubkey, vaultPubkey };
+    };
+
+    before("Create a plain Token-2022 mint and one with a transfer fee", async () => {
+      plainMint = await createMint(connection, payer, payer.publicKey, null, 9, undefined, undefined, TOKEN_2022_PROGRAM_ID);
+      const clientAccount = await createAssociatedTokenAccount(
+        connection, payer, plainMint, client.publicKey, undefined, TOKEN_2022_PROGRAM_ID
+      );
+      await mintTo(connection, payer, plainMint, clientAccount, payer.publicKey, 10_000_000, undefined, undefined, TOKEN_2022_PROGRAM_ID);
+      await configureMint(plainMint, { tokenProgram: TOKEN_2022_PROGRAM_ID });
+
+      // 1% transfer fee, which would leave the vault short of the recorded deposit
+      const [mintKeypair] = makeKeypairs(1);
+      transferFeeMint = mintKeypair.publicKey;
+      const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+      const lamports = await connection.getMinimumBalanceForRentExemption(mintLen);
+      const tx = new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.createAccount({
+          fromPubkey: payer.publicKey,
+          newAccountPubkey: transferFeeMint,
+          space: mintLen,
+          lamports,
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(
+          transferFeeMint, payer.publicKey, payer.publicKey, 100, BigInt(1_000_000), TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(transferFeeMint, 9, payer.publicKey, null, TOKEN_2022_PROGRAM_ID)
+      );
+      await anchor.web3.sendAndConfirmTransaction(connection, tx, [payer, mintKeypair]);
+      await configureMint(transferFeeMint, { tokenProgram: TOKEN_2022_PROGRAM_ID });
+    });
+
+    describe("Happy Path", () => {
+      it("Escrows a Token-2022 mint without unsupported extensions", async () => {
+        const { trustPayPubkey, vaultPubkey } = await createToken2022Contract(plainMint);
+
+        const contract = await program.account.trustPay.fetch(trustPayPubkey);
+        const vault = await connection.getTokenAccountBalance(vaultPubkey);
+        assert.equal(vault.value.amount, new BN(1_000_000).add(contract.fee).toString());
+      });
+    });
+
+    describe("Error Cases", () => {
+      it("Fails to create a contract on a mint with the transfer-fee extension", async () => {
+        try {
+          await createToken2022Contract(transferFeeMint);
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.include(err.toString(), "UnsupportedMintExtension");
+        }
+      });
+    });
+  });
+  describe("31. TRANSFER_HOOK Tests", () => {
+    const hookProgram = anchor.workspace.TransferHookExample as Program<TransferHookExample>;
+    let hookMint: PublicKey;
+    let extraAccountMetas: PublicKey;
+    let counter: PublicKey;
+    let trustPayPubkey: PublicKey;
+    let vaultPubkey: PublicKey;
+
+    const tokenAccount = (owner: PublicKey) => getAssociatedTokenAddressSync(hookMint, owner, true, TOKEN_2022_PROGRAM_ID);
+    const treasuryOf = () =>
+      PublicKey.findProgramAddressSync([Buffer.from("treasury"), hookMint.toBuffer()], program.programId)[0];
+
+    // The hook's validation account, the accounts it lists and the hook program itself
+    const hookAccounts = () => [
+      { pubkey: extraAccountMetas, isSigner: false, isWritable: false },
+      { pubkey: counter, isSigner: false, isWritable: true },
+      { pubkey: hookProgram.programId, isSigner: false, isWritable: false },
+    ];
+
+    const transfers = async () => (await hookProgram.account.counter.fetch(counter)).transfers.toNumber();
+
+    const create = (seed: BN, remainingAccounts = hookAccounts()) => {
+      [trustPayPubkey] = PublicKey.findProgramAddressSync(
+        [Buffer.from("trust-pay"), client.publicKey.toBuffer(), seed.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      vaultPubkey = tokenAccount(trustPayPubkey);
+
+      return program.methods
+        .createContract(
+          seed, ROLE_PAYER, client.publicKey, accounts.worker,
+          CONTRACT_TYPE_ONE_TIME, "Hooked token contract", "Test terms", new BN(1_000_000),
+          [], new BN(7 * 24 * 60 * 60), null
+        )
+        .accountsPartial({
+          creator: client.publicKey, mint: hookMint,
+          creatorTokenAccount: tokenAccount(client.publicKey), trustPay: trustPayPubkey,
+          vault: vaultPubkey, treasury: treasuryOf(), treasuryTokenAccount: tokenAccount(treasuryOf()),
+          globalState: accounts.globalState, systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .remainingAccounts(remainingAccounts)
+        .signers([client])
+        .rpc();
+    };
+
+    before("Create a Token-2022 mint with the example transfer hook", async () => {
+      const [mintKeypair] = makeKeypairs(1);
+      hookMint = mintKeypair.publicKey;
+      const mintLen = getMintLen([ExtensionType.TransferHook]);
+      const lamports = await connection.getMinimumBalanceForRentExemption(mintLen);
+      const tx = new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.createAccount({
+          fromPubkey: payer.publicKey,
+          newAccountPubkey: hookMint,
+          space: mintLen,
+          lamports,
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferHookInstruction(hookMint, payer.publicKey, hookProgram.programId, TOKEN_2022_PROGRAM_ID),
+        createInitializeMintInstruction(hookMint, 9, payer.publicKey, null, TOKEN_2022_PROGRAM_ID)
+      );
+      await anchor.web3.sendAndConfirmTransaction(connection, tx, [payer, mintKeypair]);
+
+      [extraAccountMetas] = PublicKey.findProgramAddressSync(
+        [Buffer.from("extra-account-metas"), hookMint.toBuffer()],
+        hookProgram.programId
+      );
+      [counter] = PublicKey.findProgramAddressSync([Buffer.from("counter"), hookMint.toBuffer()], hookProgram.programId);
+      await hookProgram.methods
+        .initializeExtraAccountMetaList()
+        .accountsPartial({ payer: payer.publicKey, extraAccountMetaList: extraAccountMetas, mint: hookMint, counter })
+        .rpc();
+      await configureMint(hookMint, { tokenProgram: TOKEN_2022_PROGRAM_ID });
+
+      const clientAccount = await createAssociatedTokenAccount(
+        connection, payer, hookMint, client.publicKey, undefined, TOKEN_2022_PROGRAM_ID
+      );
+      await mintTo(connection, payer, hookMint, clientAccount, payer.publicKey, 10_000_000, undefined, undefined, TOKEN_2022_PROGRAM_ID);
+    });
+
+    describe("Happy Path", () => {
+      it("Runs the hook on the deposit into the vault", async () => {
+        const before = await transfers();
+        await create(getRandomBigNumber());
+
+        assert.equal(await transfers(), before + 1);
+        const contract = await program.account.trustPay.fetch(trustPayPubkey);
+        const vault = await connection.getTokenAccountBalance(vaultPubkey);
+        assert.equal(vault.value.amount, new BN(1_000_000).add(contract.fee).toString());
+      });
+
+      it("Runs the hook on every payout when the contract completes", async () => {
+        await program.methods
+          .markMilestoneComplete(0)
+          .accountsPartial({ recipient: worker.publicKey, trustPay: trustPayPubkey })
+          .signers([worker])
+          .rpc();
+
+        const before = await transfers();
+        await program.methods
+          .approveMilestonePayment(0)
+          .accountsPartial({
+            payer: client.publicKey,
+            recipient: worker.publicKey,
+            mint: hookMint,
+            trustPay: trustPayPubkey,
+            vault: vaultPubkey,
+            payerTokenAccount: tokenAccount(client.publicKey),
+            recipientTokenAccount: tokenAccount(worker.publicKey),
+            treasury: treasuryOf(),
+            treasuryTokenAccount: tokenAccount(treasuryOf()),
+            globalState: accounts.globalState,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+            associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          })
+          .remainingAccounts(hookAccounts())
+          .signers([client])
+          .rpc();
+
+        // Milestone to the recipient and fee to the treasury
+        assert.equal(await transfers(), before + 2);
+        const workerBalance = await connection.getTokenAccountBalance(tokenAccount(worker.publicKey));
+        assert.equal(workerBalance.value.amount, "1000000");
+        assert.isNull(await connection.getAccountInfo(trustPayPubkey));
+      });
+    });
+
+    describe("Error Cases", () => {
+      it("Fails to deposit when the hook's accounts are not forwarded", async () => {
+        try {
+          await create(getRandomBigNumber(), []);
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.notInclude(err.toString(), "Should have failed");
+        }
+      });
+    });
+  });
+  describe("32. MINT_CONFIG Tests", () => {
+    let configuredMint: PublicKey;
+    let unconfiguredMint: PublicKey;
+
+    const mintConfigOf = (mint: PublicKey) =>
+      PublicKey.findProgramAddressSync([Buffer.from("mint-config"), mint.toBuffer()], program.programId)[0];
+
+    const createOn = async (mint: PublicKey, amount: BN) => {
+      const seed = getRandomBigNumber();
+      const [trustPayPubkey] = PublicKey.findProgramAddressSync(
+        [Buffer.from("trust-pay"), client.publicKey.toBuffer(), seed.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      const [treasury] = PublicKey.findProgramAddressSync([Buffer.from("treasury"), mint.toBuffer()], program.programId);
+
+      await program.methods
+        .createContract(
+          seed, ROLE_PAYER, client.publicKey, accounts.worker,
+          CONTRACT_TYPE_ONE_TIME, "Configured mint", "Test terms", amount,
+          [], new BN(7 * 24 * 60 * 60), null
+        )
+        .accountsPartial({
+          creator: client.publicKey, mint,
+          creatorTokenAccount: getAssociatedTokenAddressSync(mint, client.publicKey),
+          trustPay: trustPayPubkey, vault: getAssociatedTokenAddressSync(mint, trustPayPubkey, true),
+          treasury, treasuryTokenAccount: getAssociatedTokenAddressSync(mint, treasury, true),
+          globalState: accounts.globalState, systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([client])
+        .rpc();
+
+      return trustPayPubkey;
+    };
+
+    const fundedMint = async () => {
+      const mint = await createMint(connection, payer, payer.publicKey, null, 6, undefined, undefined, TOKEN_PROGRAM);
+      const clientAccount = await createAssociatedTokenAccount(connection, payer, mint, client.publicKey);
+      await mintTo(connection, payer, mint, clientAccount, payer.publicKey, 100_000_000);
+      return mint;
+    };
+
+    before("Create a mint to configure and one left unconfigured", async () => {
+      configuredMint = await fundedMint();
+      unconfiguredMint = await fundedMint();
+    });
+
+    describe("Happy Path", () => {
+      it("Enables a mint with size limits and a fee override", async () => {
+        await configureMint(configuredMint, {
+          minAmount: new BN(10_000), maxAmount: new BN(10_000_000), feeOverride: 100,
+        });
+
+        const config = await program.account.mintConfig.fetch(mintConfigOf(configuredMint));
+        const [treasury] = PublicKey.findProgramAddressSync(
+          [Buffer.from("treasury"), configuredMint.toBuffer()],
+          program.programId
+        );
+        assert.isTrue(config.enabled);
+        assert.equal(config.decimals, 6);
+        assert.equal(config.minContractAmount.toString(), "10000");
+        assert.equal(config.maxContractAmount.toString(), "10000000");
+        assert.equal(config.feeOverride, 100);
+        assert.isTrue(config.treasury.equals(treasury));
+      });
+
+      it("Charges the mint's fee override on new contracts", async () => {
+        const trustPayPubkey = await createOn(configuredMint, new BN(1_000_000));
+
+        const contract = await program.account.trustPay.fetch(trustPayPubkey);
+        assert.equal(contract.feePercentage, 100);
+        assert.equal(contract.fee.toString(), "10000");
+      });
+    });
+
+    describe("Error Cases", () => {
+      it("Fails to create a contract on a mint that was never configured", async () => {
+        try {
+          await createOn(unconfiguredMint, new BN(1_000_000));
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.include(err.toString(), "AccountNotInitialized");
+        }
+      });
+
+      it("Fails to create contracts outside the mint's size limits", async () => {
+        for (const amount of [new BN(9_999), new BN(10_000_001)]) {
+          try {
+            await createOn(configuredMint, amount);
+            assert.fail("Should have failed");
+          } catch (err) {
+            assert.include(err.toString(), "ContractAmountOutOfRange");
+          }
+        }
+      });
+
+      it("Fails to create a contract once the mint is disabled", async () => {
+        await configureMint(configuredMint, { enabled: false });
+        try {
+          await createOn(configuredMint, new BN(1_000_000));
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.include(err.toString(), "MintNotEnabled");
+        }
+      });
+
+      it("Fails with a minimum above the maximum or a fee override above 10000 bps", async () => {
+        for (const options of [
+          { minAmount: new BN(2_000), maxAmount: new BN(1_000) },
+          { feeOverride: 10_001 },
+        ]) {
+          try {
+            await configureMint(configuredMint, options);
+            assert.fail("Should have failed");
+          } catch (err) {
+            assert.include(err.toString(), "InvalidMintConfig");
+          }
+        }
+      });
+
+      it("Fails when non-authority configures a mint", async () => {
+        try {
+          await configureMint(configuredMint, { signer: worker });
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.include(err.toString(), "ConstraintHasOne");
+        }
+      });
+    });
+  });
+  describe("33. MINT_STATS Tests", () => {
+    let statsMint: PublicKey;
+    let trustPayPubkey: PublicKey;
+    let vaultPubkey: PublicKey;
+
+    const mintStatsOf = (mint: PublicKey) =>
+      PublicKey.findProgramAddressSync([Buffer.from("mint-stats"), mint.toBuffer()], program.programId)[0];
+    const statsOf = (mint: PublicKey) => program.account.mintStats.fetch(mintStatsOf(mint));
+    const tokenAccount = (owner: PublicKey) => getAssociatedTokenAddressSync(statsMint, owner, true);
+    const treasuryOf = () =>
+      PublicKey.findProgramAddressSync([Buffer.from("treasury"), statsMint.toBuffer()], program.programId)[0];
+
+    const create = (mintStats = mintStatsOf(statsMint)) => {
+      const seed = getRandomBigNumber();
+      [trustPayPubkey] = PublicKey.findProgramAddressSync(
+        [Buffer.from("trust-pay"), client.publicKey.toBuffer(), seed.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      vaultPubkey = tokenAccount(trustPayPubkey);
+
+      return program.methods
+        .createContract(
+          seed, ROLE_PAYER, client.publicKey, accounts.worker,
+          CONTRACT_TYPE_ONE_TIME, "Stats contract", "Test terms", new BN(1_000_000),
+          [], new BN(7 * 24 * 60 * 60), null
+        )
+        .accountsPartial({
+          creator: client.publicKey, mint: statsMint, mintStats,
+          creatorTokenAccount: tokenAccount(client.publicKey), trustPay: trustPayPubkey,
+          vault: vaultPubkey, treasury: treasuryOf(), treasuryTokenAccount: tokenAccount(treasuryOf()),
+          globalState: accounts.globalState, systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM, associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([client])
+        .rpc();
+    };
+
+    const markComplete = () =>
+      program.methods
+        .markMilestoneComplete(0)
+        .accountsPartial({ recipient: worker.publicKey, trustPay: trustPayPubkey })
+        .signers([worker])
+        .rpc();
+
+    before("Create and enable a mint with its own statistics", async () => {
+      statsMint = await createMint(connection, payer, payer.publicKey, null, 6, undefined, undefined, TOKEN_PROGRAM);
+      const clientAccount = await createAssociatedTokenAccount(connection, payer, statsMint, client.publicKey);
+      await mintTo(connection, payer, statsMint, clientAccount, payer.publicKey, 100_000_000);
+      await configureMint(statsMint);
+    });
+
+    describe("Happy Path", () => {
+      it("Locks the deposit on creation and releases it on payout", async () => {
+        const defaultMintBefore = await statsOf(accounts.tokenMint);
+
+        await create();
+        const contract = await program.account.trustPay.fetch(trustPayPubkey);
+        const deposit = new BN(1_000_000).add(contract.fee);
+
+        let stats = await statsOf(statsMint);
+        assert.equal(stats.contractsCreated.toNumber(), 1);
+        assert.equal(stats.openEscrows.toNumber(), 1);
+        assert.equal(stats.totalValueLocked.toString(), deposit.toString());
+        assert.equal(stats.highWatermarkTvl.toString(), deposit.toString());
+
+        await markComplete();
+        await program.methods
+          .approveMilestonePayment(0)
+          .accountsPartial({
+            payer: client.publicKey,
+            recipient: worker.publicKey,
+            mint: statsMint,
+            trustPay: trustPayPubkey,
+            vault: vaultPubkey,
+            payerTokenAccount: tokenAccount(client.publicKey),
+            recipientTokenAccount: tokenAccount(worker.publicKey),
+            treasury: treasuryOf(),
+            treasuryTokenAccount: tokenAccount(treasuryOf()),
+            globalState: accounts.globalState,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM,
+            associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          })
+          .signers([client])
+          .rpc();
+
+        stats = await statsOf(statsMint);
+        assert.equal(stats.totalValueLocked.toNumber(), 0);
+        assert.equal(stats.highWatermarkTvl.toString(), deposit.toString());
+        assert.equal(stats.openEscrows.toNumber(), 0);
+        assert.equal(stats.contractsClosed.toNumber(), 1);
+        assert.equal(stats.totalVolume.toString(), "1000000");
+        assert.equal(stats.totalFeesCollected.toString(), contract.fee.toString());
+
+        // Another mint's statistics are untouched
+        const defaultMintAfter = await statsOf(accounts.tokenMint);
+        assert.equal(defaultMintAfter.totalValueLocked.toString(), defaultMintBefore.totalValueLocked.toString());
+        assert.equal(defaultMintAfter.totalVolume.toString(), defaultMintBefore.totalVolume.toString());
+      });
+
+      it("Adds a dispute bond to TVL and removes it when the dispute is withdrawn", async () => {
+        await create();
+        await markComplete();
+        const before = await statsOf(statsMint);
+
+        const dispute = await nextDispute();
+        await program.methods
+          .disputeContract(0, "Delivery was missing the final report")
+          .accountsPartial({
+            disputer: client.publicKey,
+            mint: statsMint,
+            disputerTokenAccount: tokenAccount(client.publicKey),
+            trustPay: trustPayPubkey,
+            vault: vaultPubkey,
+            globalState: accounts.globalState,
+            dispute,
+          })
+          .signers([client])
+          .rpc();
+
+        const bond = (await program.account.trustPay.fetch(trustPayPubkey)).milestones[0].disputeBond;
+        let stats = await statsOf(statsMint);
+        assert.equal(stats.disputesOpened.toNumber(), before.disputesOpened.toNumber() + 1);
+        assert.equal(stats.totalValueLocked.toString(), before.totalValueLocked.add(bond).toString());
+
+        await program.methods
+          .withdrawDispute(0)
+          .accountsPartial({
+            disputer: client.publicKey,
+            payer: client.publicKey,
+            recipient: worker.publicKey,
+            mint: statsMint,
+            trustPay: trustPayPubkey,
+            dispute,
+            vault: vaultPubkey,
+            payerTokenAccount: tokenAccount(client.publicKey),
+            recipientTokenAccount: tokenAccount(worker.publicKey),
+            globalState: accounts.globalState,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM,
+            associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          })
+          .signers([client])
+          .rpc();
+
+        stats = await statsOf(statsMint);
+        assert.equal(stats.disputesClosed.toNumber(), before.disputesClosed.toNumber() + 1);
+        assert.equal(stats.totalValueLocked.toString(), before.totalValueLocked.toString());
+      });
+
+      it("Leaves tokens sent straight to the vault out of TVL when the contract closes", async () => {
+        const before = await statsOf(statsMint);
+        await create();
+        await mintTo(connection, payer, statsMint, vaultPubkey, payer.publicKey, 500_000);
+        await markComplete();
+
+        const clientBefore = await connection.getTokenAccountBalance(tokenAccount(client.publicKey));
+        await program.methods
+          .approveMilestonePayment(0)
+          .accountsPartial({
+            payer: client.publicKey,
+            recipient: worker.publicKey,
+            mint: statsMint,
+            trustPay: trustPayPubkey,
+            vault: vaultPubkey,
+            payerTokenAccount: tokenAccount(client.publicKey),
+            recipientTokenAccount: tokenAccount(worker.publicKey),
+            treasury: treasuryOf(),
+            treasuryTokenAccount: tokenAccount(treasuryOf()),
+            globalState: accounts.globalState,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM,
+            associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          })
+          .signers([client])
+          .rpc();
+
+        // The stray tokens go back to the payer without pulling TVL below the other contracts
+        const clientAfter = await connection.getTokenAccountBalance(tokenAccount(client.publicKey));
+        assert.equal(
+          new BN(clientAfter.value.amount).sub(new BN(clientBefore.value.amount)).toString(),
+          "500000"
+        );
+        const stats = await statsOf(statsMint);
+        assert.equal(stats.totalValueLocked.toString(), before.totalValueLocked.toString());
+      });
+    });
+
+    describe("Error Cases", () => {
+      it("Fails to create a contract with another mint's statistics account", async () => {
+        try {
+          await create(mintStatsOf(accounts.tokenMint));
+          assert.fail("Should have failed");
+        } catch (err) {
+          assert.include(err.toString(), "ConstraintSeeds");
+        }
+      });
+    });
+  });
+});
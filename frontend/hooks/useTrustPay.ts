@@ -105,6 +105,9 @@ export enum Role {
   RECIPIENT = 1,
 }
 
+// On-chain resolution that splits the disputed milestone and keeps the contract going
+const RESOLUTION_SPLIT = 0;
+
 // Resolution types
 export enum DisputeResolution {
   FAVOR_PAYER = 0,
@@ -1124,6 +1127,14 @@ const getBatchedMintInfo = useCallback(
       }
 
       const { trustPay, milestoneIndex, resolution, resolutionReason } = params;
+
+      // The program takes the recipient's share in basis points
+      const recipientShareBps =
+        resolution === DisputeResolution.FAVOR_PAYER
+          ? 0
+          : resolution === DisputeResolution.FAVOR_RECIPIENT
+          ? 10_000
+          : 5_000;
       const trustPayAccount = await getTrustPayInfo(trustPay);
 
       const isToken2022Result = await isToken2022(trustPayAccount.mint);
@@ -1165,7 +1176,7 @@ const getBatchedMintInfo = useCallback(
       );
 
       const signature = await program.methods
-        .resolveDispute(milestoneIndex, resolution, resolutionReason)
+        .resolveDispute(milestoneIndex, recipientShareBps, RESOLUTION_SPLIT, resolutionReason)
         .accountsPartial({
           resolver: publicKey,
           payer: trustPayAccount.payer,